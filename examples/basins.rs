@@ -0,0 +1,40 @@
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    basins::BasinAnalysis,
+    symbolic_domain::BinaryIntegerDomain,
+    update_fn::SmartSystemUpdateFn,
+    variable_ordering::{VariableOrder, VariableOrdering},
+};
+
+/// Prints the attractors of the model together with the sizes of their weak and strong
+/// basins, e.g. `basins model.sbml`.
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let sbml_path = args[1].clone();
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("Cannot open SBML file."),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions")
+        .expect("Cannot find transitions in the SBML file.");
+    let update_fns = bio::load_all_update_fns(&mut xml).expect("Cannot parse the SBML file.");
+    // the regulatory ordering keeps the reachability (and thus the attractor search) tractable
+    // on larger models, unlike the alphabetical one
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns_with_ordering(
+        update_fns,
+        &VariableOrdering::new(VariableOrder::RegulatoryDfs, true),
+    );
+
+    let analysis = BasinAnalysis::new(&system);
+    for (idx, basin) in analysis.basins().iter().enumerate() {
+        println!(
+            "Attractor {} ({} states): weak basin {} ({:.2}%), strong basin {} ({:.2}%)",
+            idx,
+            system.count_states_exact(&basin.attractor),
+            basin.weak_size,
+            basin.weak_ratio * 100.0,
+            basin.strong_size,
+            basin.strong_ratio * 100.0
+        );
+    }
+}
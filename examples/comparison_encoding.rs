@@ -0,0 +1,31 @@
+use biodivine_lib_logical_models::{
+    benchmarks::comparison_encoding::comparison_benchmark,
+    prelude::symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, UnaryIntegerDomain,
+    },
+};
+
+/// Usage: `comparison_encoding <representation> <sbml_path> [repetitions]`
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let representation = args[1].clone();
+    let sbml_path = args[2].clone();
+    let repetitions = args
+        .get(3)
+        .map(|it| it.parse().expect("repetitions should be a number"))
+        .unwrap_or(100);
+
+    match representation.as_str() {
+        "unary" => comparison_benchmark::<UnaryIntegerDomain>(sbml_path.as_str(), repetitions),
+        "binary" => {
+            comparison_benchmark::<BinaryIntegerDomain<u8>>(sbml_path.as_str(), repetitions)
+        }
+        "petri_net" => {
+            comparison_benchmark::<PetriNetIntegerDomain>(sbml_path.as_str(), repetitions)
+        }
+        "gray" | "grey" => {
+            comparison_benchmark::<GrayCodeIntegerDomain<u8>>(sbml_path.as_str(), repetitions)
+        }
+        _ => panic!("Unknown representation: {}.", representation),
+    }
+}
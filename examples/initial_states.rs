@@ -0,0 +1,40 @@
+use biodivine_lib_logical_models::{
+    benchmarks::rewritten_reachability::reach_fwd,
+    prelude::{
+        self as bio, proposition::parse_partial_assignment, symbolic_domain::BinaryIntegerDomain,
+        update_fn::SmartSystemUpdateFn,
+    },
+};
+
+/// Computes the states reachable from the initial states of the model, given by the
+/// `initialLevel`-s of the SBML file and further restricted by the optional partial
+/// specification, e.g. `initial_states model.sbml "{p: 1, q: >=2}"`.
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let sbml_path = args[1].clone();
+    let constraints = match args.get(2) {
+        Some(specification) => parse_partial_assignment::<u8>(specification)
+            .unwrap_or_else(|error| panic!("Invalid specification: {}.", error)),
+        None => Vec::new(),
+    };
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("Cannot open SBML file."),
+    ));
+    bio::find_start_of(&mut xml, "listOfQualitativeSpecies")
+        .expect("Cannot find species in the SBML file.");
+    let levels = bio::load_initial_levels(&mut xml).expect("Loading initial levels failed.");
+    bio::find_start_of(&mut xml, "listOfTransitions")
+        .expect("Cannot find transitions in the SBML file.");
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::try_from_xml(&mut xml)
+        .expect("Loading system fn update failed.");
+
+    let initial = system
+        .initial_states_from_levels(&levels)
+        .and_then(|initial| Ok(initial.and(&system.initial_states(&constraints)?)))
+        .expect("Invalid initial states.");
+    println!("Initial states: {}", system.count_states(&initial));
+
+    let reachable = reach_fwd(&system, &initial, &system.unit_vertex_set());
+    println!("Reachable states: {}", system.count_states(&reachable));
+}
@@ -0,0 +1,46 @@
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    reduction::{reduce, ReductionOptions},
+    symbolic_domain::BinaryIntegerDomain,
+    update_fn::SmartSystemUpdateFn,
+};
+
+/// Loads the given SBML model, reduces it and prints the reduction report together with the
+/// size of the state space before and after the reduction.
+///
+/// Pass `--eliminate` as the second argument to also eliminate intermediate variables.
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let sbml_path = args[1].clone();
+    let eliminate_intermediates = args.get(2).is_some_and(|it| it == "--eliminate");
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let model = bio::load_all_update_fns::<_, _, u8>(&mut xml).expect("should be able to parse");
+
+    let original_count = model.len();
+    let original =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(model.clone());
+
+    let now = std::time::Instant::now();
+    let options = ReductionOptions {
+        eliminate_intermediates,
+        ..Default::default()
+    };
+    let (reduced_model, report) = reduce(model, options);
+    println!("{}", report);
+    println!("Reduction time: {}ms", now.elapsed().as_millis());
+
+    let reduced_count = reduced_model.len();
+    let reduced =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(reduced_model);
+
+    println!("Variables: {} -> {}", original_count, reduced_count);
+    println!(
+        "States: {} -> {}",
+        original.count_states(&original.unit_vertex_set()),
+        reduced.count_states(&reduced.unit_vertex_set()),
+    );
+}
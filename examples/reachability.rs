@@ -4,7 +4,8 @@ use biodivine_lib_logical_models::benchmarks::reachability::reachability_benchma
 // };
 
 use biodivine_lib_logical_models::prelude::symbolic_domain::{
-    BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, UnaryIntegerDomain,
+    BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+    UnaryIntegerDomain,
 };
 
 fn main() {
@@ -19,6 +20,7 @@ fn main() {
         "binary" => reachability_benchmark::<BinaryIntegerDomain<u8>>(sbml_path.as_str()),
         "petri_net" => reachability_benchmark::<PetriNetIntegerDomain>(sbml_path.as_str()),
         "gray" | "grey" => reachability_benchmark::<GrayCodeIntegerDomain<u8>>(sbml_path.as_str()),
+        "mixed" => reachability_benchmark::<MixedIntegerDomain>(sbml_path.as_str()),
         _ => panic!("Unknown representation: {}.", representation),
     }
 
@@ -0,0 +1,71 @@
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    explicit_system::ExplicitSystem,
+    simulation::{simulate, SimulationOptions},
+    state::State,
+    symbolic_domain::MixedIntegerDomain,
+    update_fn::SmartSystemUpdateFn,
+};
+
+/// Usage: `simulation <sbml_path> [walks] [steps] [seed] [explicit|symbolic]`
+///
+/// Performs random asynchronous walks from the state where every variable is zero and prints
+/// the states in which the walks ended, which estimates the reachability of the attractors.
+/// The update functions are evaluated explicitly, unless `symbolic` is given.
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let sbml_path = args[1].clone();
+    let defaults = SimulationOptions::default();
+    let parse = |idx: usize, default: usize| {
+        args.get(idx)
+            .map(|it| it.parse().expect("arguments should be numbers"))
+            .unwrap_or(default)
+    };
+    let options = SimulationOptions {
+        walks: parse(2, defaults.walks),
+        steps: parse(3, defaults.steps),
+        seed: parse(4, defaults.seed as usize) as u64,
+        ..defaults
+    };
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let update_fns = bio::load_all_update_fns(&mut xml).expect("should be able to parse");
+
+    let start = std::time::Instant::now();
+    let result = match args.get(5).map(|it| it.as_str()) {
+        None | Some("explicit") => {
+            let system = ExplicitSystem::new(update_fns);
+            let initial = State::new(vec![0; system.get_system_variables().len()]);
+            simulate(&system, &initial, &options)
+        }
+        Some("symbolic") => {
+            let system = SmartSystemUpdateFn::<MixedIntegerDomain, u8>::from_update_fns(update_fns);
+            let initial = State::new(vec![0; system.get_system_variables().len()]);
+            simulate(&system, &initial, &options)
+        }
+        Some(other) => panic!("Unknown evaluation: {}.", other),
+    }
+    .unwrap_or_else(|error| panic!("{}", error));
+    println!(
+        "{} walks of at most {} steps in {}ms, {} reached a fixed point",
+        options.walks,
+        options.steps,
+        start.elapsed().as_millis(),
+        result.fixed_point_walks
+    );
+
+    for (state, frequency) in result.final_state_frequencies() {
+        let label = result
+            .variables
+            .iter()
+            .zip(state.values())
+            .filter(|(_, value)| **value != 0)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        println!("{:.3} {{{}}}", frequency, label);
+    }
+}
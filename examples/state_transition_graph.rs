@@ -0,0 +1,46 @@
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    state_transition_graph::{StateTransitionGraph, StgOptions, UpdateScheme},
+    symbolic_domain::MixedIntegerDomain,
+    update_fn::SmartSystemUpdateFn,
+};
+
+/// Usage: `state_transition_graph <sbml_path> <dot|graphml|json> [async|sync] [state_limit]`
+///
+/// Prints the state-transition graph of a (small) model in the given format.
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let sbml_path = args[1].clone();
+    let format = args[2].clone();
+    let scheme = match args.get(3).map(|it| it.as_str()) {
+        None | Some("async") => UpdateScheme::Asynchronous,
+        Some("sync") => UpdateScheme::Synchronous,
+        Some(other) => panic!("Unknown update scheme: {}.", other),
+    };
+    let state_limit = args
+        .get(4)
+        .map(|it| it.parse().expect("state limit should be a number"))
+        .unwrap_or(StgOptions::default().state_limit);
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let system = SmartSystemUpdateFn::<MixedIntegerDomain, u8>::try_from_xml(&mut xml)
+        .expect("should be able to parse");
+
+    let options = StgOptions {
+        scheme,
+        state_limit,
+        ..StgOptions::default()
+    };
+    let graph = StateTransitionGraph::try_from_system(&system, &options)
+        .unwrap_or_else(|error| panic!("{}", error));
+
+    match format.as_str() {
+        "dot" => print!("{}", graph.to_dot()),
+        "graphml" => print!("{}", graph.to_graphml()),
+        "json" => println!("{}", graph.to_json()),
+        _ => panic!("Unknown format: {}.", format),
+    }
+}
@@ -0,0 +1,38 @@
+use biodivine_lib_logical_models::{
+    benchmarks::system_operations::system_operations_benchmark,
+    prelude::symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+        UnaryIntegerDomain,
+    },
+};
+
+/// Usage: `system_operations <representation> <sbml_path> [repetitions]`
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let representation = args[1].clone();
+    let sbml_path = args[2].clone();
+    let repetitions = args
+        .get(3)
+        .map(|it| it.parse().expect("repetitions should be a number"))
+        .unwrap_or(10);
+
+    match representation.as_str() {
+        "unary" => {
+            system_operations_benchmark::<UnaryIntegerDomain>(sbml_path.as_str(), repetitions)
+        }
+        "binary" => {
+            system_operations_benchmark::<BinaryIntegerDomain<u8>>(sbml_path.as_str(), repetitions)
+        }
+        "petri_net" => {
+            system_operations_benchmark::<PetriNetIntegerDomain>(sbml_path.as_str(), repetitions)
+        }
+        "gray" | "grey" => system_operations_benchmark::<GrayCodeIntegerDomain<u8>>(
+            sbml_path.as_str(),
+            repetitions,
+        ),
+        "mixed" => {
+            system_operations_benchmark::<MixedIntegerDomain>(sbml_path.as_str(), repetitions)
+        }
+        _ => panic!("Unknown representation: {}.", representation),
+    }
+}
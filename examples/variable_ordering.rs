@@ -0,0 +1,25 @@
+use biodivine_lib_logical_models::{
+    benchmarks::variable_ordering::variable_ordering_benchmark,
+    prelude::symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+        UnaryIntegerDomain,
+    },
+};
+
+/// Usage: `variable_ordering <representation> <sbml_path>`
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let representation = args[1].clone();
+    let sbml_path = args[2].clone();
+
+    match representation.as_str() {
+        "unary" => variable_ordering_benchmark::<UnaryIntegerDomain>(sbml_path.as_str()),
+        "binary" => variable_ordering_benchmark::<BinaryIntegerDomain<u8>>(sbml_path.as_str()),
+        "petri_net" => variable_ordering_benchmark::<PetriNetIntegerDomain>(sbml_path.as_str()),
+        "gray" | "grey" => {
+            variable_ordering_benchmark::<GrayCodeIntegerDomain<u8>>(sbml_path.as_str())
+        }
+        "mixed" => variable_ordering_benchmark::<MixedIntegerDomain>(sbml_path.as_str()),
+        _ => panic!("Unknown representation: {}.", representation),
+    }
+}
@@ -0,0 +1,111 @@
+use biodivine_lib_bdd::{Bdd, BddVariableSet, BddVariableSetBuilder};
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    prelude::find_start_of, symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    update::update_fn::SmartSystemUpdateFn,
+};
+
+/// The original implementation of `encode_lt`, which enumerates all the smaller values.
+pub fn naive_encode_lt<DO: SymbolicDomainOrd<u8>>(
+    domain: &DO,
+    bdd_variable_set: &BddVariableSet,
+    exclusive_upper_bound: &u8,
+) -> Bdd {
+    (0..*exclusive_upper_bound).fold(domain.empty_collection(bdd_variable_set), |acc, val| {
+        acc.or(&domain.encode_one(bdd_variable_set, &val))
+    })
+}
+
+/// The original implementation of `unit_collection`, which enumerates all the values.
+pub fn naive_unit_collection<DO: SymbolicDomainOrd<u8>>(
+    domain: &DO,
+    bdd_variable_set: &BddVariableSet,
+) -> Bdd {
+    domain
+        .get_all_possible_values()
+        .iter()
+        .fold(bdd_variable_set.mk_false(), |acc, val| {
+            acc.or(&domain.encode_one(bdd_variable_set, val))
+        })
+}
+
+/// Compares the time needed to encode all `<` comparisons and unit collections of the
+/// domains of the given model using the current implementation of `DO` and using
+/// [naive_encode_lt] and [naive_unit_collection]. Both variants are run `repetitions` times,
+/// each time on freshly created domains (so that no cached values are reused).
+pub fn comparison_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str, repetitions: u32) {
+    let now = std::time::Instant::now();
+    let system = {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open(sbml_path).expect("should be able to open file"),
+        ));
+
+        find_start_of(&mut xml, "listOfTransitions")
+            .expect("Cannot find transitions in the SBML file.");
+
+        SmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut xml)
+            .expect("Loading system fn update failed.")
+    };
+    println!("System construction: {}ms", now.elapsed().as_millis());
+
+    let max_values = system
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .map(|(var_name, domain)| {
+            let max_value = domain
+                .get_all_possible_values()
+                .into_iter()
+                .max_by(DO::cmp)
+                .expect("domain is not empty");
+            (var_name.to_string(), max_value)
+        })
+        .collect::<Vec<_>>();
+
+    let fresh_domains = || {
+        let mut builder = BddVariableSetBuilder::new();
+        let domains = max_values
+            .iter()
+            .map(|(var_name, max_value)| (DO::new(&mut builder, var_name, max_value), *max_value))
+            .collect::<Vec<_>>();
+        (builder.build(), domains)
+    };
+
+    let mut naive_time = Duration::ZERO;
+    let mut direct_time = Duration::ZERO;
+    for _ in 0..repetitions {
+        let (bdd_variable_set, domains) = fresh_domains();
+        let now = std::time::Instant::now();
+        let naive = domains
+            .iter()
+            .flat_map(|(domain, max_value)| {
+                (0..=*max_value)
+                    .map(|bound| naive_encode_lt(domain, &bdd_variable_set, &bound))
+                    .chain([naive_unit_collection(domain, &bdd_variable_set)])
+            })
+            .collect::<Vec<_>>();
+        naive_time += now.elapsed();
+
+        let (bdd_variable_set, domains) = fresh_domains();
+        let now = std::time::Instant::now();
+        let direct = domains
+            .iter()
+            .flat_map(|(domain, max_value)| {
+                (0..=*max_value)
+                    .map(|bound| domain.encode_lt(&bdd_variable_set, &bound))
+                    .chain([domain.unit_collection(&bdd_variable_set)])
+            })
+            .collect::<Vec<_>>();
+        direct_time += now.elapsed();
+
+        assert_eq!(naive, direct, "the encodings should be equivalent");
+    }
+
+    println!(
+        "Encoded comparisons of {} variables ({} repetitions)",
+        max_values.len(),
+        repetitions
+    );
+    println!(" > naive: {}us", naive_time.as_micros());
+    println!(" > direct: {}us", direct_time.as_micros());
+}
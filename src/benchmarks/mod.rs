@@ -1,2 +1,5 @@
+pub mod comparison_encoding;
 pub mod reachability;
 pub mod rewritten_reachability;
+pub mod system_operations;
+pub mod variable_ordering;
@@ -0,0 +1,104 @@
+use biodivine_lib_bdd::Bdd;
+use std::{fmt::Debug, time::Duration};
+
+use crate::{
+    prelude::find_start_of,
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    update::update_fn::{SmartSystemUpdateFn, SystemUpdateFn},
+};
+
+fn load_xml(sbml_path: &str) -> xml::reader::EventReader<std::io::BufReader<std::fs::File>> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+
+    find_start_of(&mut xml, "listOfTransitions")
+        .expect("Cannot find transitions in the SBML file.");
+
+    xml
+}
+
+/// Measures the time needed to construct both [SmartSystemUpdateFn] and [SystemUpdateFn]
+/// from the given model using `DO`, and to compute the successors and predecessors of the
+/// whole state space under every variable. Everything is repeated `repetitions` times.
+///
+/// These operations do not depend on the size of the reachable sets, hence they are suitable
+/// for comparing the overhead of the domains themselves (e.g. of the [BooleanDomain] used
+/// by the [MixedIntegerDomain] against the unary encoding of Boolean variables).
+///
+/// [BooleanDomain]: crate::symbolic_domains::symbolic_domain::BooleanDomain
+/// [MixedIntegerDomain]: crate::symbolic_domains::symbolic_domain::MixedIntegerDomain
+pub fn system_operations_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(
+    sbml_path: &str,
+    repetitions: u32,
+) {
+    let mut smart_construction = Duration::ZERO;
+    let mut smart_operations = Duration::ZERO;
+    let mut dumb_construction = Duration::ZERO;
+    let mut dumb_operations = Duration::ZERO;
+
+    for _ in 0..repetitions {
+        let now = std::time::Instant::now();
+        let smart = SmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut load_xml(sbml_path))
+            .expect("Loading system fn update failed.");
+        smart_construction += now.elapsed();
+
+        let now = std::time::Instant::now();
+        let unit = smart.unit_vertex_set();
+        for var in smart.get_system_variables() {
+            let successors = smart.successors_async(&var, &unit);
+            let predecessors = smart.predecessors_async(&var, unit.clone());
+            assert!(successors.imp(&unit).is_true() && predecessors.imp(&unit).is_true());
+        }
+        smart_operations += now.elapsed();
+
+        let now = std::time::Instant::now();
+        let dumb = SystemUpdateFn::<DO, u8>::try_from_xml(&mut load_xml(sbml_path))
+            .expect("Loading system fn update failed.");
+        dumb_construction += now.elapsed();
+
+        let now = std::time::Instant::now();
+        let unit = dumb_unit_vertex_set(&smart, &dumb);
+        for var in smart.get_system_variables() {
+            dumb.successors_async(&var, &unit);
+            dumb.predecessors_async(&var, &unit);
+        }
+        dumb_operations += now.elapsed();
+    }
+
+    println!(
+        "Variables: {} ({} repetitions)",
+        SmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut load_xml(sbml_path))
+            .expect("Loading system fn update failed.")
+            .get_system_variables()
+            .len(),
+        repetitions
+    );
+    println!(
+        " > smart construction: {}ms",
+        smart_construction.as_millis()
+    );
+    println!(" > smart operations: {}ms", smart_operations.as_millis());
+    println!(" > dumb construction: {}ms", dumb_construction.as_millis());
+    println!(" > dumb operations: {}ms", dumb_operations.as_millis());
+}
+
+/// The set of all valid states of the [SystemUpdateFn], which does not expose its domains.
+fn dumb_unit_vertex_set<DO: SymbolicDomainOrd<u8>>(
+    smart: &SmartSystemUpdateFn<DO, u8>,
+    dumb: &SystemUpdateFn<DO, u8>,
+) -> Bdd {
+    smart
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .map(|(var, domain)| {
+            domain
+                .get_all_possible_values()
+                .iter()
+                .map(|value| dumb.encode_one(var, value))
+                .reduce(|acc, it| acc.or(&it))
+                .expect("domain is not empty")
+        })
+        .reduce(|acc, it| acc.and(&it))
+        .expect("model has some variables")
+}
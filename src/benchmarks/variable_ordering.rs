@@ -0,0 +1,54 @@
+use std::fmt::Debug;
+
+use crate::{
+    prelude::{find_start_of, load_all_update_fns},
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    update::{
+        update_fn::SmartSystemUpdateFn,
+        variable_ordering::{VariableOrder, VariableOrdering},
+    },
+};
+
+/// Builds the system of the given model using `DO` and every available [VariableOrder]
+/// (with and without interleaved bits), and reports the construction time and the sizes
+/// of the resulting BDDs.
+pub fn variable_ordering_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str) {
+    let update_fns = {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open(sbml_path).expect("should be able to open file"),
+        ));
+
+        find_start_of(&mut xml, "listOfTransitions")
+            .expect("Cannot find transitions in the SBML file.");
+
+        load_all_update_fns::<_, _, u8>(&mut xml).expect("Loading system fn update failed.")
+    };
+    println!("Variables: {}", update_fns.len());
+
+    let orders = [
+        ("alphabetical", VariableOrder::Alphabetical),
+        ("dfs", VariableOrder::RegulatoryDfs),
+        ("bfs", VariableOrder::RegulatoryBfs),
+        ("force", VariableOrder::Force),
+    ];
+    for (name, order) in orders {
+        for interleave_bits in [false, true] {
+            let ordering = VariableOrdering::new(order.clone(), interleave_bits);
+            let now = std::time::Instant::now();
+            let system = SmartSystemUpdateFn::<DO, u8>::from_update_fns_with_ordering(
+                update_fns.clone(),
+                &ordering,
+            );
+            let elapsed = now.elapsed();
+
+            println!(
+                "{:>12} (interleaved: {:>5}): relations size {:>8}, unit set size {:>6}, construction {}ms",
+                name,
+                interleave_bits,
+                system.transition_relations_size(),
+                system.unit_vertex_set().size(),
+                elapsed.as_millis()
+            );
+        }
+    }
+}
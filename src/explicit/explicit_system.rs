@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use crate::{
+    explicit::simulation::AsyncSemantics,
+    update::{
+        state::{State, StateError},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    },
+};
+
+/// A system which evaluates the update functions explicitly on concrete [State]-s, without
+/// building any BDDs.
+///
+/// The variables are sorted alphabetically, i.e. the [State]-s match those of a
+/// [SmartSystemUpdateFn] with the default variable ordering. Meant as a simple reference
+/// implementation of the asynchronous semantics and for fast simulation.
+///
+/// [SmartSystemUpdateFn]: crate::update::update_fn::SmartSystemUpdateFn
+#[derive(Clone, Debug)]
+pub struct ExplicitSystem<T> {
+    variables: Vec<String>,
+    update_fns: Vec<UnprocessedVariableUpdateFn<T>>,
+    mapper: HashMap<String, usize>,
+}
+
+impl<T> ExplicitSystem<T>
+where
+    T: Clone + Ord,
+{
+    pub fn new(vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>) -> Self {
+        let mut named_update_fns = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
+        named_update_fns.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let (variables, update_fns): (Vec<_>, Vec<_>) = named_update_fns.into_iter().unzip();
+        let mapper = variables
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect();
+        Self {
+            variables,
+            update_fns,
+            mapper,
+        }
+    }
+
+    /// The names of the system variables, in the order used by the [State]-s.
+    pub fn get_system_variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn variable_index(&self, variable_name: &str) -> Option<usize> {
+        self.mapper.get(variable_name).copied()
+    }
+
+    /// The value of the variable with the given index after it is updated in the `state`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `state` does not have one value per system variable, or if an update
+    /// function refers to a variable which is not a system variable.
+    pub fn next_value(&self, state: &State<T>, variable_index: usize) -> T {
+        assert_eq!(
+            state.len(),
+            self.variables.len(),
+            "the state should have one value per system variable"
+        );
+        self.update_fns[variable_index]
+            .evaluate_with(&|name| self.variable_index(name).and_then(|idx| state.get(idx)))
+    }
+
+    /// The asynchronous successors of the `state`, each paired with the index of the updated
+    /// variable. Updates which do not change the `state` are not included.
+    pub fn successors_of_state(
+        &self,
+        state: &State<T>,
+    ) -> Result<Vec<(usize, State<T>)>, StateError> {
+        if state.len() != self.variables.len() {
+            return Err(StateError::WrongVariableCount {
+                expected: self.variables.len(),
+                actual: state.len(),
+            });
+        }
+        Ok((0..self.variables.len())
+            .filter_map(|idx| {
+                let next_value = self.next_value(state, idx);
+                (state.values()[idx] != next_value).then(|| {
+                    let mut values = state.values().to_vec();
+                    values[idx] = next_value;
+                    (idx, State::new(values))
+                })
+            })
+            .collect())
+    }
+}
+
+impl<T> AsyncSemantics<T> for ExplicitSystem<T>
+where
+    T: Clone + Ord,
+{
+    fn variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+
+    fn next_value(&self, state: &State<T>, variable_index: usize) -> T {
+        ExplicitSystem::next_value(self, state, variable_index)
+    }
+}
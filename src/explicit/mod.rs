@@ -0,0 +1,3 @@
+pub mod explicit_system;
+pub mod simulation;
+pub mod state_transition_graph;
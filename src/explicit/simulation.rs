@@ -0,0 +1,323 @@
+use std::{collections::HashMap, fmt::Display, hash::Hash};
+
+use biodivine_lib_bdd::BddPartialValuation;
+use num_bigint::BigInt;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use thiserror::Error;
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainEq,
+    update::{state::State, update_fn::SmartSystemUpdateFn},
+};
+
+/// The asynchronous semantics of a model, as needed by [simulate].
+pub trait AsyncSemantics<T> {
+    /// The names of the system variables, in the order used by the [State]-s.
+    fn variables(&self) -> Vec<String>;
+
+    /// The value of the variable with the given index after it is updated in the `state`.
+    fn next_value(&self, state: &State<T>, variable_index: usize) -> T;
+
+    /// The number of colors (i.e. models) described by the semantics. Only semantics with
+    /// a single color can be simulated.
+    fn color_count(&self) -> BigInt {
+        BigInt::from(1)
+    }
+}
+
+/// Evaluates the update functions symbolically, by computing the successors of a [Bdd]
+/// containing the single `state`.
+///
+/// A system with parameters can only be simulated if there is a single valid color, i.e.
+/// if the parameters are fully determined.
+///
+/// [Bdd]: biodivine_lib_bdd::Bdd
+impl<DO, T> AsyncSemantics<T> for SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainEq<T>,
+    T: Clone,
+{
+    fn variables(&self) -> Vec<String> {
+        self.get_system_variables()
+    }
+
+    fn next_value(&self, state: &State<T>, variable_index: usize) -> T {
+        let encoded = self
+            .encode_state(state)
+            .expect("the state should belong to the system")
+            .and(&self.unit_colors());
+        let (variable, domain) = self.standard_variables_names_and_domains()[variable_index];
+        // the successor is a single state, so any witness decodes to the updated value
+        let successor = self
+            .successors_async(variable, &encoded)
+            .sat_witness()
+            .expect("every state has a successor");
+        domain.decode_bits(&BddPartialValuation::from(successor))
+    }
+
+    fn color_count(&self) -> BigInt {
+        self.count_projected_exact(&self.unit_colors(), &self.parameter_variables())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationOptions {
+    /// The number of independent walks, all starting in the initial state.
+    pub walks: usize,
+    /// The maximal number of transitions of every walk. A walk ends earlier if no variable
+    /// with a positive rate can change its value.
+    pub steps: usize,
+    pub seed: u64,
+    /// The relative rates at which the variables are updated. Variables which are not listed
+    /// have the rate `1.0`. A variable with the rate `0.0` is never updated, hence a walk can
+    /// stall in a state which is not a fixed point (see [SimulationResult::stalled_walks]).
+    pub rates: HashMap<String, f64>,
+    /// Store the visited states of every walk, not just the visit counts.
+    pub record_trajectories: bool,
+}
+
+impl Default for SimulationOptions {
+    fn default() -> Self {
+        Self {
+            walks: 100,
+            steps: 1000,
+            seed: 0,
+            rates: HashMap::new(),
+            record_trajectories: false,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    UnknownVariable(String),
+    InvalidRate {
+        variable: String,
+        rate: f64,
+    },
+    WrongVariableCount {
+        expected: usize,
+        actual: usize,
+    },
+    /// The system has parameters with more than one valid color (i.e. model).
+    MultipleColors {
+        count: BigInt,
+    },
+}
+
+impl Display for SimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimulationError::UnknownVariable(name) => {
+                write!(f, "A rate is given for unknown variable {}", name)
+            }
+            SimulationError::InvalidRate { variable, rate } => write!(
+                f,
+                "The rate of variable {} must be a non-negative number, but is {}",
+                variable, rate
+            ),
+            SimulationError::WrongVariableCount { expected, actual } => write!(
+                f,
+                "Expected an initial state with {} variables, but it has {}",
+                expected, actual
+            ),
+            SimulationError::MultipleColors { count } => write!(
+                f,
+                "The system has {} colors, but only a single one can be simulated",
+                count
+            ),
+        }
+    }
+}
+
+/// A single walk. `states[i + 1]` is reached from `states[i]` by updating the variable
+/// `updated_variables[i]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trajectory<T> {
+    pub states: Vec<State<T>>,
+    pub updated_variables: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulationResult<T> {
+    pub variables: Vec<String>,
+    /// The number of times every state was visited, over all walks (including the initial
+    /// and the final states).
+    pub visits: HashMap<State<T>, usize>,
+    /// The last state of every walk.
+    pub final_states: Vec<State<T>>,
+    /// The number of walks which ended in a fixed point before running out of steps.
+    pub fixed_point_walks: usize,
+    /// The number of walks which ended before running out of steps because only variables
+    /// with the rate `0.0` could still change their value.
+    pub stalled_walks: usize,
+    /// Empty, unless [SimulationOptions::record_trajectories] is set.
+    pub trajectories: Vec<Trajectory<T>>,
+}
+
+impl<T> SimulationResult<T>
+where
+    T: Clone + Eq + Hash + Ord,
+{
+    /// The fraction of all visits spent in every state, most visited first.
+    pub fn visit_frequencies(&self) -> Vec<(State<T>, f64)> {
+        frequencies(self.visits.iter().map(|(state, count)| (state, *count)))
+    }
+
+    /// The fraction of walks which ended in every state, most frequent first.
+    ///
+    /// For long enough walks, this estimates the probability of reaching the attractors
+    /// containing these states.
+    pub fn final_state_frequencies(&self) -> Vec<(State<T>, f64)> {
+        let mut counts = HashMap::<&State<T>, usize>::new();
+        for state in &self.final_states {
+            *counts.entry(state).or_default() += 1;
+        }
+        frequencies(counts.into_iter())
+    }
+}
+
+fn frequencies<'a, T>(counts: impl Iterator<Item = (&'a State<T>, usize)>) -> Vec<(State<T>, f64)>
+where
+    T: Clone + Ord + 'a,
+{
+    let counts = counts.collect::<Vec<_>>();
+    let total = counts.iter().map(|(_, count)| count).sum::<usize>() as f64;
+    let mut result = counts
+        .into_iter()
+        .map(|(state, count)| (state.clone(), count as f64 / total))
+        .collect::<Vec<_>>();
+    // ties are broken by the state itself, so that the result is deterministic
+    result.sort_by(|(a_state, a), (b_state, b)| b.total_cmp(a).then(a_state.cmp(b_state)));
+    result
+}
+
+/// Performs random asynchronous walks from the `initial` state.
+///
+/// In every step, the variable to update is chosen among the variables whose value would
+/// change, with probabilities proportional to their rates. The same `options` (including
+/// the seed) always produce the same result.
+pub fn simulate<S, T>(
+    semantics: &S,
+    initial: &State<T>,
+    options: &SimulationOptions,
+) -> Result<SimulationResult<T>, SimulationError>
+where
+    S: AsyncSemantics<T>,
+    T: Clone + Eq + Hash,
+{
+    let variables = semantics.variables();
+    if initial.len() != variables.len() {
+        return Err(SimulationError::WrongVariableCount {
+            expected: variables.len(),
+            actual: initial.len(),
+        });
+    }
+    let color_count = semantics.color_count();
+    if color_count > BigInt::from(1) {
+        return Err(SimulationError::MultipleColors { count: color_count });
+    }
+    let rates = variable_rates(&variables, &options.rates)?;
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut result = SimulationResult {
+        variables,
+        visits: HashMap::new(),
+        final_states: Vec::new(),
+        fixed_point_walks: 0,
+        stalled_walks: 0,
+        trajectories: Vec::new(),
+    };
+
+    for _ in 0..options.walks {
+        let mut state = initial.clone();
+        let mut trajectory = Trajectory {
+            states: vec![state.clone()],
+            updated_variables: Vec::new(),
+        };
+        *result.visits.entry(state.clone()).or_default() += 1;
+
+        for _ in 0..options.steps {
+            let enabled = (0..rates.len())
+                .filter(|idx| rates[*idx] > 0.0)
+                .filter_map(|idx| {
+                    let next_value = semantics.next_value(&state, idx);
+                    (state.values()[idx] != next_value).then_some((idx, next_value))
+                })
+                .collect::<Vec<_>>();
+            let Some((idx, next_value)) = choose_update(&mut rng, &rates, enabled) else {
+                let stalled = (0..rates.len())
+                    .filter(|idx| rates[*idx] == 0.0)
+                    .any(|idx| state.values()[idx] != semantics.next_value(&state, idx));
+                if stalled {
+                    result.stalled_walks += 1;
+                } else {
+                    result.fixed_point_walks += 1;
+                }
+                break;
+            };
+
+            let mut values = state.into_values();
+            values[idx] = next_value;
+            state = State::new(values);
+            *result.visits.entry(state.clone()).or_default() += 1;
+            if options.record_trajectories {
+                trajectory.states.push(state.clone());
+                trajectory.updated_variables.push(idx);
+            }
+        }
+
+        result.final_states.push(state);
+        if options.record_trajectories {
+            result.trajectories.push(trajectory);
+        }
+    }
+
+    Ok(result)
+}
+
+/// The rate of every variable (in the order of `variables`).
+fn variable_rates(
+    variables: &[String],
+    rates: &HashMap<String, f64>,
+) -> Result<Vec<f64>, SimulationError> {
+    if let Some(unknown) = rates.keys().find(|name| !variables.contains(name)) {
+        return Err(SimulationError::UnknownVariable(unknown.clone()));
+    }
+    variables
+        .iter()
+        .map(|name| {
+            let rate = rates.get(name).copied().unwrap_or(1.0);
+            if rate.is_finite() && rate >= 0.0 {
+                Ok(rate)
+            } else {
+                Err(SimulationError::InvalidRate {
+                    variable: name.clone(),
+                    rate,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Picks one of the `enabled` updates with probability proportional to the rate of its variable.
+fn choose_update<T>(
+    rng: &mut StdRng,
+    rates: &[f64],
+    enabled: Vec<(usize, T)>,
+) -> Option<(usize, T)> {
+    if enabled.is_empty() {
+        return None;
+    }
+    let total = enabled.iter().map(|(idx, _)| rates[*idx]).sum::<f64>();
+    let mut remaining = rng.gen_range(0.0..total);
+    let last = enabled.len() - 1;
+    for (position, (idx, value)) in enabled.into_iter().enumerate() {
+        remaining -= rates[idx];
+        // the last update also absorbs any floating point error
+        if remaining < 0.0 || position == last {
+            return Some((idx, value));
+        }
+    }
+    unreachable!("there is at least one enabled update")
+}
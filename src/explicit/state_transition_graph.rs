@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    hash::Hash,
+};
+
+use biodivine_lib_bdd::Bdd;
+use num_bigint::BigInt;
+use serde::Serialize;
+use thiserror::Error;
+use xml::escape::escape_str_pcdata;
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainEq,
+    update::{state::State, update_fn::SmartSystemUpdateFn},
+};
+
+/// The default maximal number of states of a [StateTransitionGraph].
+pub const DEFAULT_STATE_LIMIT: usize = 4096;
+
+/// Selects which transitions are included in a [StateTransitionGraph].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdateScheme {
+    /// Every transition updates a single variable.
+    #[default]
+    Asynchronous,
+    /// Every transition updates all variables at once.
+    Synchronous,
+}
+
+#[derive(Clone, Debug)]
+pub struct StgOptions {
+    pub scheme: UpdateScheme,
+    /// The graph is not built if it would contain more states than this.
+    pub state_limit: usize,
+    /// Include the transitions which do not change the state.
+    pub include_self_loops: bool,
+}
+
+impl Default for StgOptions {
+    fn default() -> Self {
+        Self {
+            scheme: UpdateScheme::default(),
+            state_limit: DEFAULT_STATE_LIMIT,
+            include_self_loops: false,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StgError {
+    TooManyStates {
+        limit: usize,
+        count: BigInt,
+    },
+    /// The system has parameters and the set contains more than one color (i.e. model).
+    MultipleColors {
+        count: BigInt,
+    },
+}
+
+impl Display for StgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StgError::TooManyStates { limit, count } => write!(
+                f,
+                "The graph would have {} states, but at most {} are allowed",
+                count, limit
+            ),
+            StgError::MultipleColors { count } => write!(
+                f,
+                "The set contains {} colors, but the graph can only be built for a single one",
+                count
+            ),
+        }
+    }
+}
+
+/// A transition of a [StateTransitionGraph] between the states with the given indices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StgEdge {
+    pub source: usize,
+    pub target: usize,
+    /// Indices of the variables whose value changes along the transition (or, for a self-loop,
+    /// the variable which is updated in the asynchronous scheme).
+    pub updated_variables: Vec<usize>,
+}
+
+/// An explicit state-transition graph of a (small) [SmartSystemUpdateFn].
+///
+/// Meant for teaching and debugging; the number of states is bounded by
+/// [StgOptions::state_limit].
+#[derive(Clone, Debug)]
+pub struct StateTransitionGraph<T> {
+    variables: Vec<String>,
+    states: Vec<State<T>>,
+    edges: Vec<StgEdge>,
+}
+
+impl<T> StateTransitionGraph<T>
+where
+    T: Clone + Eq + Hash + Display,
+{
+    /// Builds the graph over all states of the `system`.
+    ///
+    /// Returns [StgError::MultipleColors] if the system has parameters (and more than one
+    /// valid color); use [Self::try_from_set] with a set restricted to a single color instead.
+    pub fn try_from_system<DO>(
+        system: &SmartSystemUpdateFn<DO, T>,
+        options: &StgOptions,
+    ) -> Result<Self, StgError>
+    where
+        DO: SymbolicDomainEq<T>,
+    {
+        Self::try_from_set(system, &system.unit_vertex_set(), options)
+    }
+
+    /// Builds the graph induced by the states in `set`, i.e. the transitions leaving the `set`
+    /// are not included.
+    ///
+    /// If the system has parameters, the `set` must only contain a single color, e.g. the
+    /// states of the system conjoined with one valuation of the parameters, and the graph
+    /// is built for the model given by this color.
+    pub fn try_from_set<DO>(
+        system: &SmartSystemUpdateFn<DO, T>,
+        set: &Bdd,
+        options: &StgOptions,
+    ) -> Result<Self, StgError>
+    where
+        DO: SymbolicDomainEq<T>,
+    {
+        let count = system.count_states_exact(set);
+        if count > BigInt::from(options.state_limit) {
+            return Err(StgError::TooManyStates {
+                limit: options.state_limit,
+                count,
+            });
+        }
+        let color_count = system.count_projected_exact(set, &system.parameter_variables());
+        if !system.parameters().is_empty() && color_count > BigInt::from(1) {
+            return Err(StgError::MultipleColors { count: color_count });
+        }
+        let color = system.colors(set);
+
+        let variables = system.get_system_variables();
+        let states = system.iter_states(set).collect::<Vec<_>>();
+        let index_of = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (state, idx))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = Vec::new();
+        for (source, state) in states.iter().enumerate() {
+            let next_values = next_values(system, &variables, state, &color);
+            let mut add_edge = |target: State<T>, updated_variables: Vec<usize>| {
+                if let Some(target) = index_of.get(&target) {
+                    edges.push(StgEdge {
+                        source,
+                        target: *target,
+                        updated_variables,
+                    });
+                }
+            };
+
+            match options.scheme {
+                UpdateScheme::Asynchronous => {
+                    for (idx, next_value) in next_values.into_iter().enumerate() {
+                        if options.include_self_loops || state.values()[idx] != next_value {
+                            let mut target = state.values().to_vec();
+                            target[idx] = next_value;
+                            add_edge(State::new(target), vec![idx]);
+                        }
+                    }
+                }
+                UpdateScheme::Synchronous => {
+                    let updated_variables = (0..variables.len())
+                        .filter(|idx| state.values()[*idx] != next_values[*idx])
+                        .collect::<Vec<_>>();
+                    if options.include_self_loops || !updated_variables.is_empty() {
+                        add_edge(State::new(next_values), updated_variables);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            variables,
+            states,
+            edges,
+        })
+    }
+
+    /// The names of the system variables, in the order used by the [State]-s of the graph.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn states(&self) -> &[State<T>] {
+        &self.states
+    }
+
+    pub fn edges(&self) -> &[StgEdge] {
+        &self.edges
+    }
+
+    /// The label of the state with the given index, e.g. `p=1,q=0`.
+    pub fn state_label(&self, state_index: usize) -> String {
+        self.variables
+            .iter()
+            .zip(self.states[state_index].values())
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// The label of the given edge, i.e. the names of its updated variables, e.g. `p,q`.
+    pub fn edge_label(&self, edge: &StgEdge) -> String {
+        edge.updated_variables
+            .iter()
+            .map(|idx| self.variables[*idx].as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn to_dot(&self) -> String {
+        let escape = |label: String| label.replace('\\', "\\\\").replace('"', "\\\"");
+
+        let mut dot = String::from("digraph stg {\n");
+        for idx in 0..self.states.len() {
+            writeln!(
+                dot,
+                "  s{} [label=\"{}\"];",
+                idx,
+                escape(self.state_label(idx))
+            )
+            .expect("writing to a string cannot fail");
+        }
+        for edge in &self.edges {
+            writeln!(
+                dot,
+                "  s{} -> s{} [label=\"{}\"];",
+                edge.source,
+                edge.target,
+                escape(self.edge_label(edge))
+            )
+            .expect("writing to a string cannot fail");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut graphml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <key id=\"variables\" for=\"edge\" attr.name=\"variables\" attr.type=\"string\"/>\n",
+            "  <graph id=\"stg\" edgedefault=\"directed\">\n",
+        ));
+        for idx in 0..self.states.len() {
+            writeln!(
+                graphml,
+                "    <node id=\"s{}\"><data key=\"label\">{}</data></node>",
+                idx,
+                escape_str_pcdata(&self.state_label(idx))
+            )
+            .expect("writing to a string cannot fail");
+        }
+        for (idx, edge) in self.edges.iter().enumerate() {
+            writeln!(
+                graphml,
+                "    <edge id=\"e{}\" source=\"s{}\" target=\"s{}\"><data key=\"variables\">{}</data></edge>",
+                idx,
+                edge.source,
+                edge.target,
+                escape_str_pcdata(&self.edge_label(edge))
+            )
+            .expect("writing to a string cannot fail");
+        }
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+
+    pub fn to_json(&self) -> String
+    where
+        T: Serialize,
+    {
+        #[derive(Serialize)]
+        struct JsonState<'a, T> {
+            id: usize,
+            label: String,
+            values: &'a [T],
+        }
+
+        #[derive(Serialize)]
+        struct JsonEdge {
+            source: usize,
+            target: usize,
+            label: String,
+            variables: Vec<String>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonGraph<'a, T> {
+            variables: &'a [String],
+            states: Vec<JsonState<'a, T>>,
+            edges: Vec<JsonEdge>,
+        }
+
+        let graph = JsonGraph {
+            variables: &self.variables,
+            states: self
+                .states
+                .iter()
+                .enumerate()
+                .map(|(id, state)| JsonState {
+                    id,
+                    label: self.state_label(id),
+                    values: state.values(),
+                })
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .map(|edge| JsonEdge {
+                    source: edge.source,
+                    target: edge.target,
+                    label: self.edge_label(edge),
+                    variables: edge
+                        .updated_variables
+                        .iter()
+                        .map(|idx| self.variables[*idx].clone())
+                        .collect(),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&graph).expect("the graph is serializable")
+    }
+}
+
+/// The value of every variable after it is updated in the given `state`, in the model
+/// given by the single `color`.
+fn next_values<DO, T>(
+    system: &SmartSystemUpdateFn<DO, T>,
+    variables: &[String],
+    state: &State<T>,
+    color: &Bdd,
+) -> Vec<T>
+where
+    DO: SymbolicDomainEq<T>,
+    T: Clone,
+{
+    let encoded = system
+        .encode_state(state)
+        .expect("the state is taken from the system")
+        .and(color);
+    variables
+        .iter()
+        .enumerate()
+        .map(|(idx, var)| {
+            system
+                .pick_state(&system.successors_async(var, &encoded))
+                .expect("every state has a successor")
+                .values()[idx]
+                .clone()
+        })
+        .collect()
+}
@@ -1,5 +1,10 @@
 #![allow(dead_code)]
 
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
 use super::proposition::Proposition;
 
 /// Represents a recursive expression. Leaf nodes are propositions. Join `Expression::Terminal`s
@@ -21,7 +26,7 @@ use super::proposition::Proposition;
 /// - `Expression::Implies` - an implication of the inner expressions. The order of the
 /// operands follows conventional notation, i.e. `Expression::Implies(lhs, rhs)` is
 /// equivalent to `lhs => rhs`.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Expression<T> {
     Terminal(Proposition<T>),
     Not(Box<Expression<T>>),
@@ -30,3 +35,244 @@ pub enum Expression<T> {
     Xor(Box<Expression<T>>, Box<Expression<T>>),
     Implies(Box<Expression<T>>, Box<Expression<T>>),
 }
+
+impl<T> Expression<T> {
+    /// Constant `true`, represented as an empty conjunction.
+    pub fn mk_true() -> Self {
+        Expression::And(Vec::new())
+    }
+
+    /// Constant `false`, represented as an empty disjunction.
+    pub fn mk_false() -> Self {
+        Expression::Or(Vec::new())
+    }
+
+    /// If this expression is one of the constants created by [Expression::mk_true] or
+    /// [Expression::mk_false], returns its value.
+    pub fn as_constant(&self) -> Option<bool> {
+        match self {
+            Expression::And(clauses) if clauses.is_empty() => Some(true),
+            Expression::Or(clauses) if clauses.is_empty() => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Names of all variables that appear in some proposition of this expression,
+    /// sorted alphabetically.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let mut acc = BTreeSet::new();
+        self.collect_variables(&mut acc);
+        acc
+    }
+
+    fn collect_variables<'a>(&'a self, acc: &mut BTreeSet<&'a str>) {
+        match self {
+            Expression::Terminal(proposition) => {
+                acc.insert(proposition.variable.as_str());
+            }
+            Expression::Not(inner) => inner.collect_variables(acc),
+            Expression::And(clauses) | Expression::Or(clauses) => clauses
+                .iter()
+                .for_each(|clause| clause.collect_variables(acc)),
+            Expression::Xor(lhs, rhs) | Expression::Implies(lhs, rhs) => {
+                lhs.collect_variables(acc);
+                rhs.collect_variables(acc);
+            }
+        }
+    }
+}
+
+impl<T: Ord> Expression<T> {
+    /// Decides whether the expression holds in the given `state`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value of a variable used in the expression is missing from the `state`.
+    pub fn evaluate(&self, state: &HashMap<String, T>) -> bool {
+        self.evaluate_with(&|variable| state.get(variable))
+    }
+
+    /// Same as [Expression::evaluate], but the value of every variable is given by `value_of`.
+    pub fn evaluate_with<'a, F>(&self, value_of: &F) -> bool
+    where
+        F: Fn(&str) -> Option<&'a T>,
+        T: 'a,
+    {
+        match self {
+            Expression::Terminal(proposition) => {
+                let value = value_of(&proposition.variable).unwrap_or_else(|| {
+                    panic!("no value given for variable {}", proposition.variable)
+                });
+                proposition.evaluate(value)
+            }
+            Expression::Not(inner) => !inner.evaluate_with(value_of),
+            Expression::And(clauses) => clauses.iter().all(|it| it.evaluate_with(value_of)),
+            Expression::Or(clauses) => clauses.iter().any(|it| it.evaluate_with(value_of)),
+            Expression::Xor(lhs, rhs) => lhs.evaluate_with(value_of) != rhs.evaluate_with(value_of),
+            Expression::Implies(lhs, rhs) => {
+                !lhs.evaluate_with(value_of) || rhs.evaluate_with(value_of)
+            }
+        }
+    }
+}
+
+impl<T: Clone> Expression<T> {
+    /// Creates a copy of this expression where every proposition is replaced by the
+    /// expression returned from `replace`.
+    pub fn map_propositions<F>(&self, replace: &F) -> Expression<T>
+    where
+        F: Fn(&Proposition<T>) -> Expression<T>,
+    {
+        match self {
+            Expression::Terminal(proposition) => replace(proposition),
+            Expression::Not(inner) => Expression::Not(Box::new(inner.map_propositions(replace))),
+            Expression::And(clauses) => Expression::And(
+                clauses
+                    .iter()
+                    .map(|clause| clause.map_propositions(replace))
+                    .collect(),
+            ),
+            Expression::Or(clauses) => Expression::Or(
+                clauses
+                    .iter()
+                    .map(|clause| clause.map_propositions(replace))
+                    .collect(),
+            ),
+            Expression::Xor(lhs, rhs) => Expression::Xor(
+                Box::new(lhs.map_propositions(replace)),
+                Box::new(rhs.map_propositions(replace)),
+            ),
+            Expression::Implies(lhs, rhs) => Expression::Implies(
+                Box::new(lhs.map_propositions(replace)),
+                Box::new(rhs.map_propositions(replace)),
+            ),
+        }
+    }
+}
+
+impl<T> Expression<T> {
+    /// Eliminates the constants created by [Expression::mk_true] and [Expression::mk_false]
+    /// from the expression, unless the whole expression is itself constant.
+    ///
+    /// Single-item conjunctions and disjunctions are unwrapped as well. Otherwise,
+    /// the structure of the expression is left untouched.
+    pub fn propagate_constants(self) -> Expression<T> {
+        match self {
+            Expression::Terminal(_) => self,
+            Expression::Not(inner) => {
+                let inner = inner.propagate_constants();
+                match inner.as_constant() {
+                    Some(value) => constant(!value),
+                    None => Expression::Not(Box::new(inner)),
+                }
+            }
+            Expression::And(clauses) => {
+                let mut remaining = Vec::new();
+                for clause in clauses {
+                    let clause = clause.propagate_constants();
+                    match clause.as_constant() {
+                        Some(false) => return Expression::mk_false(),
+                        Some(true) => {}
+                        None => remaining.push(clause),
+                    }
+                }
+                if remaining.len() == 1 {
+                    remaining.remove(0)
+                } else {
+                    Expression::And(remaining)
+                }
+            }
+            Expression::Or(clauses) => {
+                let mut remaining = Vec::new();
+                for clause in clauses {
+                    let clause = clause.propagate_constants();
+                    match clause.as_constant() {
+                        Some(true) => return Expression::mk_true(),
+                        Some(false) => {}
+                        None => remaining.push(clause),
+                    }
+                }
+                if remaining.len() == 1 {
+                    remaining.remove(0)
+                } else {
+                    Expression::Or(remaining)
+                }
+            }
+            Expression::Xor(lhs, rhs) => {
+                let lhs = lhs.propagate_constants();
+                let rhs = rhs.propagate_constants();
+                match (lhs.as_constant(), rhs.as_constant()) {
+                    (Some(lhs), Some(rhs)) => constant(lhs ^ rhs),
+                    (Some(false), None) => rhs,
+                    (Some(true), None) => Expression::Not(Box::new(rhs)),
+                    (None, Some(false)) => lhs,
+                    (None, Some(true)) => Expression::Not(Box::new(lhs)),
+                    (None, None) => Expression::Xor(Box::new(lhs), Box::new(rhs)),
+                }
+            }
+            Expression::Implies(lhs, rhs) => {
+                let lhs = lhs.propagate_constants();
+                let rhs = rhs.propagate_constants();
+                match (lhs.as_constant(), rhs.as_constant()) {
+                    (Some(false), _) | (_, Some(true)) => Expression::mk_true(),
+                    (Some(true), _) => rhs,
+                    (None, Some(false)) => Expression::Not(Box::new(lhs)),
+                    (None, None) => Expression::Implies(Box::new(lhs), Box::new(rhs)),
+                }
+            }
+        }
+    }
+}
+
+fn constant<T>(value: bool) -> Expression<T> {
+    if value {
+        Expression::mk_true()
+    } else {
+        Expression::mk_false()
+    }
+}
+
+/// Displays the expression using `!`, `&`, `|`, `^` and `=>` for the logical operators and
+/// `true`/`false` for the constants. Nested compound expressions are always parenthesized.
+impl<T: Display> Display for Expression<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let write_operand = |f: &mut std::fmt::Formatter<'_>, operand: &Expression<T>| match operand
+        {
+            Expression::Terminal(_) | Expression::Not(_) => write!(f, "{}", operand),
+            _ if operand.as_constant().is_some() => write!(f, "{}", operand),
+            _ => write!(f, "({})", operand),
+        };
+        let write_junction =
+            |f: &mut std::fmt::Formatter<'_>, operands: &[Expression<T>], operator: &str| {
+                for (idx, operand) in operands.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, " {} ", operator)?;
+                    }
+                    write_operand(f, operand)?;
+                }
+                Ok(())
+            };
+
+        match self {
+            _ if self.as_constant() == Some(true) => write!(f, "true"),
+            _ if self.as_constant() == Some(false) => write!(f, "false"),
+            Expression::Terminal(proposition) => write!(f, "{}", proposition),
+            Expression::Not(inner) => {
+                write!(f, "!")?;
+                write_operand(f, inner)
+            }
+            Expression::And(clauses) => write_junction(f, clauses, "&"),
+            Expression::Or(clauses) => write_junction(f, clauses, "|"),
+            Expression::Xor(lhs, rhs) => {
+                write_operand(f, lhs)?;
+                write!(f, " ^ ")?;
+                write_operand(f, rhs)
+            }
+            Expression::Implies(lhs, rhs) => {
+                write_operand(f, lhs)?;
+                write!(f, " => ")?;
+                write_operand(f, rhs)
+            }
+        }
+    }
+}
@@ -1,2 +1,3 @@
 pub mod expression;
 pub mod proposition;
+pub mod simplification;
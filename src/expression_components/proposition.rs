@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug)]
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ComparisonOperator {
     Eq,
     Neq,
@@ -23,6 +25,50 @@ impl ComparisonOperator {
             Self::Geq => Self::Leq,
         }
     }
+
+    /// The operator which is satisfied exactly when this operator is not,
+    /// i.e. `!(x op v)` is equivalent to `x op.negate() v`.
+    pub fn negate(&self) -> Self {
+        match self {
+            Self::Eq => Self::Neq,
+            Self::Neq => Self::Eq,
+            Self::Lt => Self::Geq,
+            Self::Gt => Self::Leq,
+            Self::Leq => Self::Gt,
+            Self::Geq => Self::Lt,
+        }
+    }
+}
+
+impl ComparisonOperator {
+    /// The mathematical symbol of the operator, as used when displaying propositions.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Neq => "!=",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::Leq => "<=",
+            Self::Geq => ">=",
+        }
+    }
+
+    /// The operator whose [Self::symbol] is at the start of `text` (`==` is also accepted
+    /// for [Self::Eq]), together with the rest of the `text`.
+    pub fn strip_symbol(text: &str) -> Option<(Self, &str)> {
+        // the longer symbols go first, so that e.g. `<=` is not read as `<`
+        [
+            ("<=", Self::Leq),
+            (">=", Self::Geq),
+            ("!=", Self::Neq),
+            ("==", Self::Eq),
+            ("=", Self::Eq),
+            ("<", Self::Lt),
+            (">", Self::Gt),
+        ]
+        .into_iter()
+        .find_map(|(symbol, operator)| text.strip_prefix(symbol).map(|rest| (operator, rest)))
+    }
 }
 
 impl FromStr for ComparisonOperator {
@@ -59,7 +105,7 @@ impl ToString for ComparisonOperator {
 ///
 /// This order is fixed. To represent a formula of form `value comparison_operator variable`,
 /// use `comparison_operator.flip()`.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Proposition<T> {
     pub comparison_operator: ComparisonOperator,
     pub variable: String,
@@ -76,6 +122,118 @@ impl<T> Proposition<T> {
     }
 }
 
+impl<T: Display> Display for Proposition<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.variable,
+            self.comparison_operator.symbol(),
+            self.value
+        )
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PropositionParseError {
+    /// The text does not have the form `variable op value`.
+    InvalidProposition(String),
+    InvalidVariable(String),
+    InvalidValue(String),
+    /// The partial assignment is not enclosed in braces.
+    InvalidAssignment(String),
+}
+
+impl Display for PropositionParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropositionParseError::InvalidProposition(text) => {
+                write!(f, "Expected `variable op value`, found `{}`", text)
+            }
+            PropositionParseError::InvalidVariable(name) => {
+                write!(f, "Invalid variable name `{}`", name)
+            }
+            PropositionParseError::InvalidValue(value) => write!(f, "Invalid value `{}`", value),
+            PropositionParseError::InvalidAssignment(text) => {
+                write!(f, "Expected `{{variable: value, ...}}`, found `{}`", text)
+            }
+        }
+    }
+}
+
+/// Parses the `value` of a proposition about the `variable`, e.g. `1` or `>= 2`, where
+/// a value without an operator means equality.
+fn parse_constraint<T: FromStr>(
+    variable: &str,
+    value: &str,
+) -> Result<Proposition<T>, PropositionParseError> {
+    let variable = variable.trim();
+    if variable.is_empty() || !variable.chars().all(|it| it.is_alphanumeric() || it == '_') {
+        return Err(PropositionParseError::InvalidVariable(variable.to_string()));
+    }
+    let value = value.trim();
+    let (operator, value) =
+        ComparisonOperator::strip_symbol(value).unwrap_or((ComparisonOperator::Eq, value));
+    let value = value.trim();
+    let value = value
+        .parse()
+        .map_err(|_| PropositionParseError::InvalidValue(value.to_string()))?;
+    Ok(Proposition::new(operator, variable.to_string(), value))
+}
+
+/// Parses propositions in the form used by their [Display], e.g. `q >= 2` (the whitespace
+/// is optional), i.e. the same syntax as in the queries.
+impl<T: FromStr> FromStr for Proposition<T> {
+    type Err = PropositionParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let split = text
+            .find(['=', '!', '<', '>'])
+            .filter(|idx| ComparisonOperator::strip_symbol(&text[*idx..]).is_some())
+            .ok_or_else(|| PropositionParseError::InvalidProposition(text.to_string()))?;
+        parse_constraint(&text[..split], &text[split..])
+    }
+}
+
+/// Parses a partial assignment such as `{p: 1, q: >=2}` into one proposition per listed
+/// variable, i.e. `p = 1` and `q >= 2`. The value of a variable can be preceded by any
+/// comparison operator, and is compared for equality otherwise. The variables which are
+/// not listed are not constrained, so `{}` gives no propositions.
+pub fn parse_partial_assignment<T: FromStr>(
+    text: &str,
+) -> Result<Vec<Proposition<T>>, PropositionParseError> {
+    let invalid = || PropositionParseError::InvalidAssignment(text.to_string());
+    let entries = text
+        .trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    if entries.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    entries
+        .split(',')
+        .map(|entry| {
+            let (variable, value) = entry.split_once(':').ok_or_else(invalid)?;
+            parse_constraint(variable, value)
+        })
+        .collect()
+}
+
+impl<T: Ord> Proposition<T> {
+    /// Decides whether the proposition holds when `self.variable` is set to `variable_value`.
+    pub fn evaluate(&self, variable_value: &T) -> bool {
+        match self.comparison_operator {
+            ComparisonOperator::Eq => variable_value == &self.value,
+            ComparisonOperator::Neq => variable_value != &self.value,
+            ComparisonOperator::Lt => variable_value < &self.value,
+            ComparisonOperator::Gt => variable_value > &self.value,
+            ComparisonOperator::Leq => variable_value <= &self.value,
+            ComparisonOperator::Geq => variable_value >= &self.value,
+        }
+    }
+}
+
 pub struct Person {
     /// A person must have a name, no matter how much Juliet may hate it
     name: String,
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use super::{
+    expression::Expression,
+    proposition::{ComparisonOperator as CmpOp, Proposition},
+};
+
+impl<T: Clone + Ord> Expression<T> {
+    /// Rewrites the expression into a semantically equivalent, but (typically) smaller form.
+    ///
+    /// The `domains` map each variable to the list of values it can attain, sorted in ascending
+    /// order. Propositions that are always (or never) satisfied within the domain of their
+    /// variable are replaced by constants, and propositions on the same variable that appear
+    /// in the same conjunction (disjunction) are merged into a single interval. Variables
+    /// without a domain in `domains` are left as they are.
+    ///
+    /// Furthermore, nested conjunctions and disjunctions are flattened, double negations are
+    /// removed and negated propositions are replaced by the proposition with the negated
+    /// comparison operator. The result does not contain the constants [Expression::mk_true]
+    /// and [Expression::mk_false] unless the whole expression is constant.
+    pub fn simplify(&self, domains: &HashMap<String, Vec<T>>) -> Expression<T> {
+        match self {
+            Expression::Terminal(proposition) => simplify_proposition(proposition, domains),
+            Expression::Not(inner) => negate(inner.simplify(domains), domains),
+            Expression::And(clauses) => simplify_junction(clauses, true, domains),
+            Expression::Or(clauses) => simplify_junction(clauses, false, domains),
+            Expression::Xor(lhs, rhs) => Expression::Xor(
+                Box::new(lhs.simplify(domains)),
+                Box::new(rhs.simplify(domains)),
+            )
+            .propagate_constants(),
+            Expression::Implies(lhs, rhs) => Expression::Implies(
+                Box::new(lhs.simplify(domains)),
+                Box::new(rhs.simplify(domains)),
+            )
+            .propagate_constants(),
+        }
+    }
+
+    /// Converts the expression into a disjunction of conjunctions of propositions.
+    ///
+    /// Propositions on the same variable are merged within each conjunction (see
+    /// [Expression::simplify] for the meaning of `domains`), and conjunctions that cannot be
+    /// satisfied are removed. Note that the result can be exponentially larger than the
+    /// original expression.
+    pub fn to_dnf(&self, domains: &HashMap<String, Vec<T>>) -> Expression<T> {
+        let clauses = dnf_clauses(&to_nnf(self, false), domains);
+
+        if clauses.iter().any(|clause| clause.is_empty()) {
+            return Expression::mk_true();
+        }
+
+        unwrap_single(
+            clauses
+                .into_iter()
+                .map(|clause| {
+                    unwrap_single(
+                        clause.into_iter().map(Expression::Terminal).collect(),
+                        Expression::And,
+                    )
+                })
+                .collect(),
+            Expression::Or,
+        )
+    }
+
+    /// Converts the expression into a conjunction of disjunctions of propositions.
+    ///
+    /// This is computed as the negation of the [Expression::to_dnf] of the negated expression,
+    /// hence the same remarks apply.
+    pub fn to_cnf(&self, domains: &HashMap<String, Vec<T>>) -> Expression<T> {
+        let negated_clauses = dnf_clauses(&to_nnf(self, true), domains);
+
+        if negated_clauses.iter().any(|clause| clause.is_empty()) {
+            return Expression::mk_false();
+        }
+
+        unwrap_single(
+            negated_clauses
+                .into_iter()
+                .map(|clause| {
+                    unwrap_single(
+                        clause
+                            .into_iter()
+                            .map(|literal| Expression::Terminal(negate_proposition(&literal)))
+                            .collect(),
+                        Expression::Or,
+                    )
+                })
+                .collect(),
+            Expression::And,
+        )
+    }
+}
+
+fn simplify_proposition<T: Clone + Ord>(
+    proposition: &Proposition<T>,
+    domains: &HashMap<String, Vec<T>>,
+) -> Expression<T> {
+    match domains.get(&proposition.variable) {
+        Some(domain) => {
+            let allowed = domain
+                .iter()
+                .map(|value| proposition.evaluate(value))
+                .collect::<Vec<_>>();
+            values_to_expression(&proposition.variable, domain, &allowed)
+        }
+        None => Expression::Terminal(proposition.clone()),
+    }
+}
+
+/// Negates an already simplified expression.
+fn negate<T: Clone + Ord>(
+    expression: Expression<T>,
+    domains: &HashMap<String, Vec<T>>,
+) -> Expression<T> {
+    if let Some(value) = expression.as_constant() {
+        return if value {
+            Expression::mk_false()
+        } else {
+            Expression::mk_true()
+        };
+    }
+
+    match expression {
+        Expression::Not(inner) => *inner,
+        Expression::Terminal(proposition) => {
+            simplify_proposition(&negate_proposition(&proposition), domains)
+        }
+        other => Expression::Not(Box::new(other)),
+    }
+}
+
+fn simplify_junction<T: Clone + Ord>(
+    clauses: &[Expression<T>],
+    is_and: bool,
+    domains: &HashMap<String, Vec<T>>,
+) -> Expression<T> {
+    let absorbing = if is_and {
+        Expression::mk_false
+    } else {
+        Expression::mk_true
+    };
+
+    // simplify and flatten the nested junctions of the same kind
+    let mut items = Vec::new();
+    for clause in clauses {
+        let clause = clause.simplify(domains);
+        match clause.as_constant() {
+            Some(value) if value == is_and => continue,
+            Some(_) => return absorbing(),
+            None => {}
+        }
+        push_flattened(&mut items, clause, is_and);
+    }
+
+    // merge propositions on the same variable; the merged proposition takes the place of the
+    // first proposition on that variable
+    let mut merged_values: HashMap<String, (usize, Vec<bool>)> = HashMap::new();
+    let mut kept = Vec::<Option<Expression<T>>>::new();
+    for item in items {
+        let domain = match &item {
+            Expression::Terminal(proposition) => domains.get(&proposition.variable),
+            _ => None,
+        };
+        let (Some(domain), Expression::Terminal(proposition)) = (domain, &item) else {
+            kept.push(Some(item));
+            continue;
+        };
+
+        let allowed = domain.iter().map(|value| proposition.evaluate(value));
+        match merged_values.get_mut(&proposition.variable) {
+            Some((_, acc)) => acc.iter_mut().zip(allowed).for_each(|(acc, allowed)| {
+                *acc = if is_and {
+                    *acc && allowed
+                } else {
+                    *acc || allowed
+                }
+            }),
+            None => {
+                merged_values.insert(
+                    proposition.variable.clone(),
+                    (kept.len(), allowed.collect()),
+                );
+                kept.push(None);
+            }
+        }
+    }
+    for (variable, (position, allowed)) in merged_values {
+        kept[position] = Some(values_to_expression(
+            &variable,
+            &domains[&variable],
+            &allowed,
+        ));
+    }
+
+    let mut result = Vec::new();
+    for item in kept.into_iter().flatten() {
+        match item.as_constant() {
+            Some(value) if value == is_and => continue,
+            Some(_) => return absorbing(),
+            None => {}
+        }
+        let mut flattened = Vec::new();
+        push_flattened(&mut flattened, item, is_and);
+        for item in flattened {
+            if !result.contains(&item) {
+                result.push(item);
+            }
+        }
+    }
+
+    unwrap_single(
+        result,
+        if is_and {
+            Expression::And
+        } else {
+            Expression::Or
+        },
+    )
+}
+
+fn push_flattened<T>(items: &mut Vec<Expression<T>>, item: Expression<T>, is_and: bool) {
+    match item {
+        Expression::And(inner) if is_and => items.extend(inner),
+        Expression::Or(inner) if !is_and => items.extend(inner),
+        other => items.push(other),
+    }
+}
+
+/// Converts the expression into negation normal form, i.e. an expression with only
+/// conjunctions, disjunctions and propositions.
+fn to_nnf<T: Clone>(expression: &Expression<T>, negated: bool) -> Expression<T> {
+    match expression {
+        Expression::Terminal(proposition) if negated => {
+            Expression::Terminal(negate_proposition(proposition))
+        }
+        Expression::Terminal(proposition) => Expression::Terminal(proposition.clone()),
+        Expression::Not(inner) => to_nnf(inner, !negated),
+        Expression::And(clauses) | Expression::Or(clauses) => {
+            let clauses = clauses
+                .iter()
+                .map(|clause| to_nnf(clause, negated))
+                .collect();
+            if matches!(expression, Expression::And(_)) != negated {
+                Expression::And(clauses)
+            } else {
+                Expression::Or(clauses)
+            }
+        }
+        Expression::Xor(lhs, rhs) => {
+            // `lhs ^ rhs` is `(lhs & !rhs) | (!lhs & rhs)`, the negation is `lhs <=> rhs`
+            Expression::Or(vec![
+                Expression::And(vec![to_nnf(lhs, false), to_nnf(rhs, !negated)]),
+                Expression::And(vec![to_nnf(lhs, true), to_nnf(rhs, negated)]),
+            ])
+        }
+        Expression::Implies(lhs, rhs) if negated => {
+            Expression::And(vec![to_nnf(lhs, false), to_nnf(rhs, true)])
+        }
+        Expression::Implies(lhs, rhs) => {
+            Expression::Or(vec![to_nnf(lhs, true), to_nnf(rhs, false)])
+        }
+    }
+}
+
+/// Computes the clauses of the DNF of an expression in negation normal form.
+fn dnf_clauses<T: Clone + Ord>(
+    nnf: &Expression<T>,
+    domains: &HashMap<String, Vec<T>>,
+) -> Vec<Vec<Proposition<T>>> {
+    let mut clauses = match nnf {
+        Expression::Terminal(proposition) => normalize_clause(vec![proposition.clone()], domains),
+        Expression::And(conjuncts) => conjuncts.iter().fold(vec![Vec::new()], |acc, conjunct| {
+            let conjunct_clauses = dnf_clauses(conjunct, domains);
+            let mut product = Vec::new();
+            for lhs in &acc {
+                for rhs in &conjunct_clauses {
+                    let clause = lhs.iter().chain(rhs).cloned().collect();
+                    product.extend(normalize_clause(clause, domains));
+                }
+            }
+            dedup(product)
+        }),
+        Expression::Or(disjuncts) => disjuncts
+            .iter()
+            .flat_map(|disjunct| dnf_clauses(disjunct, domains))
+            .collect(),
+        _ => unreachable!("expression must be in negation normal form"),
+    };
+    clauses = dedup(clauses);
+    clauses
+}
+
+/// Merges the propositions on the same variable within a conjunctive clause. Returns no
+/// clause if the clause is unsatisfiable, or multiple clauses if the values allowed for some
+/// variable do not form an interval.
+fn normalize_clause<T: Clone + Ord>(
+    clause: Vec<Proposition<T>>,
+    domains: &HashMap<String, Vec<T>>,
+) -> Vec<Vec<Proposition<T>>> {
+    let mut variables = Vec::<&str>::new();
+    clause.iter().for_each(|literal| {
+        if !variables.contains(&literal.variable.as_str()) {
+            variables.push(literal.variable.as_str());
+        }
+    });
+
+    let mut result = vec![Vec::new()];
+    for variable in variables {
+        let literals = clause.iter().filter(|literal| literal.variable == variable);
+
+        let Some(domain) = domains.get(variable) else {
+            for literal in literals {
+                result
+                    .iter_mut()
+                    .for_each(|clause: &mut Vec<Proposition<T>>| {
+                        if !clause.contains(literal) {
+                            clause.push(literal.clone())
+                        }
+                    });
+            }
+            continue;
+        };
+
+        let allowed = domain
+            .iter()
+            .map(|value| literals.clone().all(|literal| literal.evaluate(value)))
+            .collect::<Vec<_>>();
+
+        let alternatives = if let Some(excluded) = single_inner_gap(&allowed) {
+            vec![vec![Proposition::new(
+                CmpOp::Neq,
+                variable.to_string(),
+                domain[excluded].clone(),
+            )]]
+        } else {
+            value_blocks(&allowed)
+                .into_iter()
+                .map(|block| interval_propositions(variable, domain, block))
+                .collect::<Vec<_>>()
+        };
+
+        result = result
+            .into_iter()
+            .flat_map(|clause| {
+                alternatives.iter().map(move |alternative| {
+                    clause
+                        .iter()
+                        .chain(alternative)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+    }
+
+    result
+}
+
+/// Converts the set of values (given as a mask over the sorted `domain`) into an expression
+/// which is satisfied exactly by these values.
+pub(crate) fn values_to_expression<T: Clone + Ord>(
+    variable: &str,
+    domain: &[T],
+    allowed: &[bool],
+) -> Expression<T> {
+    if allowed.iter().all(|it| *it) {
+        return Expression::mk_true();
+    }
+    if let Some(excluded) = single_inner_gap(allowed) {
+        return Expression::Terminal(Proposition::new(
+            CmpOp::Neq,
+            variable.to_string(),
+            domain[excluded].clone(),
+        ));
+    }
+
+    let blocks = value_blocks(allowed)
+        .into_iter()
+        .map(|block| {
+            unwrap_single(
+                interval_propositions(variable, domain, block)
+                    .into_iter()
+                    .map(Expression::Terminal)
+                    .collect(),
+                Expression::And,
+            )
+        })
+        .collect();
+
+    unwrap_single(blocks, Expression::Or)
+}
+
+/// If exactly one value is not allowed and it is neither the smallest nor the largest value
+/// of the domain, returns its index. Such a set is best described using `!=`.
+fn single_inner_gap(allowed: &[bool]) -> Option<usize> {
+    let mut excluded = allowed.iter().enumerate().filter(|(_, it)| !**it);
+    match (excluded.next(), excluded.next()) {
+        (Some((idx, _)), None) if idx != 0 && idx != allowed.len() - 1 => Some(idx),
+        _ => None,
+    }
+}
+
+/// The maximal contiguous runs of `true` in `allowed`, as inclusive index ranges.
+fn value_blocks(allowed: &[bool]) -> Vec<(usize, usize)> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    for (idx, is_allowed) in allowed.iter().chain(Some(&false)).enumerate() {
+        match (start, is_allowed) {
+            (None, true) => start = Some(idx),
+            (Some(block_start), false) => {
+                blocks.push((block_start, idx - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// Propositions which together describe the values `domain[first..=last]`.
+fn interval_propositions<T: Clone>(
+    variable: &str,
+    domain: &[T],
+    (first, last): (usize, usize),
+) -> Vec<Proposition<T>> {
+    let mk = |operator, idx: usize| {
+        Proposition::new(operator, variable.to_string(), domain[idx].clone())
+    };
+
+    match (first == 0, last == domain.len() - 1) {
+        (true, true) => Vec::new(),
+        _ if first == last => vec![mk(CmpOp::Eq, first)],
+        (true, false) => vec![mk(CmpOp::Leq, last)],
+        (false, true) => vec![mk(CmpOp::Geq, first)],
+        (false, false) => vec![mk(CmpOp::Geq, first), mk(CmpOp::Leq, last)],
+    }
+}
+
+fn negate_proposition<T: Clone>(proposition: &Proposition<T>) -> Proposition<T> {
+    Proposition::new(
+        proposition.comparison_operator.negate(),
+        proposition.variable.clone(),
+        proposition.value.clone(),
+    )
+}
+
+fn unwrap_single<T>(
+    mut items: Vec<Expression<T>>,
+    junction: fn(Vec<Expression<T>>) -> Expression<T>,
+) -> Expression<T> {
+    if items.len() == 1 {
+        items.remove(0)
+    } else {
+        junction(items)
+    }
+}
+
+fn dedup<I: PartialEq>(items: Vec<I>) -> Vec<I> {
+    items.into_iter().fold(Vec::new(), |mut acc, item| {
+        if !acc.contains(&item) {
+            acc.push(item);
+        }
+        acc
+    })
+}
@@ -3,8 +3,11 @@ pub mod prelude;
 pub mod test_utils; // TODO:
                     //   Once this becomes a library, this needs to become private, but for now it is convenient
                     //   to have it accessible from outside binaries.
+mod explicit;
 mod expression_components;
+mod model_checking;
 mod symbolic_domains;
+mod transformations;
 mod update;
 mod utils;
 mod xml_parsing;
@@ -0,0 +1,120 @@
+use biodivine_lib_bdd::Bdd;
+use num_bigint::BigInt;
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd, update::update_fn::SmartSystemUpdateFn,
+};
+
+use super::{ctl::CtlChecker, hctl::HctlChecker};
+
+/// The basins of a single attractor, together with their sizes.
+///
+/// The sizes count the states regardless of the colors (see
+/// [SmartSystemUpdateFn::count_states_exact]), and the ratios are relative to the number
+/// of all the valid states of the system.
+#[derive(Clone, Debug)]
+pub struct Basin {
+    pub attractor: Bdd,
+    /// The states from which the attractor is reachable.
+    pub weak: Bdd,
+    /// The states from which no other attractor is reachable.
+    pub strong: Bdd,
+    pub weak_size: BigInt,
+    pub strong_size: BigInt,
+    pub weak_ratio: f64,
+    pub strong_ratio: f64,
+}
+
+/// Computes the basins of attraction of the attractors of a [SmartSystemUpdateFn].
+///
+/// The weak basin of an attractor is its backward reachable set, while its strong basin
+/// only contains the states from which this attractor is the only reachable one. Every
+/// state is in the weak basin of some attractor, but the states from which several
+/// attractors are reachable are in no strong basin.
+pub struct BasinAnalysis<'a, D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    system: &'a SmartSystemUpdateFn<D, T>,
+    ctl: CtlChecker<'a, D, T>,
+    /// all valid states (and colors)
+    universe: Bdd,
+    /// the individual attractors
+    attractors: Vec<Bdd>,
+    /// the states (and colors) of all the attractors
+    attractor_states: Bdd,
+}
+
+impl<'a, D, T> BasinAnalysis<'a, D, T>
+where
+    D: SymbolicDomainOrd<T>,
+    T: Clone + PartialEq,
+{
+    pub fn new(system: &'a SmartSystemUpdateFn<D, T>) -> Self {
+        let attractors = HctlChecker::new(system).attractors();
+        let universe = system.unit_vertex_set();
+        let attractor_states = attractors.iter().fold(
+            system.get_bdd_variable_set().mk_false(),
+            |result, attractor| result.or(attractor),
+        );
+        Self {
+            system,
+            ctl: CtlChecker::new(system),
+            universe,
+            attractors,
+            attractor_states,
+        }
+    }
+
+    /// The states of all the attractors of the system.
+    pub fn attractor_states(&self) -> &Bdd {
+        &self.attractor_states
+    }
+
+    /// The individual attractors of the system, each given by its states (and the colors
+    /// in which these states form an attractor).
+    pub fn attractors(&self) -> Vec<Bdd> {
+        self.attractors.clone()
+    }
+
+    /// The states from which the `attractor` is reachable, i.e. the backward reachable set
+    /// of the `attractor`.
+    pub fn weak_basin(&self, attractor: &Bdd) -> Bdd {
+        self.ctl.reach_bwd(attractor, &self.universe)
+    }
+
+    /// The states from which the `attractor` is the only reachable attractor.
+    pub fn strong_basin(&self, attractor: &Bdd) -> Bdd {
+        self.strong_within(attractor, &self.weak_basin(attractor))
+    }
+
+    /// The states of the `weak` basin of the `attractor` which cannot reach other attractors.
+    fn strong_within(&self, attractor: &Bdd, weak: &Bdd) -> Bdd {
+        let others = self.attractor_states.and_not(attractor);
+        weak.and_not(&self.ctl.reach_bwd(&others, &self.universe))
+    }
+
+    /// The basins of the `attractor`, together with their sizes.
+    pub fn basin(&self, attractor: &Bdd) -> Basin {
+        let total = self.system.count_states(&self.universe);
+        let weak = self.weak_basin(attractor);
+        let strong = self.strong_within(attractor, &weak);
+        Basin {
+            attractor: attractor.clone(),
+            weak_size: self.system.count_states_exact(&weak),
+            strong_size: self.system.count_states_exact(&strong),
+            weak_ratio: self.system.count_states(&weak) / total,
+            strong_ratio: self.system.count_states(&strong) / total,
+            weak,
+            strong,
+        }
+    }
+
+    /// The basins of all the [Self::attractors] of the system.
+    pub fn basins(&self) -> Vec<Basin> {
+        self.attractors()
+            .iter()
+            .map(|attractor| self.basin(attractor))
+            .collect()
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, fmt::Display};
+
+use biodivine_lib_bdd::Bdd;
+use thiserror::Error;
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    update::{
+        perturbation::Perturbation,
+        state::{State, StateError},
+        update_fn::SmartSystemUpdateFn,
+    },
+};
+
+use super::ctl::CtlChecker;
+
+/// Limits the search of [PhenotypeControl].
+#[derive(Clone, Debug)]
+pub struct ControlOptions {
+    /// The largest number of fixed variables in a solution.
+    pub max_size: usize,
+    /// The variables which can be fixed, or `None` to allow all system variables.
+    pub variables: Option<Vec<String>>,
+}
+
+impl Default for ControlOptions {
+    fn default() -> Self {
+        Self {
+            max_size: 2,
+            variables: None,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ControlError {
+    UnknownVariable(String),
+    InvalidSource(#[from] StateError),
+}
+
+impl Display for ControlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ControlError::UnknownVariable(name) => {
+                write!(f, "Variable {} is not a system variable", name)
+            }
+            ControlError::InvalidSource(error) => write!(f, "Invalid source state: {}", error),
+        }
+    }
+}
+
+/// Searches for the minimal sets of variable fixations (i.e. [Perturbation::Fixed]) which
+/// drive the system into the `phenotype`, i.e. after which every relevant attractor lies
+/// within the `phenotype`.
+///
+/// The candidates are enumerated by increasing size, and every candidate is verified using
+/// symbolic reachability. The supersets of the solutions found so far are skipped, so all
+/// the returned solutions are minimal w.r.t. inclusion. The solutions are sorted by size.
+///
+/// For a system with parameters, a solution has to work for all the colors.
+pub struct PhenotypeControl<'a, D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    system: &'a SmartSystemUpdateFn<D, T>,
+    /// the valid states (and colors) in the phenotype
+    phenotype: Bdd,
+    /// the states (and colors) from which all reachable attractors lie in the phenotype
+    controlled: Bdd,
+}
+
+impl<'a, D, T> PhenotypeControl<'a, D, T>
+where
+    D: SymbolicDomainOrd<T> + Clone,
+    T: Clone + PartialEq + Display,
+{
+    /// The `phenotype` is a set of states, e.g. given by a partial specification using
+    /// [SmartSystemUpdateFn::initial_states].
+    pub fn new(system: &'a SmartSystemUpdateFn<D, T>, phenotype: &Bdd) -> Self {
+        let universe = system.unit_vertex_set();
+        let phenotype = phenotype.and(&universe);
+        Self {
+            system,
+            controlled: Self::controlled(&CtlChecker::new(system), &universe, &phenotype),
+            phenotype,
+        }
+    }
+
+    /// The states from which all reachable attractors lie in the `target` set.
+    ///
+    /// An attractor containing a state outside the `target` is reachable from a state iff
+    /// some state reachable from it satisfies `AG EF !target` (every attractor state does,
+    /// and every attractor reachable from such a state contains a state outside the target).
+    fn controlled(checker: &CtlChecker<'_, D, T>, universe: &Bdd, target: &Bdd) -> Bdd {
+        let escaping = checker.ag(&checker.ef(&universe.and_not(target)));
+        checker.ag(&universe.and_not(&escaping))
+    }
+
+    /// The minimal sets of variables and their values such that when they are fixed
+    /// permanently, all attractors of the perturbed system lie in the phenotype.
+    pub fn permanent(
+        &self,
+        options: &ControlOptions,
+    ) -> Result<Vec<Vec<(String, T)>>, ControlError> {
+        self.search(options, None, |fixations| {
+            let perturbed = self.perturbed(fixations);
+            let universe = perturbed.unit_vertex_set();
+            let checker = CtlChecker::new(&perturbed);
+            let escaping = checker.ag(&checker.ef(&universe.and_not(&self.phenotype)));
+            escaping.is_false()
+        })
+    }
+
+    /// The minimal sets of variables and their values such that when they are set in the
+    /// `source` state (once, after which the system evolves freely), all the reachable
+    /// attractors lie in the phenotype.
+    pub fn one_step(
+        &self,
+        source: &State<T>,
+        options: &ControlOptions,
+    ) -> Result<Vec<Vec<(String, T)>>, ControlError> {
+        self.system.encode_state(source)?;
+        self.search(options, Some(source), |fixations| {
+            let mut values = source.values().to_vec();
+            for (variable, value) in fixations {
+                let idx = self
+                    .system
+                    .variable_index(variable)
+                    .expect("known variable");
+                values[idx] = value.clone();
+            }
+            let perturbed_source = self
+                .system
+                .encode_state(&State::new(values))
+                .expect("valid state")
+                .and(&self.system.unit_colors());
+            perturbed_source.and_not(&self.controlled).is_false()
+        })
+    }
+
+    /// The minimal sets of variables and their values such that when they are fixed in the
+    /// `source` state until the perturbed system reaches an attractor, and then released,
+    /// all the attractors reachable afterwards lie in the phenotype.
+    pub fn temporary(
+        &self,
+        source: &State<T>,
+        options: &ControlOptions,
+    ) -> Result<Vec<Vec<(String, T)>>, ControlError> {
+        let source = self.system.encode_state(source)?;
+        self.search(options, None, |fixations| {
+            let perturbed = self.perturbed(fixations);
+            let universe = perturbed.unit_vertex_set();
+            // the perturbation is released in an attractor of the perturbed system, so
+            // these must all lie in the states controlled by the unperturbed system
+            let checker = CtlChecker::new(&perturbed);
+            let released_controlled = Self::controlled(&checker, &universe, &self.controlled);
+            source
+                .and(&universe)
+                .and_not(&released_controlled)
+                .is_false()
+        })
+    }
+
+    fn perturbed(&self, fixations: &[(String, T)]) -> SmartSystemUpdateFn<D, T> {
+        let perturbations = fixations
+            .iter()
+            .map(|(variable, value)| (variable.clone(), Perturbation::Fixed(value.clone())))
+            .collect::<HashMap<_, _>>();
+        self.system
+            .perturbed(&perturbations)
+            .expect("the values are taken from the domains")
+    }
+
+    /// Enumerates the candidates by increasing size and returns the minimal ones accepted
+    /// by `is_solution`. With a `source` state, fixing a variable to its value in the
+    /// `source` has no effect, so such candidates are skipped.
+    fn search(
+        &self,
+        options: &ControlOptions,
+        source: Option<&State<T>>,
+        is_solution: impl Fn(&[(String, T)]) -> bool,
+    ) -> Result<Vec<Vec<(String, T)>>, ControlError> {
+        let variables = match &options.variables {
+            Some(variables) => variables.clone(),
+            None => self.system.get_system_variables(),
+        };
+        // the possible fixations of every variable
+        let fixations = variables
+            .iter()
+            .map(|variable| {
+                let domain = self
+                    .system
+                    .get_domain(variable)
+                    .ok_or_else(|| ControlError::UnknownVariable(variable.clone()))?;
+                let mut values = domain.get_all_possible_values();
+                values.sort_by(D::cmp);
+                if let Some(source) = source {
+                    let current = self
+                        .system
+                        .state_value(source, variable)
+                        .expect("known variable");
+                    values.retain(|value| value != current);
+                }
+                Ok(values
+                    .into_iter()
+                    .map(|value| (variable.clone(), value))
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>, ControlError>>()?;
+
+        let mut solutions = Vec::<Vec<(String, T)>>::new();
+        for size in 0..=options.max_size.min(fixations.len()) {
+            for candidate in candidates(&fixations, size) {
+                let is_superset = solutions
+                    .iter()
+                    .any(|solution| solution.iter().all(|it| candidate.contains(it)));
+                if !is_superset && is_solution(&candidate) {
+                    solutions.push(candidate);
+                }
+            }
+        }
+        Ok(solutions)
+    }
+}
+
+/// All the ways to pick `size` items, each from a different group, in lexicographic order.
+fn candidates<I: Clone>(groups: &[Vec<I>], size: usize) -> Vec<Vec<I>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    (0..groups.len())
+        .flat_map(|first| {
+            candidates(&groups[first + 1..], size - 1)
+                .into_iter()
+                .flat_map(move |rest| {
+                    groups[first].iter().map(move |item| {
+                        let mut candidate = vec![item.clone()];
+                        candidate.extend(rest.iter().cloned());
+                        candidate
+                    })
+                })
+        })
+        .collect()
+}
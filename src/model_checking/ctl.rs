@@ -0,0 +1,334 @@
+use std::fmt::Display;
+
+use biodivine_lib_bdd::Bdd;
+use thiserror::Error;
+
+use crate::{
+    expression_components::proposition::{ComparisonOperator, Proposition},
+    symbolic_domains::symbolic_domain::{DomainError, SymbolicDomainEq},
+    update::update_fn::SmartSystemUpdateFn,
+};
+
+/// A CTL formula whose atomic propositions compare a system variable with a value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CtlFormula<T> {
+    True,
+    False,
+    Atom(Proposition<T>),
+    Not(Box<CtlFormula<T>>),
+    And(Box<CtlFormula<T>>, Box<CtlFormula<T>>),
+    Or(Box<CtlFormula<T>>, Box<CtlFormula<T>>),
+    Implies(Box<CtlFormula<T>>, Box<CtlFormula<T>>),
+    /// Some successor satisfies the formula.
+    Ex(Box<CtlFormula<T>>),
+    /// All successors satisfy the formula.
+    Ax(Box<CtlFormula<T>>),
+    /// Some path reaches a state satisfying the formula.
+    Ef(Box<CtlFormula<T>>),
+    /// All paths reach a state satisfying the formula.
+    Af(Box<CtlFormula<T>>),
+    /// Some path stays in the states satisfying the formula forever.
+    Eg(Box<CtlFormula<T>>),
+    /// All reachable states satisfy the formula.
+    Ag(Box<CtlFormula<T>>),
+    /// `E[lhs U rhs]`, i.e. some path satisfies `lhs` until it reaches `rhs`.
+    Eu(Box<CtlFormula<T>>, Box<CtlFormula<T>>),
+    /// `A[lhs U rhs]`, i.e. all paths satisfy `lhs` until they reach `rhs`.
+    Au(Box<CtlFormula<T>>, Box<CtlFormula<T>>),
+}
+
+impl<T> CtlFormula<T> {
+    pub fn atom(variable: &str, comparison_operator: ComparisonOperator, value: T) -> Self {
+        CtlFormula::Atom(Proposition::new(
+            comparison_operator,
+            variable.to_string(),
+            value,
+        ))
+    }
+
+    pub fn negate(inner: Self) -> Self {
+        CtlFormula::Not(Box::new(inner))
+    }
+
+    pub fn and(lhs: Self, rhs: Self) -> Self {
+        CtlFormula::And(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn or(lhs: Self, rhs: Self) -> Self {
+        CtlFormula::Or(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn implies(lhs: Self, rhs: Self) -> Self {
+        CtlFormula::Implies(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn ex(inner: Self) -> Self {
+        CtlFormula::Ex(Box::new(inner))
+    }
+
+    pub fn ax(inner: Self) -> Self {
+        CtlFormula::Ax(Box::new(inner))
+    }
+
+    pub fn ef(inner: Self) -> Self {
+        CtlFormula::Ef(Box::new(inner))
+    }
+
+    pub fn af(inner: Self) -> Self {
+        CtlFormula::Af(Box::new(inner))
+    }
+
+    pub fn eg(inner: Self) -> Self {
+        CtlFormula::Eg(Box::new(inner))
+    }
+
+    pub fn ag(inner: Self) -> Self {
+        CtlFormula::Ag(Box::new(inner))
+    }
+
+    pub fn eu(lhs: Self, rhs: Self) -> Self {
+        CtlFormula::Eu(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn au(lhs: Self, rhs: Self) -> Self {
+        CtlFormula::Au(Box::new(lhs), Box::new(rhs))
+    }
+}
+
+impl<T: Display> Display for CtlFormula<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtlFormula::True => write!(f, "true"),
+            CtlFormula::False => write!(f, "false"),
+            CtlFormula::Atom(proposition) => write!(f, "{}", proposition),
+            CtlFormula::Not(inner) => write!(f, "!({})", inner),
+            CtlFormula::And(lhs, rhs) => write!(f, "({}) & ({})", lhs, rhs),
+            CtlFormula::Or(lhs, rhs) => write!(f, "({}) | ({})", lhs, rhs),
+            CtlFormula::Implies(lhs, rhs) => write!(f, "({}) => ({})", lhs, rhs),
+            CtlFormula::Ex(inner) => write!(f, "EX ({})", inner),
+            CtlFormula::Ax(inner) => write!(f, "AX ({})", inner),
+            CtlFormula::Ef(inner) => write!(f, "EF ({})", inner),
+            CtlFormula::Af(inner) => write!(f, "AF ({})", inner),
+            CtlFormula::Eg(inner) => write!(f, "EG ({})", inner),
+            CtlFormula::Ag(inner) => write!(f, "AG ({})", inner),
+            CtlFormula::Eu(lhs, rhs) => write!(f, "E[({}) U ({})]", lhs, rhs),
+            CtlFormula::Au(lhs, rhs) => write!(f, "A[({}) U ({})]", lhs, rhs),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CtlError {
+    UnknownVariable(String),
+    /// A proposition uses a comparison that is not supported by the domain of its variable.
+    UnsupportedProposition {
+        variable: String,
+        #[source]
+        source: DomainError,
+    },
+}
+
+impl Display for CtlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtlError::UnknownVariable(name) => {
+                write!(f, "Variable {} is not a system variable", name)
+            }
+            CtlError::UnsupportedProposition { variable, source } => write!(
+                f,
+                "Cannot encode a proposition on variable {}: {}",
+                variable, source
+            ),
+        }
+    }
+}
+
+/// Evaluates CTL formulas on the asynchronous state-transition graph of a system.
+///
+/// Only the transitions which change the state are considered, except that every fixed point
+/// has a self-loop, so that every state has a successor (and every path is infinite). For a
+/// system with parameters, the results are colored sets, i.e. a (state, color) pair is in
+/// the result iff the state satisfies the formula in the model given by the color.
+pub struct CtlChecker<'a, D, T>
+where
+    D: SymbolicDomainEq<T>,
+{
+    system: &'a SmartSystemUpdateFn<D, T>,
+    variables: Vec<String>,
+    /// all valid states (and colors)
+    universe: Bdd,
+    /// the states (and colors) without any outgoing transition
+    fixed_points: Bdd,
+}
+
+impl<'a, D, T> CtlChecker<'a, D, T>
+where
+    D: SymbolicDomainEq<T>,
+{
+    pub fn new(system: &'a SmartSystemUpdateFn<D, T>) -> Self {
+        let variables = system.get_system_variables();
+        let universe = system.unit_vertex_set();
+        let can_transition = variables
+            .iter()
+            .fold(system.get_bdd_variable_set().mk_false(), |acc, variable| {
+                acc.or(&system.predecessors_async_exclude_loops(variable, &universe))
+            });
+        Self {
+            system,
+            variables,
+            fixed_points: universe.and_not(&can_transition),
+            universe,
+        }
+    }
+
+    /// The set of states (colored, if the system has parameters) satisfying the `formula`.
+    pub fn check(&self, formula: &CtlFormula<T>) -> Result<Bdd, CtlError> {
+        let check = |formula| self.check(formula);
+        Ok(match formula {
+            CtlFormula::True => self.universe.clone(),
+            CtlFormula::False => self.system.get_bdd_variable_set().mk_false(),
+            CtlFormula::Atom(proposition) => self.atom(proposition)?,
+            CtlFormula::Not(inner) => self.universe.and_not(&check(inner)?),
+            CtlFormula::And(lhs, rhs) => check(lhs)?.and(&check(rhs)?),
+            CtlFormula::Or(lhs, rhs) => check(lhs)?.or(&check(rhs)?),
+            CtlFormula::Implies(lhs, rhs) => self.universe.and(&check(lhs)?.imp(&check(rhs)?)),
+            CtlFormula::Ex(inner) => self.ex(&check(inner)?),
+            CtlFormula::Ax(inner) => self.ax(&check(inner)?),
+            CtlFormula::Ef(inner) => self.ef(&check(inner)?),
+            CtlFormula::Af(inner) => self.af(&check(inner)?),
+            CtlFormula::Eg(inner) => self.eg(&check(inner)?),
+            CtlFormula::Ag(inner) => self.ag(&check(inner)?),
+            CtlFormula::Eu(lhs, rhs) => self.eu(&check(lhs)?, &check(rhs)?),
+            CtlFormula::Au(lhs, rhs) => self.au(&check(lhs)?, &check(rhs)?),
+        })
+    }
+
+    /// The states in which the `proposition` holds.
+    pub fn atom(&self, proposition: &Proposition<T>) -> Result<Bdd, CtlError> {
+        let domain = self
+            .system
+            .get_domain(&proposition.variable)
+            .ok_or_else(|| CtlError::UnknownVariable(proposition.variable.clone()))?;
+        let encoded = domain
+            .encode_comparison(
+                self.system.get_bdd_variable_set(),
+                proposition.comparison_operator,
+                &proposition.value,
+            )
+            .map_err(|source| CtlError::UnsupportedProposition {
+                variable: proposition.variable.clone(),
+                source,
+            })?;
+        Ok(encoded.and(&self.universe))
+    }
+
+    /// The states with some successor in the `set`.
+    pub fn ex(&self, set: &Bdd) -> Bdd {
+        self.variables
+            .iter()
+            .fold(set.and(&self.fixed_points), |acc, variable| {
+                acc.or(&self.system.predecessors_async_exclude_loops(variable, set))
+            })
+    }
+
+    /// The states reachable from the `initial` states (including them), e.g. from the
+    /// [SmartSystemUpdateFn::initial_states].
+    pub fn reachable_from(&self, initial: &Bdd) -> Bdd {
+        self.reach_fwd(initial, &self.universe)
+    }
+
+    /// The states of the `universe` reachable from the `initial` states (including them)
+    /// by the paths which never leave the `universe`.
+    pub fn reach_fwd(&self, initial: &Bdd, universe: &Bdd) -> Bdd {
+        self.saturate(initial.and(universe), |variable, set| {
+            self.system.successors_async(variable, set).and(universe)
+        })
+    }
+
+    /// The states of the `universe` from which the `target` states are reachable (including
+    /// them) by the paths which never leave the `universe`.
+    pub fn reach_bwd(&self, target: &Bdd, universe: &Bdd) -> Bdd {
+        self.saturate(target.and(universe), |variable, set| {
+            self.system
+                .predecessors_async_exclude_loops(variable, set)
+                .and(universe)
+        })
+    }
+
+    /// Extends the `set` by the `step` of the individual variables until a fixed point is
+    /// reached. After every extension, the variables are processed from the last one again,
+    /// which keeps the intermediate results much smaller than applying all the steps at once.
+    fn saturate(&self, mut set: Bdd, step: impl Fn(&str, &Bdd) -> Bdd) -> Bdd {
+        'saturate: loop {
+            for variable in self.variables.iter().rev() {
+                let extension = step(variable, &set);
+                if !extension.imp(&set).is_true() {
+                    set = set.or(&extension);
+                    continue 'saturate;
+                }
+            }
+            return set;
+        }
+    }
+
+    /// The states whose successors are all in the `set`.
+    pub fn ax(&self, set: &Bdd) -> Bdd {
+        self.universe.and_not(&self.ex(&self.universe.and_not(set)))
+    }
+
+    /// The states from which some path reaches the `set`.
+    pub fn ef(&self, set: &Bdd) -> Bdd {
+        self.eu(&self.universe, set)
+    }
+
+    /// The states from which all paths reach the `set`.
+    pub fn af(&self, set: &Bdd) -> Bdd {
+        self.au(&self.universe, set)
+    }
+
+    /// The states from which all reachable states are in the `set`.
+    pub fn ag(&self, set: &Bdd) -> Bdd {
+        self.universe.and_not(&self.ef(&self.universe.and_not(set)))
+    }
+
+    /// The least fixed point of `Z = target | (through & EX Z)`.
+    pub fn eu(&self, through: &Bdd, target: &Bdd) -> Bdd {
+        let mut result = target.and(&self.universe);
+        loop {
+            // the variables are processed one by one, which usually converges faster
+            // than computing the whole `EX` in every iteration
+            let extended = self.variables.iter().fold(result.clone(), |acc, variable| {
+                let predecessors = self.system.predecessors_async_exclude_loops(variable, &acc);
+                acc.or(&predecessors.and(through))
+            });
+            if extended == result {
+                return result;
+            }
+            result = extended;
+        }
+    }
+
+    /// The least fixed point of `Z = target | (through & AX Z)`.
+    pub fn au(&self, through: &Bdd, target: &Bdd) -> Bdd {
+        let mut result = target.and(&self.universe);
+        loop {
+            let extended = result.or(&through.and(&self.ax(&result)));
+            if extended == result {
+                return result;
+            }
+            result = extended;
+        }
+    }
+
+    /// The greatest fixed point of `Z = set & EX Z`.
+    pub fn eg(&self, set: &Bdd) -> Bdd {
+        let mut result = set.and(&self.universe);
+        loop {
+            let restricted = result.and(&self.ex(&result));
+            if restricted == result {
+                return result;
+            }
+            result = restricted;
+        }
+    }
+}
@@ -0,0 +1,736 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use biodivine_lib_bdd::{Bdd, BddVariable};
+use thiserror::Error;
+
+use crate::{
+    expression_components::proposition::{ComparisonOperator, Proposition},
+    symbolic_domains::symbolic_domain::SymbolicDomainEq,
+    update::update_fn::SmartSystemUpdateFn,
+};
+
+use super::ctl::{CtlChecker, CtlError};
+
+/// A formula of the hybrid extension of CTL, i.e. CTL with state variables.
+///
+/// A state variable is bound to a single state by `↓x` (the current state), `∃x` or `∀x`
+/// (some or every state), and it can be used as an atom (which holds exactly in the bound
+/// state) or to jump to the bound state with `@x`. This allows for queries about
+/// attractors, e.g. the attractor states are exactly those satisfying `↓x. AG EF x`.
+///
+/// Formulas can be parsed from strings (see the [FromStr] implementation), and they are
+/// displayed in the same syntax.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HctlFormula<T> {
+    True,
+    False,
+    Atom(Proposition<T>),
+    /// Holds exactly in the state bound to the state variable.
+    StateVariable(String),
+    Not(Box<HctlFormula<T>>),
+    And(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    Or(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    Implies(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    Iff(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    Ex(Box<HctlFormula<T>>),
+    Ax(Box<HctlFormula<T>>),
+    Ef(Box<HctlFormula<T>>),
+    Af(Box<HctlFormula<T>>),
+    Eg(Box<HctlFormula<T>>),
+    Ag(Box<HctlFormula<T>>),
+    Eu(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    Au(Box<HctlFormula<T>>, Box<HctlFormula<T>>),
+    /// `↓x. inner`, binds the state variable to the current state.
+    Bind(String, Box<HctlFormula<T>>),
+    /// `@x. inner`, evaluates the inner formula in the state bound to the state variable.
+    Jump(String, Box<HctlFormula<T>>),
+    /// `∃x. inner`, the inner formula holds for some binding of the state variable.
+    Exists(String, Box<HctlFormula<T>>),
+    /// `∀x. inner`, the inner formula holds for every binding of the state variable.
+    Forall(String, Box<HctlFormula<T>>),
+}
+
+impl<T> HctlFormula<T> {
+    pub fn atom(variable: &str, comparison_operator: ComparisonOperator, value: T) -> Self {
+        HctlFormula::Atom(Proposition::new(
+            comparison_operator,
+            variable.to_string(),
+            value,
+        ))
+    }
+
+    pub fn state_variable(name: &str) -> Self {
+        HctlFormula::StateVariable(name.to_string())
+    }
+
+    pub fn negate(inner: Self) -> Self {
+        HctlFormula::Not(Box::new(inner))
+    }
+
+    pub fn and(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::And(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn or(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::Or(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn implies(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::Implies(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn iff(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::Iff(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn ex(inner: Self) -> Self {
+        HctlFormula::Ex(Box::new(inner))
+    }
+
+    pub fn ax(inner: Self) -> Self {
+        HctlFormula::Ax(Box::new(inner))
+    }
+
+    pub fn ef(inner: Self) -> Self {
+        HctlFormula::Ef(Box::new(inner))
+    }
+
+    pub fn af(inner: Self) -> Self {
+        HctlFormula::Af(Box::new(inner))
+    }
+
+    pub fn eg(inner: Self) -> Self {
+        HctlFormula::Eg(Box::new(inner))
+    }
+
+    pub fn ag(inner: Self) -> Self {
+        HctlFormula::Ag(Box::new(inner))
+    }
+
+    pub fn eu(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::Eu(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn au(lhs: Self, rhs: Self) -> Self {
+        HctlFormula::Au(Box::new(lhs), Box::new(rhs))
+    }
+
+    pub fn bind(variable: &str, inner: Self) -> Self {
+        HctlFormula::Bind(variable.to_string(), Box::new(inner))
+    }
+
+    pub fn jump(variable: &str, inner: Self) -> Self {
+        HctlFormula::Jump(variable.to_string(), Box::new(inner))
+    }
+
+    pub fn exists(variable: &str, inner: Self) -> Self {
+        HctlFormula::Exists(variable.to_string(), Box::new(inner))
+    }
+
+    pub fn forall(variable: &str, inner: Self) -> Self {
+        HctlFormula::Forall(variable.to_string(), Box::new(inner))
+    }
+
+    /// `↓x. AG EF x`, i.e. the states of the attractors.
+    pub fn attractor() -> Self {
+        Self::bind("x", Self::ag(Self::ef(Self::state_variable("x"))))
+    }
+}
+
+impl<T: Display> Display for HctlFormula<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HctlFormula::True => write!(f, "true"),
+            HctlFormula::False => write!(f, "false"),
+            HctlFormula::Atom(proposition) => write!(f, "{}", proposition),
+            HctlFormula::StateVariable(name) => write!(f, "{}", name),
+            HctlFormula::Not(inner) => write!(f, "!({})", inner),
+            HctlFormula::And(lhs, rhs) => write!(f, "({}) & ({})", lhs, rhs),
+            HctlFormula::Or(lhs, rhs) => write!(f, "({}) | ({})", lhs, rhs),
+            HctlFormula::Implies(lhs, rhs) => write!(f, "({}) => ({})", lhs, rhs),
+            HctlFormula::Iff(lhs, rhs) => write!(f, "({}) <=> ({})", lhs, rhs),
+            HctlFormula::Ex(inner) => write!(f, "EX ({})", inner),
+            HctlFormula::Ax(inner) => write!(f, "AX ({})", inner),
+            HctlFormula::Ef(inner) => write!(f, "EF ({})", inner),
+            HctlFormula::Af(inner) => write!(f, "AF ({})", inner),
+            HctlFormula::Eg(inner) => write!(f, "EG ({})", inner),
+            HctlFormula::Ag(inner) => write!(f, "AG ({})", inner),
+            HctlFormula::Eu(lhs, rhs) => write!(f, "E[({}) U ({})]", lhs, rhs),
+            HctlFormula::Au(lhs, rhs) => write!(f, "A[({}) U ({})]", lhs, rhs),
+            HctlFormula::Bind(variable, inner) => write!(f, "↓{}. ({})", variable, inner),
+            HctlFormula::Jump(variable, inner) => write!(f, "@{}. ({})", variable, inner),
+            HctlFormula::Exists(variable, inner) => write!(f, "∃{}. ({})", variable, inner),
+            HctlFormula::Forall(variable, inner) => write!(f, "∀{}. ({})", variable, inner),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HctlError {
+    /// The query is not a well-formed formula. The `position` is a byte offset into the query.
+    Parse {
+        position: usize,
+        message: String,
+    },
+    UnboundStateVariable(String),
+    Ctl(#[from] CtlError),
+}
+
+impl Display for HctlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HctlError::Parse { position, message } => {
+                write!(f, "Invalid query at position {}: {}", position, message)
+            }
+            HctlError::UnboundStateVariable(name) => {
+                write!(f, "State variable {} is not bound", name)
+            }
+            HctlError::Ctl(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// a variable name, a value or a keyword
+    Word(String),
+    Symbol(&'static str),
+}
+
+/// Longer symbols go first, so that e.g. `<=>` is not split into `<=` and `>`.
+const SYMBOLS: [&str; 21] = [
+    "<=>", "=>", "<=", ">=", "!=", "==", "=", "<", ">", "!", "&", "|", "(", ")", "[", "]", ".",
+    ":", "↓", "∃", "∀",
+];
+
+fn tokenize(query: &str) -> Result<Vec<(usize, Token)>, HctlError> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < query.len() {
+        let rest = &query[position..];
+        let char = rest.chars().next().expect("not at the end");
+        if char.is_whitespace() {
+            position += char.len_utf8();
+        } else if char.is_alphanumeric() || char == '_' {
+            let len = rest
+                .find(|it: char| !(it.is_alphanumeric() || it == '_'))
+                .unwrap_or(rest.len());
+            tokens.push((position, Token::Word(rest[..len].to_string())));
+            position += len;
+        } else if char == '@' {
+            tokens.push((position, Token::Symbol("@")));
+            position += 1;
+        } else if let Some(symbol) = SYMBOLS.iter().find(|it| rest.starts_with(**it)) {
+            tokens.push((position, Token::Symbol(symbol)));
+            position += symbol.len();
+        } else {
+            return Err(HctlError::Parse {
+                position,
+                message: format!("unexpected character `{}`", char),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser. From the loosest to the tightest, the binary operators are
+/// `<=>`, `=>` (right associative), `|` and `&`. The unary operators bind tighter than all
+/// binary operators, while the binders (`↓x.`, `@x.`, `∃x.`, `∀x.`) extend as far to the right
+/// as possible.
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    query: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn peek_nth(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.next + offset).map(|(_, token)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.query.len(), |(position, _)| *position)
+    }
+
+    fn error<R>(&self, message: &str) -> Result<R, HctlError> {
+        Err(HctlError::Parse {
+            position: self.position(),
+            message: message.to_string(),
+        })
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).map(|(_, token)| token.clone());
+        self.next += 1;
+        token
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(it)) if *it == symbol)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(it)) if it == word)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), HctlError> {
+        if self.is_symbol(symbol) {
+            self.next += 1;
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", symbol))
+        }
+    }
+
+    fn expect_word(&mut self) -> Result<String, HctlError> {
+        match self.peek() {
+            Some(Token::Word(word)) => {
+                let word = word.clone();
+                self.next += 1;
+                Ok(word)
+            }
+            _ => self.error("expected a name"),
+        }
+    }
+
+    fn formula<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        let mut result = self.implication()?;
+        while self.is_symbol("<=>") {
+            self.next += 1;
+            result = HctlFormula::iff(result, self.implication()?);
+        }
+        Ok(result)
+    }
+
+    fn implication<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        let lhs = self.disjunction()?;
+        if self.is_symbol("=>") {
+            self.next += 1;
+            return Ok(HctlFormula::implies(lhs, self.implication()?));
+        }
+        Ok(lhs)
+    }
+
+    fn disjunction<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        let mut result = self.conjunction()?;
+        while self.is_symbol("|") {
+            self.next += 1;
+            result = HctlFormula::or(result, self.conjunction()?);
+        }
+        Ok(result)
+    }
+
+    fn conjunction<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        let mut result = self.unary()?;
+        while self.is_symbol("&") {
+            self.next += 1;
+            result = HctlFormula::and(result, self.unary()?);
+        }
+        Ok(result)
+    }
+
+    fn unary<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        if self.is_symbol("!") {
+            self.next += 1;
+            return Ok(HctlFormula::negate(self.unary()?));
+        }
+        let operator: Option<fn(HctlFormula<T>) -> HctlFormula<T>> = match self.peek() {
+            // a keyword followed by a comparison is a proposition on a variable of that name
+            _ if self.is_comparison(1) => None,
+            Some(Token::Word(word)) => match word.as_str() {
+                "EX" => Some(HctlFormula::ex),
+                "AX" => Some(HctlFormula::ax),
+                "EF" => Some(HctlFormula::ef),
+                "AF" => Some(HctlFormula::af),
+                "EG" => Some(HctlFormula::eg),
+                "AG" => Some(HctlFormula::ag),
+                _ => None,
+            },
+            _ => None,
+        };
+        match operator {
+            Some(operator) => {
+                self.next += 1;
+                Ok(operator(self.unary()?))
+            }
+            None => self.primary(),
+        }
+    }
+
+    fn primary<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        if self.is_symbol("(") {
+            self.next += 1;
+            let inner = self.formula()?;
+            self.expect_symbol(")")?;
+            return Ok(inner);
+        }
+        if let Some(binder) = self.binder::<T>() {
+            self.next += 1;
+            let variable = self.expect_word()?;
+            if !(self.is_symbol(".") || self.is_symbol(":")) {
+                return self.error("expected `.` after the state variable");
+            }
+            self.next += 1;
+            return Ok(binder(&variable, self.formula()?));
+        }
+        if self.is_comparison(1) {
+            return self.proposition();
+        }
+        if (self.is_word("E") || self.is_word("A"))
+            && matches!(self.peek_nth(1), Some(Token::Symbol("[")))
+        {
+            let existential = self.is_word("E");
+            self.next += 2;
+            let lhs = self.formula()?;
+            if !self.is_word("U") {
+                return self.error("expected `U`");
+            }
+            self.next += 1;
+            let rhs = self.formula()?;
+            self.expect_symbol("]")?;
+            return Ok(if existential {
+                HctlFormula::eu(lhs, rhs)
+            } else {
+                HctlFormula::au(lhs, rhs)
+            });
+        }
+        match self.peek() {
+            Some(Token::Word(word)) if word == "true" => {
+                self.next += 1;
+                Ok(HctlFormula::True)
+            }
+            Some(Token::Word(word)) if word == "false" => {
+                self.next += 1;
+                Ok(HctlFormula::False)
+            }
+            Some(Token::Word(_)) => Ok(HctlFormula::StateVariable(self.expect_word()?)),
+            Some(_) => self.error("expected a formula"),
+            None => self.error("unexpected end of the query"),
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn binder<T>(&self) -> Option<fn(&str, HctlFormula<T>) -> HctlFormula<T>> {
+        match self.peek()? {
+            Token::Symbol("↓") => Some(HctlFormula::bind),
+            Token::Symbol("@") => Some(HctlFormula::jump),
+            Token::Symbol("∃") => Some(HctlFormula::exists),
+            Token::Symbol("∀") => Some(HctlFormula::forall),
+            // ASCII alternatives, unless used as a variable in a proposition
+            Token::Word(word) if !self.is_comparison(1) => match word.as_str() {
+                "bind" => Some(HctlFormula::bind),
+                "at" => Some(HctlFormula::jump),
+                "exists" => Some(HctlFormula::exists),
+                "forall" => Some(HctlFormula::forall),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn comparison(token: Option<&Token>) -> Option<ComparisonOperator> {
+        match token? {
+            Token::Symbol("=") | Token::Symbol("==") => Some(ComparisonOperator::Eq),
+            Token::Symbol("!=") => Some(ComparisonOperator::Neq),
+            Token::Symbol("<") => Some(ComparisonOperator::Lt),
+            Token::Symbol(">") => Some(ComparisonOperator::Gt),
+            Token::Symbol("<=") => Some(ComparisonOperator::Leq),
+            Token::Symbol(">=") => Some(ComparisonOperator::Geq),
+            _ => None,
+        }
+    }
+
+    fn is_comparison(&self, offset: usize) -> bool {
+        matches!(self.peek(), Some(Token::Word(_)))
+            && Self::comparison(self.peek_nth(offset)).is_some()
+    }
+
+    /// `variable op value`, as in the [Display] of a [Proposition].
+    fn proposition<T: FromStr>(&mut self) -> Result<HctlFormula<T>, HctlError> {
+        let variable = self.expect_word()?;
+        let operator = Self::comparison(self.advance().as_ref()).expect("checked by the caller");
+        let position = self.position();
+        let value = self.expect_word()?;
+        let value = value.parse().map_err(|_| HctlError::Parse {
+            position,
+            message: format!("invalid value `{}`", value),
+        })?;
+        Ok(HctlFormula::Atom(Proposition::new(
+            operator, variable, value,
+        )))
+    }
+}
+
+/// Parses queries such as `∃x. @x. (AG EF x & p >= 1)`. The propositions use the same
+/// syntax as when displayed (`variable op value`, with `=`, `!=`, `<`, `>`, `<=` or `>=`),
+/// and a name which is not followed by a comparison is a state variable. The binders also
+/// have ASCII forms: `bind x.`, `at x.`, `exists x.` and `forall x.` (a `:` can be used
+/// instead of the `.`).
+impl<T: FromStr> FromStr for HctlFormula<T> {
+    type Err = HctlError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            next: 0,
+            query,
+        };
+        let formula = parser.formula()?;
+        if parser.peek().is_some() {
+            return parser.error("unexpected trailing input");
+        }
+        Ok(formula)
+    }
+}
+
+/// Evaluates [HctlFormula]-s on the asynchronous state-transition graph of a system, with
+/// the same semantics as the [CtlChecker] (which is used for the temporal operators).
+///
+/// The state variables are bound by enumerating the states of the system one by one, so
+/// the binders are only feasible for systems with a moderate number of states. The
+/// attractors do not need the binders, see [Self::attractor_states].
+pub struct HctlChecker<'a, D, T>
+where
+    D: SymbolicDomainEq<T>,
+{
+    system: &'a SmartSystemUpdateFn<D, T>,
+    ctl: CtlChecker<'a, D, T>,
+    /// all valid states (and colors)
+    universe: Bdd,
+}
+
+impl<'a, D, T> HctlChecker<'a, D, T>
+where
+    D: SymbolicDomainEq<T>,
+{
+    pub fn new(system: &'a SmartSystemUpdateFn<D, T>) -> Self {
+        Self {
+            system,
+            ctl: CtlChecker::new(system),
+            universe: system.unit_vertex_set(),
+        }
+    }
+
+    /// The set of states (colored, if the system has parameters) satisfying the `formula`.
+    /// The `formula` must not have free state variables.
+    pub fn check(&self, formula: &HctlFormula<T>) -> Result<Bdd, HctlError> {
+        self.check_in(formula, &mut HashMap::new())
+    }
+
+    /// Parses the `query` and evaluates it (see [Self::check]).
+    pub fn check_query(&self, query: &str) -> Result<Bdd, HctlError>
+    where
+        T: FromStr,
+    {
+        self.check(&query.parse()?)
+    }
+
+    /// The states (and colors) of the attractors, i.e. the states satisfying `↓x. AG EF x`.
+    ///
+    /// Unlike [Self::check] of [HctlFormula::attractor], this does not enumerate the states,
+    /// but decomposes the state space into the bottom strongly connected components
+    /// symbolically.
+    pub fn attractor_states(&self) -> Bdd {
+        self.union(self.attractors())
+    }
+
+    /// The individual attractors, each given by its states (and the colors in which these
+    /// states form an attractor), found in the same way as [Self::attractor_states].
+    pub fn attractors(&self) -> Vec<Bdd> {
+        self.bottom_components(self.universe.clone())
+    }
+
+    /// The states (and colors) of the attractors reachable from the `initial` states, e.g.
+    /// from the [SmartSystemUpdateFn::initial_states].
+    pub fn reachable_attractor_states(&self, initial: &Bdd) -> Bdd {
+        // the reachable states are closed under the transitions
+        self.union(self.bottom_components(self.ctl.reachable_from(initial)))
+    }
+
+    fn union(&self, sets: Vec<Bdd>) -> Bdd {
+        sets.iter().fold(
+            self.system.get_bdd_variable_set().mk_false(),
+            |result, set| result.or(set),
+        )
+    }
+
+    /// The bottom strongly connected components within the `universe`, which must be closed
+    /// under the transitions (in every color).
+    ///
+    /// In every iteration, a pivot state is picked for each color, and its component is
+    /// a bottom one iff all the states reachable from the pivot can also reach it back.
+    /// Either way, no other bottom component can reach the pivot, so all the states which
+    /// reach the pivot are removed from the `universe`.
+    fn bottom_components(&self, mut universe: Bdd) -> Vec<Bdd> {
+        let state_variables = self.system.standard_variables();
+        let mut result = Vec::new();
+        // the states which cannot reach the previous pivots, but are reachable from them,
+        // and are therefore closer to the bottom components
+        let mut candidates = universe.clone();
+        while !universe.is_false() {
+            if candidates.is_false() {
+                candidates = universe.clone();
+            }
+            let pivot = self.descend(candidates.pick(&state_variables), &state_variables);
+            let backward = self.ctl.reach_bwd(&pivot, &universe);
+            let (component, escaping) = self.forward_within(&pivot, &backward);
+            if !component.is_false() {
+                result.push(component);
+            }
+            universe = universe.and_not(&backward);
+            candidates = escaping.and(&universe);
+        }
+        result
+    }
+
+    /// Follows a path from the `pivot` (a single state per color) for a bounded number of
+    /// steps, which usually brings it much closer to (or into) a bottom component, whose
+    /// backward reachable set then removes a large part of the universe at once.
+    fn descend(&self, mut pivot: Bdd, state_variables: &[BddVariable]) -> Bdd {
+        let variables = self.system.get_system_variables();
+        for _ in 0..variables.len() {
+            let mut moved_any = false;
+            for variable in &variables {
+                let moved = self.system.successors_async_exclude_loops(variable, &pivot);
+                if !moved.is_false() {
+                    pivot = pivot
+                        .and_not(&self.system.colors(&moved))
+                        .or(&moved.pick(state_variables));
+                    moved_any = true;
+                }
+            }
+            if !moved_any {
+                break;
+            }
+        }
+        pivot
+    }
+
+    /// The states reachable from the `initial` states, in the colors in which all of them
+    /// are in the `bound`. The search is stopped for each color as soon as it leaves the
+    /// `bound`, and the states reached outside of it are returned as well.
+    fn forward_within(&self, initial: &Bdd, bound: &Bdd) -> (Bdd, Bdd) {
+        let variables = self.system.get_system_variables();
+        let mut result = initial.clone();
+        let mut escaping = self.system.get_bdd_variable_set().mk_false();
+        loop {
+            let mut extended = false;
+            for variable in &variables {
+                let successors = self.system.successors_async(variable, &result);
+                let escaped = successors.and_not(bound);
+                let successors = if escaped.is_false() {
+                    successors
+                } else {
+                    let colors = self.system.colors(&escaped);
+                    result = result.and_not(&colors);
+                    escaping = escaping.or(&escaped);
+                    successors.and_not(&colors)
+                };
+                if !successors.imp(&result).is_true() {
+                    result = result.or(&successors);
+                    extended = true;
+                }
+            }
+            if !extended {
+                return (result, escaping);
+            }
+        }
+    }
+
+    /// Evaluates the `formula`, where the free state variables are bound to the states
+    /// (without colors) in the `environment`.
+    fn check_in(
+        &self,
+        formula: &HctlFormula<T>,
+        environment: &mut HashMap<String, Bdd>,
+    ) -> Result<Bdd, HctlError> {
+        let ctl = &self.ctl;
+        let mut check = |formula| self.check_in(formula, environment);
+        Ok(match formula {
+            HctlFormula::True => self.universe.clone(),
+            HctlFormula::False => self.system.get_bdd_variable_set().mk_false(),
+            HctlFormula::Atom(proposition) => ctl.atom(proposition)?,
+            HctlFormula::StateVariable(name) => environment
+                .get(name)
+                .ok_or_else(|| HctlError::UnboundStateVariable(name.clone()))?
+                .and(&self.universe),
+            HctlFormula::Not(inner) => self.universe.and_not(&check(inner)?),
+            HctlFormula::And(lhs, rhs) => check(lhs)?.and(&check(rhs)?),
+            HctlFormula::Or(lhs, rhs) => check(lhs)?.or(&check(rhs)?),
+            HctlFormula::Implies(lhs, rhs) => self.universe.and(&check(lhs)?.imp(&check(rhs)?)),
+            HctlFormula::Iff(lhs, rhs) => self.universe.and(&check(lhs)?.iff(&check(rhs)?)),
+            HctlFormula::Ex(inner) => ctl.ex(&check(inner)?),
+            HctlFormula::Ax(inner) => ctl.ax(&check(inner)?),
+            HctlFormula::Ef(inner) => ctl.ef(&check(inner)?),
+            HctlFormula::Af(inner) => ctl.af(&check(inner)?),
+            HctlFormula::Eg(inner) => ctl.eg(&check(inner)?),
+            HctlFormula::Ag(inner) => ctl.ag(&check(inner)?),
+            HctlFormula::Eu(lhs, rhs) => ctl.eu(&check(lhs)?, &check(rhs)?),
+            HctlFormula::Au(lhs, rhs) => ctl.au(&check(lhs)?, &check(rhs)?),
+            HctlFormula::Bind(variable, inner) => {
+                // the pairs (s, c) such that (s, c) satisfies `inner` with `variable := s`
+                self.fold_bindings(variable, inner, environment, |acc, state, result| {
+                    acc.or(&result.and(state))
+                })?
+            }
+            HctlFormula::Jump(variable, inner) => {
+                let result = check(inner)?;
+                let state = environment
+                    .get(variable)
+                    .ok_or_else(|| HctlError::UnboundStateVariable(variable.clone()))?;
+                self.universe.and(&self.system.colors(&result.and(state)))
+            }
+            HctlFormula::Exists(variable, inner) => {
+                self.fold_bindings(variable, inner, environment, |acc, _, result| {
+                    acc.or(&result)
+                })?
+            }
+            HctlFormula::Forall(variable, inner) => {
+                let counterexamples =
+                    self.fold_bindings(variable, inner, environment, |acc, _, result| {
+                        acc.or(&self.universe.and_not(&result))
+                    })?;
+                self.universe.and_not(&counterexamples)
+            }
+        })
+    }
+
+    /// Folds the results of the `inner` formula over all bindings of the state `variable`,
+    /// starting from the empty set. The `step` gets the bound state and the result.
+    fn fold_bindings(
+        &self,
+        variable: &str,
+        inner: &HctlFormula<T>,
+        environment: &mut HashMap<String, Bdd>,
+        step: impl Fn(Bdd, &Bdd, Bdd) -> Bdd,
+    ) -> Result<Bdd, HctlError> {
+        let states = self
+            .system
+            .iter_states(&self.universe)
+            .map(|state| {
+                self.system
+                    .encode_state(&state)
+                    .expect("the states of the system are valid")
+            })
+            .collect::<Vec<_>>();
+        let shadowed = environment.remove(variable);
+        let result = states.iter().try_fold(
+            self.system.get_bdd_variable_set().mk_false(),
+            |acc, state| {
+                environment.insert(variable.to_string(), state.clone());
+                Ok(step(acc, state, self.check_in(inner, environment)?))
+            },
+        );
+        match shadowed {
+            Some(shadowed) => environment.insert(variable.to_string(), shadowed),
+            None => environment.remove(variable),
+        };
+        result
+    }
+}
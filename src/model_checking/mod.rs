@@ -0,0 +1,4 @@
+pub mod basins;
+pub mod control;
+pub mod ctl;
+pub mod hctl;
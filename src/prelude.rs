@@ -1,4 +1,19 @@
+pub use crate::explicit::explicit_system;
+pub use crate::explicit::simulation;
+pub use crate::explicit::state_transition_graph;
+pub use crate::expression_components::{expression, proposition};
+pub use crate::model_checking::{basins, control, ctl, hctl};
 pub use crate::symbolic_domains::symbolic_domain;
+pub use crate::transformations::{percolation, reduction};
+pub use crate::update::expression_from_bdd;
+pub use crate::update::parametrized_update_function;
+pub use crate::update::persistence;
+pub use crate::update::perturbation;
+pub use crate::update::state;
+pub use crate::update::unprocessed_variable_update_function;
 pub use crate::update::update_fn;
+pub use crate::update::variable_ordering;
 
+pub use crate::xml_parsing::qualitative_species_parser::load_initial_levels;
 pub use crate::xml_parsing::utils::find_start_of;
+pub use crate::xml_parsing::variable_update_fn_parser::load_all_update_fns;
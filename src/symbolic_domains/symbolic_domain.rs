@@ -1,8 +1,19 @@
-use std::{cell::OnceCell, collections::HashSet};
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+    marker::PhantomData,
+    str::FromStr,
+};
 
 use biodivine_lib_bdd::{
     Bdd, BddPartialValuation, BddVariable, BddVariableSet, BddVariableSetBuilder,
 };
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::expression_components::proposition::ComparisonOperator;
 
 pub trait SymbolicDomain<T> {
     /// Encode the given `value` into the provided `BddPartialValuation`.
@@ -80,6 +91,14 @@ pub trait SymbolicDomain<T> {
     }
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable>;
 
+    /// Replaces the symbolic variables of this domain according to the `permutation` (variables
+    /// that are not in the `permutation` are kept). Used when the variables are reordered after
+    /// the domain was created.
+    ///
+    /// *Contract:* The `permutation` must preserve the relative order of the variables of this
+    /// domain, and the domain must not be used with the old `BddVariableSet` afterwards.
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>);
+
     /// Decode a value from the provided `BddPartialValuation`.
     ///
     /// *Contract:* This method only reads the symbolic variables from `Self::symbolic_variables`.
@@ -129,7 +148,26 @@ pub trait SymbolicDomain<T> {
     }
 }
 
-pub trait SymbolicDomainOrd<T>: SymbolicDomain<T> {
+/// A domain whose values can only be compared for (in)equality.
+pub trait SymbolicDomainEq<T>: SymbolicDomain<T> {
+    /// Creates a domain (with variables named after `name`) able to represent all the `values`.
+    /// The domain may also contain values that are not listed.
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self;
+
+    /// Encodes the set of values `x` satisfying `x <operator> value`.
+    ///
+    /// Returns an error if the domain does not support the `operator`.
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError>;
+
+    fn get_all_possible_values(&self) -> Vec<T>;
+}
+
+pub trait SymbolicDomainOrd<T>: SymbolicDomainEq<T> {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self;
     /// Encodes the set of values that are strictly less than the given value.
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd;
@@ -152,26 +190,92 @@ pub trait SymbolicDomainOrd<T>: SymbolicDomain<T> {
     }
 
     fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering;
+}
 
-    fn get_all_possible_values(&self) -> Vec<T>;
+#[derive(Error, Debug)]
+pub enum DomainError {
+    /// The comparison operator requires the values of the domain to be ordered.
+    UnsupportedOperator(ComparisonOperator),
+}
+
+impl Display for DomainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DomainError::UnsupportedOperator(operator) => write!(
+                f,
+                "Unsupported operator; `{}` cannot be used with a domain without ordering",
+                operator.symbol()
+            ),
+        }
+    }
 }
 
+/// Implements [SymbolicDomainEq::encode_comparison] for domains with ordering.
+fn encode_ord_comparison<D, T>(
+    domain: &D,
+    bdd_variable_set: &BddVariableSet,
+    operator: ComparisonOperator,
+    value: &T,
+) -> Bdd
+where
+    D: SymbolicDomainOrd<T>,
+{
+    match operator {
+        ComparisonOperator::Eq => domain.encode_one(bdd_variable_set, value),
+        ComparisonOperator::Neq => domain.encode_one(bdd_variable_set, value).not(),
+        ComparisonOperator::Lt => domain.encode_lt(bdd_variable_set, value),
+        ComparisonOperator::Leq => domain.encode_le(bdd_variable_set, value),
+        ComparisonOperator::Gt => domain.encode_gt(bdd_variable_set, value),
+        ComparisonOperator::Geq => domain.encode_ge(bdd_variable_set, value),
+    }
+}
+
+/// The unsigned integer types (`u8`, `u16` and `u32`) that can be used as values
+/// of the integer domains.
+pub trait UnsignedInteger: Copy + Ord + Hash + Debug + Display + FromStr + Default {
+    fn to_usize(self) -> usize;
+
+    /// # Panics
+    ///
+    /// If the `value` does not fit into `Self`.
+    fn from_usize(value: usize) -> Self;
+}
+
+macro_rules! impl_unsigned_integer {
+    ($($t:ty),*) => {
+        $(
+            impl UnsignedInteger for $t {
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                fn from_usize(value: usize) -> Self {
+                    Self::try_from(value).expect("value should fit into the integer type")
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_integer!(u8, u16, u32);
+
 /// Implementation of a `SymbolicDomain` using unary integer encoding, i.e. each integer domain
 /// `D = { 0 ... max }` is encoded using `max` symbolic variables.
 ///
 /// In this encoding, to represent value `k \in D`, we set the values of the first `k` symbolic
 /// variables to `true` and leave the remaining as `false`.
 #[derive(Clone, Debug)]
-pub struct UnaryIntegerDomain {
+pub struct UnaryIntegerDomain<T = u8> {
     /// invariant: sorted
     variables: Vec<BddVariable>, // todo maybe Rc<[BddVariable]>
     unit_collection_cell: OnceCell<Bdd>,
+    _marker: PhantomData<T>,
 }
 
 // implementation author: Samuel Pastva
-impl SymbolicDomain<u8> for UnaryIntegerDomain {
-    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.variables.len() as u8) {
+impl<T: UnsignedInteger> SymbolicDomain<T> for UnaryIntegerDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        if value.to_usize() > self.variables.len() {
             let vars = self
                 .variables
                 .iter()
@@ -188,7 +292,7 @@ impl SymbolicDomain<u8> for UnaryIntegerDomain {
         }
 
         self.variables.iter().enumerate().for_each(|(i, var)| {
-            bdd_valuation.set_value(*var, i < (*value as usize));
+            bdd_valuation.set_value(*var, i < value.to_usize());
         });
     }
 
@@ -222,7 +326,12 @@ impl SymbolicDomain<u8> for UnaryIntegerDomain {
         self.raw_bdd_variables() // already the optimal performance
     }
 
-    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        rename_all(&mut self.variables, permutation);
+        self.unit_collection_cell = OnceCell::new();
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
         // This method does not always check if the valuation is valid in the unary encoding, it
         // just picks the "simplest" interpretation of the given valuation. For increased safety,
         // we should check that that after the last "true" value, only "false" values follow.
@@ -235,14 +344,42 @@ impl SymbolicDomain<u8> for UnaryIntegerDomain {
                     .get_value(**var)
                     .expect("var should be in the valuation")
             })
-            .map(|(idx, _)| idx as u8)
-            .unwrap_or(self.variables.len() as u8)
+            .map(|(idx, _)| T::from_usize(idx))
+            .unwrap_or_else(|| T::from_usize(self.variables.len()))
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for UnaryIntegerDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        Ok(encode_ord_comparison(
+            self,
+            bdd_variable_set,
+            operator,
+            value,
+        ))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        (0..=self.variables.len()).map(T::from_usize).collect() // notice the inclusive range; n values is represented by n-1 bdd variables
     }
 }
 
-impl SymbolicDomainOrd<u8> for UnaryIntegerDomain {
-    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let variables = (0..*max_value)
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for UnaryIntegerDomain<T> {
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        let variables = (0..max_value.to_usize())
             .map(|var_idx| {
                 let name = format!("{name}_v{}", var_idx + 1);
                 builder.make_variable(name.as_str())
@@ -252,36 +389,38 @@ impl SymbolicDomainOrd<u8> for UnaryIntegerDomain {
         Self {
             variables,
             unit_collection_cell: OnceCell::new(),
+            _marker: PhantomData,
         }
     }
 
-    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        if exclusive_upper_bound.to_usize() == 0 {
+            return self.empty_collection(bdd_variable_set);
+        }
+
         // forbid values greater than or equal to the upper bound by forbidding upper_bound_bit
         let not_upper_bound_bit =
-            bdd_variable_set.mk_not_var(self.variables[(*exclusive_upper_bound - 1) as usize]);
+            bdd_variable_set.mk_not_var(self.variables[exclusive_upper_bound.to_usize() - 1]);
 
         self.unit_collection(bdd_variable_set)
             .and(&not_upper_bound_bit)
     }
 
-    fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
         lhs.cmp(rhs)
     }
-
-    fn get_all_possible_values(&self) -> Vec<u8> {
-        (0..=self.variables.len() as u8).collect() // notice the inclusive range; n values is represented by n-1 bdd variables
-    }
 }
 
-#[derive(Debug)]
-pub struct PetriNetIntegerDomain {
+#[derive(Clone, Debug)]
+pub struct PetriNetIntegerDomain<T = u8> {
     /// invariant: sorted
     variables: Vec<BddVariable>,
+    _marker: PhantomData<T>,
 }
 
-impl SymbolicDomain<u8> for PetriNetIntegerDomain {
-    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.variables.len() as u8) {
+impl<T: UnsignedInteger> SymbolicDomain<T> for PetriNetIntegerDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        if value.to_usize() > self.variables.len() {
             let vars = self
                 .variables
                 .iter()
@@ -301,7 +440,7 @@ impl SymbolicDomain<u8> for PetriNetIntegerDomain {
             .iter()
             .enumerate()
             .for_each(|(var_idx_within_sym_var, var)| {
-                bdd_valuation.set_value(*var, var_idx_within_sym_var == (*value as usize));
+                bdd_valuation.set_value(*var, var_idx_within_sym_var == value.to_usize());
             });
     }
 
@@ -321,7 +460,11 @@ impl SymbolicDomain<u8> for PetriNetIntegerDomain {
         self.raw_bdd_variables() // already the optimal performance
     }
 
-    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        rename_all(&mut self.variables, permutation);
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
         // This method does not always check if the valuation is valid in the unary encoding, it
         // just picks the "simplest" interpretation of the given valuation. For increased safety,
         // we should check that that after the only "true" value, only "false" values follow.
@@ -334,50 +477,80 @@ impl SymbolicDomain<u8> for PetriNetIntegerDomain {
                     .get_value(**var)
                     .expect("var should be in the valuation")
             })
-            .map(|(idx, _)| idx as u8)
+            .map(|(idx, _)| T::from_usize(idx))
             .expect("a valid value should be encoded by a \"true\" bit")
     }
 }
 
-impl SymbolicDomainOrd<u8> for PetriNetIntegerDomain {
-    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let variables = (0..=*max_value) // notice the inclusive range
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for PetriNetIntegerDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        Ok(encode_ord_comparison(
+            self,
+            bdd_variable_set,
+            operator,
+            value,
+        ))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        (0..self.variables.len()).map(T::from_usize).collect() // notice the exclusive range; n values is represented by n bdd variables
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for PetriNetIntegerDomain<T> {
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        let variables = (0..=max_value.to_usize()) // notice the inclusive range
             .map(|var_idx| {
                 let name = format!("{name}_v{}", var_idx + 1);
                 builder.make_variable(name.as_str())
             })
             .collect();
 
-        Self { variables }
+        Self {
+            variables,
+            _marker: PhantomData,
+        }
     }
 
-    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        (0..exclusive_upper_bound.to_usize())
+            .map(T::from_usize)
+            .fold(self.empty_collection(bdd_variable_set), |acc, val| {
+                acc.or(&self.encode_one(bdd_variable_set, &val))
+            })
     }
 
-    fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
         lhs.cmp(rhs)
     }
-
-    fn get_all_possible_values(&self) -> Vec<u8> {
-        (0..self.variables.len() as u8).collect() // notice the exclusive range; n values is represented by n bdd variables
-    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct BinaryIntegerDomain<T> {
     /// invariant: sorted
     variables: Vec<BddVariable>,
     /// in older implementations, this used to be the `max_value`
     /// since we no longer require ordering, no `max_value` -> Bdd of all the possible values
     max_value: T, // todo mb, cannot implemnent BinaryIntegerDomain generically -> it must be SymbolicDomainOrd
+    unit_collection_cell: OnceCell<Bdd>,
 }
 
-impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
-    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.max_value) {
+impl<T: UnsignedInteger> SymbolicDomain<T> for BinaryIntegerDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        if value > &self.max_value {
             // this breaks the idea of SymbolicDomain being not bound to the ordering
             let vars = self
                 .variables
@@ -393,7 +566,7 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
         }
 
         self.variables.iter().enumerate().for_each(|(idx, var)| {
-            bdd_valuation.set_value(*var, (value & (1 << idx)) != 0);
+            bdd_valuation.set_value(*var, (value.to_usize() & (1 << idx)) != 0);
         })
     }
 
@@ -402,9 +575,16 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
     }
 
     fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
-        (0..=self.max_value).fold(bdd_variable_set.mk_false(), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+        self.unit_collection_cell
+            .get_or_init(|| {
+                encode_lt_bitwise(
+                    bdd_variable_set,
+                    &self.variables,
+                    self.max_value.to_usize() as u64 + 1,
+                    false,
+                )
+            })
+            .clone()
     }
 
     fn raw_bdd_variables(&self) -> Vec<BddVariable> {
@@ -415,13 +595,18 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
         self.raw_bdd_variables() // already the optimal performance
     }
 
-    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        rename_all(&mut self.variables, permutation);
+        self.unit_collection_cell = OnceCell::new();
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
         let res = self
             .variables
             .iter()
             .enumerate()
             .fold(0, |acc, (idx, var)| {
-                let bit = u8::from(
+                let bit = usize::from(
                     bdd_valuation
                         .get_value(*var)
                         .expect("bits of the value should be in the valuation"),
@@ -429,6 +614,7 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
 
                 acc | (bit << idx)
             });
+        let res = T::from_usize(res);
 
         if res > self.max_value {
             panic!(
@@ -441,9 +627,37 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
     }
 }
 
-impl SymbolicDomainOrd<u8> for BinaryIntegerDomain<u8> {
-    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let bit_count = 8 - max_value.leading_zeros();
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for BinaryIntegerDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        Ok(encode_ord_comparison(
+            self,
+            bdd_variable_set,
+            operator,
+            value,
+        ))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        (0..=self.max_value.to_usize()).map(T::from_usize).collect()
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for BinaryIntegerDomain<T> {
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        let bit_count = usize::BITS - max_value.to_usize().leading_zeros();
 
         let variables = (0..bit_count)
             .map(|it| {
@@ -455,40 +669,45 @@ impl SymbolicDomainOrd<u8> for BinaryIntegerDomain<u8> {
         Self {
             variables,
             max_value: *max_value,
+            unit_collection_cell: OnceCell::new(),
         }
     }
 
-    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
-    }
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        if exclusive_upper_bound > &self.max_value {
+            return self.unit_collection(bdd_variable_set);
+        }
 
-    fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
-        lhs.cmp(rhs)
+        encode_lt_bitwise(
+            bdd_variable_set,
+            &self.variables,
+            exclusive_upper_bound.to_usize() as u64,
+            false,
+        )
     }
 
-    fn get_all_possible_values(&self) -> Vec<u8> {
-        (0..=self.max_value).collect()
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
+        lhs.cmp(rhs)
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct GrayCodeIntegerDomain<T> {
     /// invariant: sorted
     variables: Vec<BddVariable>,
     /// in older implementations, this used to be the `max_value`
     /// since we no longer require ordering, no `max_value` -> Bdd of all the possible values
     max_value: T, // todo same as in the case of BinaryIntegerDomain
+    unit_collection_cell: OnceCell<Bdd>,
 }
 
-impl GrayCodeIntegerDomain<u8> {
-    fn binary_to_gray_code(n: u8) -> u8 {
+impl<T> GrayCodeIntegerDomain<T> {
+    fn binary_to_gray_code(n: usize) -> usize {
         // magic
         n ^ (n >> 1)
     }
 
-    fn gray_code_to_binary(n: u8) -> u8 {
+    fn gray_code_to_binary(n: usize) -> usize {
         // magic II
         let mut n = n;
         let mut mask = n >> 1;
@@ -500,9 +719,9 @@ impl GrayCodeIntegerDomain<u8> {
     }
 }
 
-impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
-    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.max_value) {
+impl<T: UnsignedInteger> SymbolicDomain<T> for GrayCodeIntegerDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        if value > &self.max_value {
             // this breaks the idea of SymbolicDomain being not bound to the ordering
             let vars = self
                 .variables
@@ -517,7 +736,7 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
             )
         }
 
-        let gray_code = Self::binary_to_gray_code(*value);
+        let gray_code = Self::binary_to_gray_code(value.to_usize());
         self.variables.iter().enumerate().for_each(|(idx, var)| {
             bdd_valuation.set_value(*var, (gray_code & (1 << idx)) != 0);
         })
@@ -528,9 +747,16 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
     }
 
     fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
-        (0..=self.max_value).fold(bdd_variable_set.mk_false(), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+        self.unit_collection_cell
+            .get_or_init(|| {
+                encode_lt_bitwise(
+                    bdd_variable_set,
+                    &self.variables,
+                    self.max_value.to_usize() as u64 + 1,
+                    true,
+                )
+            })
+            .clone()
     }
 
     fn raw_bdd_variables(&self) -> Vec<BddVariable> {
@@ -541,13 +767,18 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
         self.raw_bdd_variables() // already the optimal performance
     }
 
-    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        rename_all(&mut self.variables, permutation);
+        self.unit_collection_cell = OnceCell::new();
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
         let read_gray_code = self
             .variables
             .iter()
             .enumerate()
             .fold(0, |acc, (idx, var)| {
-                let bit = u8::from(
+                let bit = usize::from(
                     bdd_valuation
                         .get_value(*var)
                         .expect("bits of the value should be in the valuation"),
@@ -556,7 +787,7 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
                 acc | (bit << idx)
             });
 
-        let res = Self::gray_code_to_binary(read_gray_code);
+        let res = T::from_usize(Self::gray_code_to_binary(read_gray_code));
 
         if res > self.max_value {
             panic!(
@@ -569,9 +800,37 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
     }
 }
 
-impl SymbolicDomainOrd<u8> for GrayCodeIntegerDomain<u8> {
-    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let bit_count = 8 - max_value.leading_zeros();
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for GrayCodeIntegerDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        Ok(encode_ord_comparison(
+            self,
+            bdd_variable_set,
+            operator,
+            value,
+        ))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        (0..=self.max_value.to_usize()).map(T::from_usize).collect()
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for GrayCodeIntegerDomain<T> {
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        let bit_count = usize::BITS - max_value.to_usize().leading_zeros();
 
         let variables = (0..bit_count)
             .map(|it| {
@@ -583,20 +842,544 @@ impl SymbolicDomainOrd<u8> for GrayCodeIntegerDomain<u8> {
         Self {
             variables,
             max_value: *max_value,
+            unit_collection_cell: OnceCell::new(),
+        }
+    }
+
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        if exclusive_upper_bound > &self.max_value {
+            return self.unit_collection(bdd_variable_set);
+        }
+
+        encode_lt_bitwise(
+            bdd_variable_set,
+            &self.variables,
+            exclusive_upper_bound.to_usize() as u64,
+            true,
+        )
+    }
+
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
+        lhs.cmp(rhs)
+    }
+}
+
+/// Implementation of a `SymbolicDomain` for Boolean variables, i.e. with values `0` and `1`,
+/// which are encoded using a single symbolic variable.
+///
+/// Both valuations of the variable are valid, hence the unit collection is `true` and the
+/// values can be encoded and decoded directly, without the generic bit manipulation.
+#[derive(Clone, Debug)]
+pub struct BooleanDomain<T = u8> {
+    variable: BddVariable,
+    _marker: PhantomData<T>,
+}
+
+impl<T: UnsignedInteger> BooleanDomain<T> {
+    fn value_to_bit(&self, value: &T) -> bool {
+        match value.to_usize() {
+            0 => false,
+            1 => true,
+            _ => panic!(
+                "Value is too big for domain {:?}; value: {}, domain size: 1",
+                self.variable, value
+            ),
+        }
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomain<T> for BooleanDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        bdd_valuation.set_value(self.variable, self.value_to_bit(value));
+    }
+
+    fn encode_one(&self, variables: &BddVariableSet, value: &T) -> Bdd {
+        variables.mk_literal(self.variable, self.value_to_bit(value))
+    }
+
+    fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        bdd_variable_set.mk_false()
+    }
+
+    fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        bdd_variable_set.mk_true()
+    }
+
+    fn raw_bdd_variables_encode(&self, value: &T) -> Vec<bool> {
+        vec![self.value_to_bit(value)]
+    }
+
+    fn raw_bdd_variables(&self) -> Vec<BddVariable> {
+        vec![self.variable]
+    }
+
+    fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
+        vec![self.variable]
+    }
+
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        if let Some(variable) = permutation.get(&self.variable) {
+            self.variable = *variable;
         }
     }
 
-    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
+        let bit = bdd_valuation
+            .get_value(self.variable)
+            .expect("bit of the value should be in the valuation");
+        T::from_usize(usize::from(bit))
+    }
+
+    fn decode_collection(&self, _variables: &BddVariableSet, collection: &Bdd) -> Vec<T> {
+        [false, true]
+            .into_iter()
+            .filter(|bit| !collection.var_restrict(self.variable, *bit).is_false())
+            .map(|bit| T::from_usize(usize::from(bit)))
+            .collect()
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for BooleanDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        Ok(encode_ord_comparison(
+            self,
+            bdd_variable_set,
+            operator,
+            value,
+        ))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        vec![T::from_usize(0), T::from_usize(1)]
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for BooleanDomain<T> {
+    /// # Panics
+    ///
+    /// If the `max_value` is greater than `1`. The domain always contains both `0` and `1`,
+    /// even if the `max_value` is `0`.
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        assert!(
+            max_value.to_usize() <= 1,
+            "Boolean domain cannot represent value {}",
+            max_value
+        );
+
+        Self {
+            variable: builder.make_variable(format!("{name}_v1").as_str()),
+            _marker: PhantomData,
+        }
+    }
+
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        match exclusive_upper_bound.to_usize() {
+            0 => bdd_variable_set.mk_false(),
+            1 => bdd_variable_set.mk_literal(self.variable, false),
+            _ => bdd_variable_set.mk_true(),
+        }
+    }
+
+    fn encode_gt(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        // the unit collection is `true`, so there are no invalid values to remove
+        self.encode_le(bdd_variable_set, value).not()
+    }
+
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
+        lhs.cmp(rhs)
+    }
+}
+
+/// Implementation of a `SymbolicDomain` for categorical values which have no ordering,
+/// e.g. `{inactive, cytoplasm, nucleus}`. Only `Eq` and `Neq` comparisons are supported.
+///
+/// The values are stored in the order in which they were given; the `k`-th value is encoded
+/// as the number `k` in binary.
+#[derive(Clone, Debug)]
+pub struct EnumeratedDomain<T> {
+    /// invariant: sorted
+    variables: Vec<BddVariable>,
+    /// invariant: no duplicates
+    values: Vec<T>,
+    unit_collection_cell: OnceCell<Bdd>,
+}
+
+impl<T: PartialEq> EnumeratedDomain<T> {
+    fn index_of(&self, value: &T) -> Option<usize> {
+        self.values.iter().position(|it| it == value)
+    }
+}
+
+impl<T: Clone + PartialEq + Debug> SymbolicDomain<T> for EnumeratedDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        let Some(index) = self.index_of(value) else {
+            panic!(
+                "Value is not in the domain; value: {:?}, domain: {:?}",
+                value, self.values
+            )
+        };
+
+        self.variables.iter().enumerate().for_each(|(idx, var)| {
+            bdd_valuation.set_value(*var, (index & (1 << idx)) != 0);
+        })
+    }
+
+    fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        bdd_variable_set.mk_false()
+    }
+
+    fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        self.unit_collection_cell
+            .get_or_init(|| {
+                encode_lt_bitwise(
+                    bdd_variable_set,
+                    &self.variables,
+                    self.values.len() as u64,
+                    false,
+                )
+            })
+            .clone()
+    }
+
+    fn raw_bdd_variables(&self) -> Vec<BddVariable> {
+        self.variables.clone() // already sorted
+    }
+
+    fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
+        self.raw_bdd_variables() // already the optimal performance
+    }
+
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        rename_all(&mut self.variables, permutation);
+        self.unit_collection_cell = OnceCell::new();
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
+        let index = self
+            .variables
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (idx, var)| {
+                let bit = usize::from(
+                    bdd_valuation
+                        .get_value(*var)
+                        .expect("bits of the value should be in the valuation"),
+                );
+
+                acc | (bit << idx)
+            });
+
+        self.values.get(index).cloned().unwrap_or_else(|| {
+            panic!(
+                "invalid encoding; should not contain index greater than {}, but contains {}",
+                self.values.len() - 1,
+                index
+            )
         })
     }
+}
+
+impl<T: Clone + PartialEq + Debug> SymbolicDomainEq<T> for EnumeratedDomain<T> {
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let values = values.iter().fold(Vec::new(), |mut acc, value| {
+            if !acc.contains(value) {
+                acc.push(value.clone());
+            }
+            acc
+        });
+        assert!(!values.is_empty(), "at least one value should be given");
+
+        let bit_count = usize::BITS - (values.len() - 1).leading_zeros();
+        let variables = (0..bit_count)
+            .map(|it| {
+                let name = format!("{name}_v{}", it + 1);
+                builder.make_variable(name.as_str())
+            })
+            .collect();
+
+        Self {
+            variables,
+            values,
+            unit_collection_cell: OnceCell::new(),
+        }
+    }
+
+    /// Values that are not in the domain are never equal to the value of the variable.
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        let equal = match self.index_of(value) {
+            Some(_) => self.encode_one(bdd_variable_set, value),
+            None => self.empty_collection(bdd_variable_set),
+        };
 
-    fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
+        match operator {
+            ComparisonOperator::Eq => Ok(equal),
+            ComparisonOperator::Neq => Ok(equal.not()),
+            _ => Err(DomainError::UnsupportedOperator(operator)),
+        }
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        self.values.clone()
+    }
+}
+
+/// The encodings of integer domains that can be selected for individual variables
+/// of a [MixedIntegerDomain].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntegerEncoding {
+    Boolean,
+    Unary,
+    Binary,
+    GrayCode,
+    PetriNet,
+}
+
+impl IntegerEncoding {
+    /// The largest `max_value` for which [IntegerEncoding::default_for] picks the unary
+    /// encoding. For such small domains, the unary encoding needs at most one more BDD
+    /// variable than the binary one, but contains no invalid values.
+    pub const UNARY_MAX_VALUE: usize = 3;
+
+    /// The encoding used when none is requested explicitly: [BooleanDomain] for Boolean
+    /// variables, unary for other small domains (see [IntegerEncoding::UNARY_MAX_VALUE])
+    /// and binary for larger ones.
+    pub fn default_for<T: UnsignedInteger>(max_value: &T) -> Self {
+        match max_value.to_usize() {
+            1 => IntegerEncoding::Boolean,
+            max_value if max_value <= Self::UNARY_MAX_VALUE => IntegerEncoding::Unary,
+            _ => IntegerEncoding::Binary,
+        }
+    }
+}
+
+/// Implementation of a `SymbolicDomain` which delegates to one of the integer domains
+/// chosen at runtime. This allows a single system to encode each of its variables
+/// differently, e.g. Boolean variables using [BooleanDomain] and highly multi-valued ones
+/// in binary.
+#[derive(Clone, Debug)]
+pub enum MixedIntegerDomain<T = u8> {
+    Boolean(BooleanDomain<T>),
+    Unary(UnaryIntegerDomain<T>),
+    Binary(BinaryIntegerDomain<T>),
+    GrayCode(GrayCodeIntegerDomain<T>),
+    PetriNet(PetriNetIntegerDomain<T>),
+}
+
+/// Evaluates `$body` with `$inner` bound to the domain wrapped by the [MixedIntegerDomain].
+macro_rules! dispatch {
+    ($domain:expr, $inner:ident => $body:expr) => {
+        match $domain {
+            MixedIntegerDomain::Boolean($inner) => $body,
+            MixedIntegerDomain::Unary($inner) => $body,
+            MixedIntegerDomain::Binary($inner) => $body,
+            MixedIntegerDomain::GrayCode($inner) => $body,
+            MixedIntegerDomain::PetriNet($inner) => $body,
+        }
+    };
+}
+
+impl<T: UnsignedInteger> MixedIntegerDomain<T> {
+    /// Creates a domain representing values `0..=max_value` using the given `encoding`.
+    ///
+    /// # Panics
+    ///
+    /// If the [IntegerEncoding::Boolean] is requested for `max_value` greater than `1`.
+    pub fn new_with_encoding(
+        builder: &mut BddVariableSetBuilder,
+        name: &str,
+        max_value: &T,
+        encoding: IntegerEncoding,
+    ) -> Self {
+        match encoding {
+            IntegerEncoding::Boolean => {
+                MixedIntegerDomain::Boolean(BooleanDomain::new(builder, name, max_value))
+            }
+            IntegerEncoding::Unary => {
+                MixedIntegerDomain::Unary(UnaryIntegerDomain::new(builder, name, max_value))
+            }
+            IntegerEncoding::Binary => {
+                MixedIntegerDomain::Binary(BinaryIntegerDomain::new(builder, name, max_value))
+            }
+            IntegerEncoding::GrayCode => {
+                MixedIntegerDomain::GrayCode(GrayCodeIntegerDomain::new(builder, name, max_value))
+            }
+            IntegerEncoding::PetriNet => {
+                MixedIntegerDomain::PetriNet(PetriNetIntegerDomain::new(builder, name, max_value))
+            }
+        }
+    }
+
+    pub fn encoding(&self) -> IntegerEncoding {
+        match self {
+            MixedIntegerDomain::Boolean(_) => IntegerEncoding::Boolean,
+            MixedIntegerDomain::Unary(_) => IntegerEncoding::Unary,
+            MixedIntegerDomain::Binary(_) => IntegerEncoding::Binary,
+            MixedIntegerDomain::GrayCode(_) => IntegerEncoding::GrayCode,
+            MixedIntegerDomain::PetriNet(_) => IntegerEncoding::PetriNet,
+        }
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomain<T> for MixedIntegerDomain<T> {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T) {
+        dispatch!(self, domain => domain.encode_bits(bdd_valuation, value))
+    }
+
+    fn encode_one(&self, variables: &BddVariableSet, value: &T) -> Bdd {
+        dispatch!(self, domain => domain.encode_one(variables, value))
+    }
+
+    fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        dispatch!(self, domain => domain.empty_collection(bdd_variable_set))
+    }
+
+    fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        dispatch!(self, domain => domain.unit_collection(bdd_variable_set))
+    }
+
+    fn raw_bdd_variables(&self) -> Vec<BddVariable> {
+        dispatch!(self, domain => domain.raw_bdd_variables())
+    }
+
+    fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
+        dispatch!(self, domain => domain.raw_bdd_variables_unsorted())
+    }
+
+    fn rename_bdd_variables(&mut self, permutation: &HashMap<BddVariable, BddVariable>) {
+        dispatch!(self, domain => domain.rename_bdd_variables(permutation))
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> T {
+        dispatch!(self, domain => domain.decode_bits(bdd_valuation))
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainEq<T> for MixedIntegerDomain<T> {
+    /// Uses the encoding given by [IntegerEncoding::default_for].
+    fn new_from_values(builder: &mut BddVariableSetBuilder, name: &str, values: &[T]) -> Self {
+        let max_value = values
+            .iter()
+            .max()
+            .expect("at least one value should be given");
+        Self::new(builder, name, max_value)
+    }
+
+    fn encode_comparison(
+        &self,
+        bdd_variable_set: &BddVariableSet,
+        operator: ComparisonOperator,
+        value: &T,
+    ) -> Result<Bdd, DomainError> {
+        dispatch!(self, domain => domain.encode_comparison(bdd_variable_set, operator, value))
+    }
+
+    fn get_all_possible_values(&self) -> Vec<T> {
+        dispatch!(self, domain => domain.get_all_possible_values())
+    }
+}
+
+impl<T: UnsignedInteger> SymbolicDomainOrd<T> for MixedIntegerDomain<T> {
+    /// Uses the encoding given by [IntegerEncoding::default_for].
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self {
+        Self::new_with_encoding(
+            builder,
+            name,
+            max_value,
+            IntegerEncoding::default_for(max_value),
+        )
+    }
+
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd {
+        dispatch!(self, domain => domain.encode_lt(bdd_variable_set, exclusive_upper_bound))
+    }
+
+    fn encode_le(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        dispatch!(self, domain => domain.encode_le(bdd_variable_set, value))
+    }
+
+    fn encode_gt(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        dispatch!(self, domain => domain.encode_gt(bdd_variable_set, value))
+    }
+
+    fn encode_ge(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        dispatch!(self, domain => domain.encode_ge(bdd_variable_set, value))
+    }
+
+    fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering {
         lhs.cmp(rhs)
     }
+}
+
+/// Implements [SymbolicDomain::rename_bdd_variables] for domains storing their (sorted)
+/// variables in a vector.
+fn rename_all(variables: &mut [BddVariable], permutation: &HashMap<BddVariable, BddVariable>) {
+    for variable in variables.iter_mut() {
+        if let Some(renamed) = permutation.get(variable) {
+            *variable = *renamed;
+        }
+    }
+    debug_assert!(
+        variables.windows(2).all(|pair| pair[0] < pair[1]),
+        "renaming should preserve the order of the variables"
+    );
+}
 
-    fn get_all_possible_values(&self) -> Vec<u8> {
-        (0..=self.max_value).collect()
+/// Encodes the set of all bit vectors (stored in `variables`, least significant bit first) that
+/// represent a number strictly less than the `exclusive_upper_bound`, without enumerating the
+/// values. If `gray_code` is set, the bits are interpreted as a (reflected) Gray code.
+///
+/// A number is smaller than the bound iff there is a bit in which the bound has `1`, the number
+/// has `0` and all the more significant bits of the two are equal. Equality of the more
+/// significant bits in binary is equivalent to their equality in the Gray code. Given such
+/// prefix, binary bit `i` of the number is `0` iff its Gray bit `i` is equal to the binary
+/// bit `i + 1` of the bound.
+fn encode_lt_bitwise(
+    bdd_variable_set: &BddVariableSet,
+    variables: &[BddVariable],
+    exclusive_upper_bound: u64,
+    gray_code: bool,
+) -> Bdd {
+    if exclusive_upper_bound >= 1 << variables.len() {
+        return bdd_variable_set.mk_true();
     }
+
+    let bound_bit = |idx: usize| exclusive_upper_bound & (1 << idx) != 0;
+    let encoded_bound = if gray_code {
+        exclusive_upper_bound ^ (exclusive_upper_bound >> 1)
+    } else {
+        exclusive_upper_bound
+    };
+
+    // one clause for every bit in which the number can become smaller than the bound
+    let mut clauses = Vec::new();
+    let mut equal_prefix = BddPartialValuation::empty();
+    for (idx, var) in variables.iter().enumerate().rev() {
+        if bound_bit(idx) {
+            let mut clause = equal_prefix.clone();
+            clause.set_value(*var, gray_code && bound_bit(idx + 1));
+            clauses.push(clause);
+        }
+        equal_prefix.set_value(*var, encoded_bound & (1 << idx) != 0);
+    }
+
+    bdd_variable_set.mk_dnf(&clauses)
 }
@@ -1,17 +1,31 @@
 use biodivine_lib_bdd::Bdd;
 use num_bigint::BigInt;
+use std::collections::HashMap;
 use std::fmt::Debug;
 
-use crate::utils::{count_states_exact, encode_state_map, pick_state_map};
+use crate::utils::count_states_exact;
 
-use crate::xml_parsing::utils::find_start_of;
+use crate::xml_parsing::{utils::find_start_of, variable_update_fn_parser::load_all_update_fns};
 
 use crate::symbolic_domains::symbolic_domain::{
-    BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
-    UnaryIntegerDomain,
+    BinaryIntegerDomain, GrayCodeIntegerDomain, IntegerEncoding, MixedIntegerDomain,
+    PetriNetIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain,
 };
 
-use crate::update::update_fn::SmartSystemUpdateFn;
+use crate::expression_components::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+};
+use crate::update::{
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::SmartSystemUpdateFn,
+};
+
+/// Random models and a differential check of the symbolic semantics against the explicit one.
+#[cfg(feature = "test-utils")]
+mod random_models;
+#[cfg(feature = "test-utils")]
+pub use random_models::{check_random_model, random_model, RandomModel, RandomModelOptions};
 
 pub struct ComputationStep {
     steps: usize,
@@ -19,14 +33,17 @@ pub struct ComputationStep {
     universe_binary: Bdd,
     universe_gray: Bdd,
     universe_petri_net: Bdd,
+    universe_mixed: Bdd,
     result_unary: Option<Bdd>,
     result_binary: Option<Bdd>,
     result_gray: Option<Bdd>,
     result_petri_net: Option<Bdd>,
+    result_mixed: Option<Bdd>,
     system_unary: SmartSystemUpdateFn<UnaryIntegerDomain, u8>,
     system_binary: SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>,
     system_gray: SmartSystemUpdateFn<GrayCodeIntegerDomain<u8>, u8>,
     system_petri_net: SmartSystemUpdateFn<PetriNetIntegerDomain, u8>,
+    system_mixed: SmartSystemUpdateFn<MixedIntegerDomain, u8>,
 }
 
 /// Perform one step of backward reachability procedure. Returns either a new [Bdd] value, or
@@ -70,6 +87,20 @@ fn fwd_step<D: SymbolicDomainOrd<u8> + Debug>(
     None
 }
 
+/// Encode a state given as a map from variable names to their values into a [Bdd] that is
+/// valid within the provided [SmartSystemUpdateFn].
+fn encode_state_from_map<D: SymbolicDomainOrd<u8> + Debug>(
+    system: &SmartSystemUpdateFn<D, u8>,
+    state: &HashMap<String, u8>,
+) -> Bdd {
+    let state = system
+        .state_from_map(state)
+        .expect("the state is valid within the system");
+    system
+        .encode_state(&state)
+        .expect("the state is valid within the system")
+}
+
 /// A generic function that builds [SmartSystemUpdateFn] from an SBML file.
 fn build_update_fn<D: SymbolicDomainOrd<u8> + Debug>(
     sbml_path: &str,
@@ -84,12 +115,30 @@ fn build_update_fn<D: SymbolicDomainOrd<u8> + Debug>(
     SmartSystemUpdateFn::try_from_xml(&mut xml).expect("Loading system fn update failed.")
 }
 
+/// Builds a [SmartSystemUpdateFn] from an SBML file where the multi-valued variables
+/// use different encodings (chosen arbitrarily, but deterministically, by their name).
+fn build_mixed_update_fn(sbml_path: &str) -> SmartSystemUpdateFn<MixedIntegerDomain, u8> {
+    let update_fns = load_update_fns(sbml_path);
+    SmartSystemUpdateFn::from_update_fns_with_encoding(update_fns, |var_name, max_value| {
+        if *max_value <= 1 {
+            return IntegerEncoding::default_for(max_value);
+        }
+        match var_name.len() % 4 {
+            0 => IntegerEncoding::Unary,
+            1 => IntegerEncoding::Binary,
+            2 => IntegerEncoding::GrayCode,
+            _ => IntegerEncoding::PetriNet,
+        }
+    })
+}
+
 impl ComputationStep {
     pub fn new(sbml_path: &str) -> ComputationStep {
         let system_unary = build_update_fn::<UnaryIntegerDomain>(sbml_path);
         let system_binary = build_update_fn::<BinaryIntegerDomain<u8>>(sbml_path);
         let system_gray = build_update_fn::<GrayCodeIntegerDomain<u8>>(sbml_path);
         let system_petri_net = build_update_fn::<PetriNetIntegerDomain>(sbml_path);
+        let system_mixed = build_mixed_update_fn(sbml_path);
 
         ComputationStep {
             steps: 0,
@@ -97,14 +146,17 @@ impl ComputationStep {
             result_binary: None,
             result_gray: None,
             result_petri_net: None,
+            result_mixed: None,
             universe_unary: system_unary.unit_vertex_set(),
             universe_binary: system_binary.unit_vertex_set(),
             universe_gray: system_gray.unit_vertex_set(),
             universe_petri_net: system_petri_net.unit_vertex_set(),
+            universe_mixed: system_mixed.unit_vertex_set(),
             system_unary,
             system_binary,
             system_gray,
             system_petri_net,
+            system_mixed,
         }
     }
 
@@ -126,12 +178,17 @@ impl ComputationStep {
     pub fn initialize(&mut self) {
         assert!(!self.is_done());
         assert!(self.can_initialize());
-        let state = pick_state_map::<UnaryIntegerDomain>(&self.system_unary, &self.universe_unary);
+        let state = self
+            .system_unary
+            .pick_state(&self.universe_unary)
+            .expect("the universe is not empty");
+        let state = self.system_unary.state_to_map(&state);
         self.steps = 0;
-        self.result_unary = Some(encode_state_map(&self.system_unary, &state));
-        self.result_binary = Some(encode_state_map(&self.system_binary, &state));
-        self.result_gray = Some(encode_state_map(&self.system_gray, &state));
-        self.result_petri_net = Some(encode_state_map(&self.system_petri_net, &state));
+        self.result_unary = Some(encode_state_from_map(&self.system_unary, &state));
+        self.result_binary = Some(encode_state_from_map(&self.system_binary, &state));
+        self.result_gray = Some(encode_state_from_map(&self.system_gray, &state));
+        self.result_petri_net = Some(encode_state_from_map(&self.system_petri_net, &state));
+        self.result_mixed = Some(encode_state_from_map(&self.system_mixed, &state));
     }
 
     pub fn perform_bwd_step(&mut self) {
@@ -143,6 +200,7 @@ impl ComputationStep {
             &self.system_petri_net,
             self.result_petri_net.as_ref().unwrap(),
         );
+        self.result_mixed = bwd_step(&self.system_mixed, self.result_mixed.as_ref().unwrap());
         if let Some(result_unary) = &self.result_unary {
             self.universe_unary = self.universe_unary.and_not(result_unary);
         }
@@ -155,6 +213,9 @@ impl ComputationStep {
         if let Some(result_petri_net) = &self.result_petri_net {
             self.universe_petri_net = self.universe_petri_net.and_not(result_petri_net);
         }
+        if let Some(result_mixed) = &self.result_mixed {
+            self.universe_mixed = self.universe_mixed.and_not(result_mixed);
+        }
     }
 
     pub fn perform_fwd_step(&mut self) {
@@ -166,6 +227,7 @@ impl ComputationStep {
             &self.system_petri_net,
             self.result_petri_net.as_ref().unwrap(),
         );
+        self.result_mixed = fwd_step(&self.system_mixed, self.result_mixed.as_ref().unwrap());
         if let Some(result_unary) = &self.result_unary {
             self.universe_unary = self.universe_unary.and_not(result_unary);
         }
@@ -178,6 +240,9 @@ impl ComputationStep {
         if let Some(result_petri_net) = &self.result_petri_net {
             self.universe_petri_net = self.universe_petri_net.and_not(result_petri_net);
         }
+        if let Some(result_mixed) = &self.result_mixed {
+            self.universe_mixed = self.universe_mixed.and_not(result_mixed);
+        }
     }
 
     pub fn check_consistency(&self) {
@@ -197,13 +262,18 @@ impl ComputationStep {
             .result_petri_net
             .as_ref()
             .map(|it| count_states_exact(&self.system_petri_net, it));
+        let count_mixed = self
+            .result_mixed
+            .as_ref()
+            .map(|it| count_states_exact(&self.system_mixed, it));
         if count_unary != count_binary
             || count_binary != count_gray
             || count_gray != count_petri_net
+            || count_petri_net != count_mixed
         {
             panic!(
-                "Error at step {}. {:?} <> {:?} <> {:?} <> {:?}",
-                self.steps, count_unary, count_binary, count_gray, count_petri_net
+                "Error at step {}. {:?} <> {:?} <> {:?} <> {:?} <> {:?}",
+                self.steps, count_unary, count_binary, count_gray, count_petri_net, count_mixed
             )
         } else {
             println!(
@@ -211,12 +281,61 @@ impl ComputationStep {
                 self.steps, count_unary
             );
             println!(
-                " > BDD sizes: {:?} {:?} {:?} {:?}",
+                " > BDD sizes: {:?} {:?} {:?} {:?} {:?}",
                 self.result_unary.as_ref().map(|it| it.size()),
                 self.result_binary.as_ref().map(|it| it.size()),
                 self.result_gray.as_ref().map(|it| it.size()),
                 self.result_petri_net.as_ref().map(|it| it.size()),
+                self.result_mixed.as_ref().map(|it| it.size()),
             );
         }
     }
 }
+
+/// Loads the update functions of all the variables from an SBML file.
+pub fn load_update_fns(sbml_path: &str) -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    let file = std::fs::File::open(sbml_path).expect("Cannot open SBML file.");
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(file));
+    find_start_of(&mut xml, "listOfTransitions")
+        .expect("Cannot find transitions in the SBML file.");
+    load_all_update_fns(&mut xml).expect("Loading system fn update failed.")
+}
+
+/// The expression consisting of the single proposition `variable <operator> value`.
+pub fn prop<T>(operator: ComparisonOperator, variable: &str, value: T) -> Expression<T> {
+    Expression::Terminal(Proposition::new(operator, variable.to_string(), value))
+}
+
+/// Two variables which copy each other, i.e. `p := q` and `q := p`. The states `p=0,q=1`
+/// and `p=1,q=0` can reach both fixed points `p=0,q=0` and `p=1,q=1`.
+pub fn mutual_copy() -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    let copy = |target: &str, source: &str| {
+        (
+            target.to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![source.to_string()],
+                target.to_string(),
+                vec![(1, prop(ComparisonOperator::Eq, source, 1))],
+                0,
+            ),
+        )
+    };
+    HashMap::from([copy("p", "q"), copy("q", "p")])
+}
+
+/// `p` and `q` inhibit each other, so `{p = 1, q = 0}` and `{p = 0, q = 1}` are the only
+/// attractors (both fixed points).
+pub fn toggle_switch() -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    let inhibited_by = |target: &str, regulator: &str| {
+        (
+            target.to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![],
+                target.to_string(),
+                vec![(1, prop(ComparisonOperator::Eq, regulator, 0))],
+                0,
+            ),
+        )
+    };
+    HashMap::from([inhibited_by("p", "q"), inhibited_by("q", "p")])
+}
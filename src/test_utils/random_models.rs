@@ -0,0 +1,370 @@
+use biodivine_lib_bdd::Bdd;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::explicit::explicit_system::ExplicitSystem;
+use crate::expression_components::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+};
+use crate::symbolic_domains::symbolic_domain::{
+    BinaryIntegerDomain, GrayCodeIntegerDomain, IntegerEncoding, MixedIntegerDomain,
+    PetriNetIntegerDomain, SymbolicDomainEq, UnaryIntegerDomain,
+};
+use crate::update::{
+    state::State,
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::{SmartSystemUpdateFn, SystemUpdateFn},
+};
+
+#[derive(Clone, Debug)]
+pub struct RandomModelOptions {
+    /// The number of system variables.
+    pub variables: usize,
+    /// The largest possible maximal value of a variable; every variable has a maximal value
+    /// of at least `1`.
+    pub max_value: u8,
+    /// The largest number of terms of an update function (besides the default).
+    pub max_terms: usize,
+    /// The largest nesting depth of the term conditions.
+    pub max_depth: usize,
+}
+
+impl Default for RandomModelOptions {
+    fn default() -> Self {
+        Self {
+            variables: 3,
+            max_value: 3,
+            max_terms: 3,
+            max_depth: 3,
+        }
+    }
+}
+
+/// A small random model, together with the domains of its variables (which are known
+/// independently of how the domains are inferred from the update functions).
+#[derive(Clone, Debug)]
+pub struct RandomModel {
+    /// The seed the model was generated from.
+    pub seed: u64,
+    pub update_fns: HashMap<String, UnprocessedVariableUpdateFn<u8>>,
+    pub max_values: HashMap<String, u8>,
+    /// The encodings used for the variables of the [MixedIntegerDomain] systems.
+    pub encodings: HashMap<String, IntegerEncoding>,
+}
+
+/// Generates a random model; the same `seed` and `options` always give the same model.
+///
+/// Every variable attains its maximal value (as the output of some term or the default)
+/// and all the propositions compare variables with values from their domains.
+pub fn random_model(seed: u64, options: &RandomModelOptions) -> RandomModel {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let domains = (0..options.variables)
+        .map(|idx| (format!("v{}", idx), rng.gen_range(1..=options.max_value)))
+        .collect::<Vec<_>>();
+
+    let update_fns = domains
+        .iter()
+        .map(|(name, max_value)| {
+            let terms_count = rng.gen_range(0..=options.max_terms);
+            let mut terms = (0..terms_count)
+                .map(|_| {
+                    let output = rng.gen_range(0..=*max_value);
+                    (
+                        output,
+                        random_expression(&mut rng, &domains, options.max_depth),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let mut default = rng.gen_range(0..=*max_value);
+            if default != *max_value && terms.iter().all(|(output, _)| output != max_value) {
+                match rng.gen_range(0..=terms.len()) {
+                    idx if idx == terms.len() => default = *max_value,
+                    idx => terms[idx].0 = *max_value,
+                }
+            }
+
+            let input_vars_names = terms
+                .iter()
+                .flat_map(|(_, condition)| condition.variables())
+                .map(str::to_string)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let update_fn =
+                UnprocessedVariableUpdateFn::new(input_vars_names, name.clone(), terms, default);
+            (name.clone(), update_fn)
+        })
+        .collect();
+
+    let encodings = domains
+        .iter()
+        .map(|(name, max_value)| {
+            let mut candidates = vec![
+                IntegerEncoding::Unary,
+                IntegerEncoding::Binary,
+                IntegerEncoding::GrayCode,
+                IntegerEncoding::PetriNet,
+            ];
+            if *max_value == 1 {
+                candidates.push(IntegerEncoding::Boolean);
+            }
+            let encoding = *candidates.choose(&mut rng).expect("there are candidates");
+            (name.clone(), encoding)
+        })
+        .collect();
+
+    RandomModel {
+        seed,
+        update_fns,
+        max_values: domains.into_iter().collect(),
+        encodings,
+    }
+}
+
+fn random_expression(
+    rng: &mut StdRng,
+    domains: &[(String, u8)],
+    max_depth: usize,
+) -> Expression<u8> {
+    if max_depth == 0 || rng.gen_bool(0.3) {
+        let operator = *[
+            ComparisonOperator::Eq,
+            ComparisonOperator::Neq,
+            ComparisonOperator::Lt,
+            ComparisonOperator::Gt,
+            ComparisonOperator::Leq,
+            ComparisonOperator::Geq,
+        ]
+        .choose(rng)
+        .expect("there are operators");
+        let (variable, max_value) = domains.choose(rng).expect("there are variables");
+        let value = rng.gen_range(0..=*max_value);
+        return Expression::Terminal(Proposition::new(operator, variable.clone(), value));
+    }
+
+    let operand = |rng: &mut StdRng| random_expression(rng, domains, max_depth - 1);
+    match rng.gen_range(0..5) {
+        0 => Expression::Not(Box::new(operand(rng))),
+        1 => Expression::Xor(Box::new(operand(rng)), Box::new(operand(rng))),
+        2 => Expression::Implies(Box::new(operand(rng)), Box::new(operand(rng))),
+        n => {
+            let operands = (0..rng.gen_range(0..=3)).map(|_| operand(rng)).collect();
+            if n == 3 {
+                Expression::And(operands)
+            } else {
+                Expression::Or(operands)
+            }
+        }
+    }
+}
+
+/// The operations of the symbolic systems which are compared with the explicit semantics.
+trait SymbolicAsyncSemantics {
+    fn encode_one(&self, variable_name: &str, value: &u8) -> Bdd;
+    fn mk_true(&self) -> Bdd;
+    fn successors_async(&self, variable_name: &str, set: &Bdd) -> Bdd;
+    fn predecessors_async(&self, variable_name: &str, set: &Bdd) -> Bdd;
+}
+
+impl<D: SymbolicDomainEq<u8>> SymbolicAsyncSemantics for SystemUpdateFn<D, u8> {
+    fn encode_one(&self, variable_name: &str, value: &u8) -> Bdd {
+        SystemUpdateFn::encode_one(self, variable_name, value)
+    }
+
+    fn mk_true(&self) -> Bdd {
+        self.get_bdd_variable_set().mk_true()
+    }
+
+    fn successors_async(&self, variable_name: &str, set: &Bdd) -> Bdd {
+        SystemUpdateFn::successors_async(self, variable_name, set)
+    }
+
+    fn predecessors_async(&self, variable_name: &str, set: &Bdd) -> Bdd {
+        SystemUpdateFn::predecessors_async(self, variable_name, set)
+    }
+}
+
+impl<D: SymbolicDomainEq<u8>> SymbolicAsyncSemantics for SmartSystemUpdateFn<D, u8> {
+    fn encode_one(&self, variable_name: &str, value: &u8) -> Bdd {
+        SmartSystemUpdateFn::encode_one(self, variable_name, value)
+    }
+
+    fn mk_true(&self) -> Bdd {
+        self.get_bdd_variable_set().mk_true()
+    }
+
+    fn successors_async(&self, variable_name: &str, set: &Bdd) -> Bdd {
+        SmartSystemUpdateFn::successors_async(self, variable_name, set)
+    }
+
+    fn predecessors_async(&self, variable_name: &str, set: &Bdd) -> Bdd {
+        SmartSystemUpdateFn::predecessors_async(self, variable_name, set.clone())
+    }
+}
+
+/// The explicit asynchronous transitions of a [RandomModel], computed by evaluating
+/// the update functions in every state.
+struct ExplicitTransitions {
+    /// The seed of the model, reported when a check fails.
+    seed: u64,
+    variables: Vec<String>,
+    states: Vec<State<u8>>,
+    /// `successors[s][v]` is the state reached from `states[s]` by updating the variable `v`.
+    successors: Vec<Vec<State<u8>>>,
+}
+
+impl ExplicitTransitions {
+    fn new(model: &RandomModel) -> Self {
+        let system = ExplicitSystem::new(model.update_fns.clone());
+        let variables = system.get_system_variables().to_vec();
+        let states = variables
+            .iter()
+            .fold(vec![Vec::new()], |partial_states, name| {
+                let max_value = model.max_values[name];
+                partial_states
+                    .into_iter()
+                    .flat_map(|values| {
+                        (0..=max_value).map(move |value| {
+                            let mut values = values.clone();
+                            values.push(value);
+                            values
+                        })
+                    })
+                    .collect()
+            });
+        let states = states.into_iter().map(State::new).collect::<Vec<_>>();
+        let successors = states
+            .iter()
+            .map(|state| {
+                (0..variables.len())
+                    .map(|idx| {
+                        let mut values = state.values().to_vec();
+                        values[idx] = system.next_value(state, idx);
+                        State::new(values)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            seed: model.seed,
+            variables,
+            states,
+            successors,
+        }
+    }
+
+    /// Compares the successors and predecessors of every single state under every variable
+    /// with the explicit ones.
+    fn check<S: SymbolicAsyncSemantics>(&self, system: &S, system_name: &str) {
+        let encode = |state: &State<u8>| {
+            self.variables
+                .iter()
+                .zip(state.values())
+                .fold(system.mk_true(), |acc, (name, value)| {
+                    acc.and(&system.encode_one(name, value))
+                })
+        };
+        let encoded = self.states.iter().map(encode).collect::<Vec<_>>();
+
+        for (idx, variable) in self.variables.iter().enumerate() {
+            let mut expected_predecessors = HashMap::<&State<u8>, Bdd>::new();
+            for (source, state) in self.states.iter().enumerate() {
+                let target = &self.successors[source][idx];
+                let expected = encode(target);
+                let predecessors = expected_predecessors
+                    .entry(target)
+                    .or_insert_with(|| system.mk_true().not());
+                *predecessors = predecessors.or(&encoded[source]);
+
+                let actual = system.successors_async(variable, &encoded[source]);
+                assert!(
+                    actual == expected,
+                    "seed {}, {}: wrong successors of {:?} under {}",
+                    self.seed,
+                    system_name,
+                    state,
+                    variable
+                );
+            }
+
+            for (target, state) in self.states.iter().enumerate() {
+                let expected = expected_predecessors
+                    .remove(state)
+                    .unwrap_or_else(|| system.mk_true().not());
+                let actual = system.predecessors_async(variable, &encoded[target]);
+                assert!(
+                    actual == expected,
+                    "seed {}, {}: wrong predecessors of {:?} under {}",
+                    self.seed,
+                    system_name,
+                    state,
+                    variable
+                );
+            }
+        }
+    }
+}
+
+/// Checks that the asynchronous successors and predecessors of every state of the `model`
+/// computed by [SystemUpdateFn] and [SmartSystemUpdateFn] match exactly the ones obtained
+/// by evaluating the update functions explicitly, for every integer encoding.
+///
+/// # Panics
+///
+/// Panics with the seed of the model, the name of the system and the offending state if they
+/// do not match.
+pub fn check_random_model(model: &RandomModel) {
+    let explicit = ExplicitTransitions::new(model);
+
+    explicit.check(
+        &SystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(model.update_fns.clone()),
+        "unary",
+    );
+    explicit.check(
+        &SystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(model.update_fns.clone()),
+        "binary",
+    );
+    explicit.check(
+        &SystemUpdateFn::<GrayCodeIntegerDomain<u8>, u8>::from_update_fns(model.update_fns.clone()),
+        "gray",
+    );
+    explicit.check(
+        &SystemUpdateFn::<PetriNetIntegerDomain, u8>::from_update_fns(model.update_fns.clone()),
+        "petri net",
+    );
+    explicit.check(
+        &SystemUpdateFn::<MixedIntegerDomain, u8>::from_update_fns(model.update_fns.clone()),
+        "mixed",
+    );
+
+    explicit.check(
+        &SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(model.update_fns.clone()),
+        "smart unary",
+    );
+    explicit.check(
+        &SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(
+            model.update_fns.clone(),
+        ),
+        "smart binary",
+    );
+    explicit.check(
+        &SmartSystemUpdateFn::<GrayCodeIntegerDomain<u8>, u8>::from_update_fns(
+            model.update_fns.clone(),
+        ),
+        "smart gray",
+    );
+    explicit.check(
+        &SmartSystemUpdateFn::<PetriNetIntegerDomain, u8>::from_update_fns(
+            model.update_fns.clone(),
+        ),
+        "smart petri net",
+    );
+    explicit.check(
+        &SmartSystemUpdateFn::from_update_fns_with_encoding(
+            model.update_fns.clone(),
+            |var_name, _| model.encodings[var_name],
+        ),
+        "smart mixed",
+    );
+}
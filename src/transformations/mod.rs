@@ -0,0 +1,2 @@
+pub mod percolation;
+pub mod reduction;
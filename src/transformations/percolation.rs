@@ -0,0 +1,131 @@
+use std::{collections::HashMap, fmt::Display};
+
+use biodivine_lib_bdd::Bdd;
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    update::{
+        state::StateError,
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn as UnprocessedFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+};
+
+use super::reduction::{constant_output, substitute};
+
+/// Propagates the partial assignment of `fixed` variables through the model given as a map
+/// of update functions (as produced by the SBML parser), in the style of trap space
+/// percolation.
+///
+/// The fixed values are substituted into the update functions, and every variable whose
+/// update function becomes constant is fixed to that value as well, until a fixpoint is
+/// reached. Returns the extended assignment; the values of the originally `fixed` variables
+/// are kept even if their update functions disagree with them.
+///
+/// The update functions are simplified syntactically, so a function which is constant only
+/// semantically (e.g. `p = 0 | p != 0`) may not be detected; see
+/// [SmartSystemUpdateFn::percolate] for the exact symbolic variant.
+pub fn percolate<T>(
+    model: &HashMap<String, UnprocessedFn<T>>,
+    fixed: &HashMap<String, T>,
+) -> HashMap<String, T>
+where
+    T: Clone + Ord,
+{
+    let mut fixed = fixed.clone();
+    let mut remaining = model
+        .iter()
+        .filter(|(var_name, _)| !fixed.contains_key(*var_name))
+        .map(|(var_name, update_fn)| (var_name.clone(), update_fn.clone()))
+        .collect::<HashMap<_, _>>();
+
+    loop {
+        let known_values = fixed
+            .iter()
+            .map(|(var_name, value)| (var_name.as_str(), value))
+            .collect::<HashMap<_, _>>();
+        remaining
+            .values_mut()
+            .for_each(|update_fn| substitute(update_fn, &known_values));
+
+        let constants = remaining
+            .iter()
+            .filter_map(|(var_name, update_fn)| {
+                constant_output(update_fn).map(|value| (var_name.clone(), value.clone()))
+            })
+            .collect::<Vec<_>>();
+        if constants.is_empty() {
+            return fixed;
+        }
+        for (var_name, value) in constants {
+            remaining.remove(&var_name);
+            fixed.insert(var_name, value);
+        }
+    }
+}
+
+impl<D, T> SmartSystemUpdateFn<D, T>
+where
+    D: SymbolicDomainOrd<T>,
+    T: Clone,
+{
+    /// Propagates the `subspace` through the system, i.e. repeatedly fixes every variable
+    /// which is free in the `subspace`, but which can only be updated to a single value from
+    /// the states of the `subspace`, until a fixpoint is reached.
+    ///
+    /// Unlike [percolate], this uses the exact semantics of the update functions. If the
+    /// system has parameters, a variable is only fixed if its value is the same for all the
+    /// colors of the `subspace`.
+    pub fn percolate(&self, subspace: &Bdd) -> Bdd {
+        let variables = self.get_system_variables();
+        let mut result = subspace.and(&self.unit_vertex_set());
+        loop {
+            let mut changed = false;
+            for variable in &variables {
+                if self.values_in(variable, &result).len() > 1 {
+                    let updated = self.successors_async(variable, &result);
+                    if let [value] = &self.values_in(variable, &updated)[..] {
+                        result = result.and(&self.encode_one(variable, value));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                return result;
+            }
+        }
+    }
+
+    /// Same as [Self::percolate], but the subspace is given by a partial assignment of
+    /// the `fixed` variables, and the result is the extended assignment.
+    ///
+    /// Returns an error if `fixed` contains an unknown variable or a value outside the domain
+    /// of its variable.
+    pub fn percolate_assignment(
+        &self,
+        fixed: &HashMap<String, T>,
+    ) -> Result<HashMap<String, T>, StateError>
+    where
+        T: PartialEq + Display,
+    {
+        let subspace = self.percolate(&self.initial_states_from_levels(fixed)?);
+        Ok(self
+            .get_system_variables()
+            .into_iter()
+            .filter_map(|variable| match &self.values_in(&variable, &subspace)[..] {
+                [value] => Some((variable, value.clone())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// The values of the `variable` in the states of the `set`.
+    fn values_in(&self, variable: &str, set: &Bdd) -> Vec<T> {
+        self.get_domain(variable)
+            .expect("known variable")
+            .get_all_possible_values()
+            .into_iter()
+            .filter(|value| !set.and(&self.encode_one(variable, value)).is_false())
+            .collect()
+    }
+}
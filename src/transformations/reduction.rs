@@ -0,0 +1,354 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use crate::{
+    expression_components::{expression::Expression, proposition::Proposition},
+    update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn as UnprocessedFn,
+};
+
+/// Selects which reduction steps are applied by [reduce].
+#[derive(Clone, Copy, Debug)]
+pub struct ReductionOptions {
+    /// Replace variables whose update function is constant by their (eventual) value.
+    /// This also covers the inputs of the model, which are constant by default.
+    pub propagate_constants: bool,
+    /// Remove variables that do not appear in any update function, including their own.
+    /// Such variables do not influence the rest of the model, hence their removal does not
+    /// change the number of attractors.
+    pub remove_unused_outputs: bool,
+    /// Substitute the update function of every variable without a self-loop into the update
+    /// functions of its targets (Naldi et al. 2011). This preserves the fixed points of the
+    /// model, but it can considerably increase the size of the remaining update functions.
+    pub eliminate_intermediates: bool,
+}
+
+impl Default for ReductionOptions {
+    fn default() -> Self {
+        Self {
+            propagate_constants: true,
+            remove_unused_outputs: true,
+            eliminate_intermediates: false,
+        }
+    }
+}
+
+/// Summary of the variables that were removed by [reduce], in the order of their removal.
+#[derive(Debug)]
+pub struct ReductionReport<T> {
+    pub constants: Vec<(String, T)>,
+    pub unused_outputs: Vec<String>,
+    pub eliminated_intermediates: Vec<String>,
+}
+
+impl<T> ReductionReport<T> {
+    /// The total number of variables removed from the model.
+    pub fn removed_count(&self) -> usize {
+        self.constants.len() + self.unused_outputs.len() + self.eliminated_intermediates.len()
+    }
+}
+
+impl<T: Display> Display for ReductionReport<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Removed {} variable(s).", self.removed_count())?;
+        writeln!(
+            f,
+            " > constants ({}): [{}]",
+            self.constants.len(),
+            self.constants
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(
+            f,
+            " > unused outputs ({}): [{}]",
+            self.unused_outputs.len(),
+            self.unused_outputs.join(", ")
+        )?;
+        write!(
+            f,
+            " > eliminated intermediates ({}): [{}]",
+            self.eliminated_intermediates.len(),
+            self.eliminated_intermediates.join(", ")
+        )
+    }
+}
+
+/// Reduces the model given as a map of update functions (as produced by the SBML parser).
+///
+/// The selected steps of [ReductionOptions] are repeated until none of them can remove
+/// any further variable. Returns the reduced model, together with a report of what was removed.
+/// The `input_vars_names` of the remaining update functions are updated to only contain
+/// variables which are still present in the model.
+pub fn reduce<T>(
+    mut model: HashMap<String, UnprocessedFn<T>>,
+    options: ReductionOptions,
+) -> (HashMap<String, UnprocessedFn<T>>, ReductionReport<T>)
+where
+    T: Clone + Ord,
+{
+    let mut report = ReductionReport {
+        constants: Vec::new(),
+        unused_outputs: Vec::new(),
+        eliminated_intermediates: Vec::new(),
+    };
+
+    loop {
+        let mut changed = false;
+        if options.propagate_constants {
+            changed |= propagate_constants(&mut model, &mut report.constants);
+        }
+        if options.remove_unused_outputs {
+            changed |= remove_unused_outputs(&mut model, &mut report.unused_outputs);
+        }
+        if options.eliminate_intermediates {
+            changed |= eliminate_intermediate(&mut model, &mut report.eliminated_intermediates);
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let remaining_variables = model.keys().cloned().collect::<BTreeSet<_>>();
+    model
+        .values_mut()
+        .for_each(|update_fn| refresh_input_vars(update_fn, &remaining_variables));
+
+    (model, report)
+}
+
+/// Repeatedly removes all variables with a constant update function and substitutes their
+/// value into the remaining update functions. Returns `true` if anything was removed.
+fn propagate_constants<T>(
+    model: &mut HashMap<String, UnprocessedFn<T>>,
+    found_constants: &mut Vec<(String, T)>,
+) -> bool
+where
+    T: Clone + Ord,
+{
+    let mut changed = false;
+    loop {
+        let constants = sorted_variables(model)
+            .into_iter()
+            .filter_map(|var_name| {
+                constant_output(&model[&var_name]).map(|value| (var_name, value.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        if constants.is_empty() {
+            return changed;
+        }
+        changed = true;
+
+        constants.iter().for_each(|(var_name, _)| {
+            model.remove(var_name);
+        });
+
+        let known_values = constants
+            .iter()
+            .map(|(var_name, value)| (var_name.as_str(), value))
+            .collect::<HashMap<_, _>>();
+        model
+            .values_mut()
+            .for_each(|update_fn| substitute(update_fn, &known_values));
+
+        found_constants.extend(constants);
+    }
+}
+
+/// Substitutes the `known_values` of variables into the conditions of the terms of
+/// `update_fn`, and removes the terms that can no longer be applied.
+pub(super) fn substitute<T>(update_fn: &mut UnprocessedFn<T>, known_values: &HashMap<&str, &T>)
+where
+    T: Clone + Ord,
+{
+    update_fn.terms = std::mem::take(&mut update_fn.terms)
+        .into_iter()
+        .map(|(value, condition)| {
+            let condition = condition.map_propositions(&|proposition| match known_values
+                .get(proposition.variable.as_str())
+            {
+                Some(known_value) if proposition.evaluate(known_value) => Expression::mk_true(),
+                Some(_) => Expression::mk_false(),
+                None => Expression::Terminal(proposition.clone()),
+            });
+            (value, condition)
+        })
+        .collect();
+    prune_terms(update_fn);
+}
+
+/// Repeatedly removes all variables that do not appear in any update function.
+/// Returns `true` if anything was removed.
+fn remove_unused_outputs<T>(
+    model: &mut HashMap<String, UnprocessedFn<T>>,
+    found_outputs: &mut Vec<String>,
+) -> bool {
+    let mut changed = false;
+    loop {
+        let outputs = sorted_variables(model)
+            .into_iter()
+            .filter(|var_name| {
+                !model
+                    .values()
+                    .any(|update_fn| references(update_fn, var_name))
+            })
+            .collect::<Vec<_>>();
+
+        if outputs.is_empty() {
+            return changed;
+        }
+        changed = true;
+
+        outputs.iter().for_each(|var_name| {
+            model.remove(var_name);
+        });
+        found_outputs.extend(outputs);
+    }
+}
+
+/// Eliminates the first (alphabetically) variable that does not regulate itself, but regulates
+/// some other variable. Returns `true` if a variable was eliminated.
+fn eliminate_intermediate<T>(
+    model: &mut HashMap<String, UnprocessedFn<T>>,
+    eliminated: &mut Vec<String>,
+) -> bool
+where
+    T: Clone + Ord,
+{
+    let candidate = sorted_variables(model).into_iter().find(|var_name| {
+        !references(&model[var_name], var_name)
+            && model.iter().any(|(other_name, other_fn)| {
+                other_name != var_name && references(other_fn, var_name)
+            })
+    });
+
+    let Some(var_name) = candidate else {
+        return false;
+    };
+
+    let eliminated_fn = model.remove(&var_name).expect("candidate is in the model");
+
+    model.values_mut().for_each(|update_fn| {
+        if !references(update_fn, &var_name) {
+            return;
+        }
+        update_fn.terms = std::mem::take(&mut update_fn.terms)
+            .into_iter()
+            .map(|(value, condition)| {
+                let condition = condition.map_propositions(&|proposition| {
+                    if proposition.variable == var_name {
+                        output_condition(&eliminated_fn, proposition)
+                    } else {
+                        Expression::Terminal(proposition.clone())
+                    }
+                });
+                (value, condition)
+            })
+            .collect();
+        prune_terms(update_fn);
+    });
+
+    eliminated.push(var_name);
+    true
+}
+
+/// Builds the condition under which the output of `update_fn` satisfies the `proposition`
+/// (the variable of the proposition is ignored).
+fn output_condition<T>(update_fn: &UnprocessedFn<T>, proposition: &Proposition<T>) -> Expression<T>
+where
+    T: Clone + Ord,
+{
+    // Term `i` is applied only if none of the previous terms matched.
+    let not_previous = |idx: usize| {
+        update_fn.terms[..idx]
+            .iter()
+            .map(|(_, condition)| Expression::Not(Box::new(condition.clone())))
+            .collect::<Vec<_>>()
+    };
+
+    let mut disjuncts = update_fn
+        .terms
+        .iter()
+        .enumerate()
+        .filter(|(_, (value, _))| proposition.evaluate(value))
+        .map(|(idx, (_, condition))| {
+            let mut conjuncts = not_previous(idx);
+            conjuncts.push(condition.clone());
+            Expression::And(conjuncts)
+        })
+        .collect::<Vec<_>>();
+
+    if proposition.evaluate(&update_fn.default) {
+        disjuncts.push(Expression::And(not_previous(update_fn.terms.len())));
+    }
+
+    Expression::Or(disjuncts).propagate_constants()
+}
+
+/// If the update function always produces the same value, returns it.
+pub(super) fn constant_output<T: PartialEq>(update_fn: &UnprocessedFn<T>) -> Option<&T> {
+    update_fn
+        .terms
+        .iter()
+        .all(|(value, _)| value == &update_fn.default)
+        .then_some(&update_fn.default)
+}
+
+/// Simplifies the conditions of the terms, removes terms that can never be applied and
+/// turns the first term that is always applied into the default.
+fn prune_terms<T: PartialEq>(update_fn: &mut UnprocessedFn<T>) {
+    let mut pruned = Vec::new();
+    for (value, condition) in std::mem::take(&mut update_fn.terms) {
+        let condition = condition.propagate_constants();
+        match condition.as_constant() {
+            Some(false) => {}
+            Some(true) => {
+                update_fn.default = value;
+                break;
+            }
+            None => pruned.push((value, condition)),
+        }
+    }
+
+    // trailing terms that produce the default value are redundant
+    while matches!(pruned.last(), Some((value, _)) if value == &update_fn.default) {
+        pruned.pop();
+    }
+
+    update_fn.terms = pruned;
+}
+
+fn refresh_input_vars<T>(update_fn: &mut UnprocessedFn<T>, remaining_variables: &BTreeSet<String>) {
+    let referenced = update_fn
+        .terms
+        .iter()
+        .flat_map(|(_, condition)| condition.variables())
+        .map(|var_name| var_name.to_owned())
+        .collect::<BTreeSet<_>>();
+
+    update_fn
+        .input_vars_names
+        .retain(|var_name| remaining_variables.contains(var_name));
+    referenced.into_iter().for_each(|var_name| {
+        if !update_fn.input_vars_names.contains(&var_name) {
+            update_fn.input_vars_names.push(var_name);
+        }
+    });
+}
+
+fn references<T>(update_fn: &UnprocessedFn<T>, var_name: &str) -> bool {
+    update_fn
+        .terms
+        .iter()
+        .any(|(_, condition)| condition.variables().contains(var_name))
+}
+
+fn sorted_variables<T>(model: &HashMap<String, UnprocessedFn<T>>) -> Vec<String> {
+    let mut var_names = model.keys().cloned().collect::<Vec<_>>();
+    var_names.sort();
+    var_names
+}
@@ -0,0 +1,217 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
+
+use crate::{
+    expression_components::{expression::Expression, simplification::values_to_expression},
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+};
+
+use super::update_fn::variable_update_fn::VariableUpdateFn;
+
+/// Decodes the `bdd` into an equivalent `Expression` over the variables of `named_domains`.
+///
+/// Only valuations that are valid in the given domains are considered, i.e. the result is
+/// equivalent to the `bdd` restricted to the admissible values of all domains. Variables
+/// that do not belong to any of the domains (e.g. primed variables) are existentially
+/// quantified before decoding. The domains should be listed in the order in which their
+/// variables appear in the `bdd_variable_set`, otherwise the result can be unnecessarily large.
+///
+/// The decoding treats the `bdd` as a multi-valued decision diagram: for each variable, values
+/// that lead to the same remaining set are grouped together and described using `<=`, `>=`,
+/// `=` or `!=` propositions.
+pub fn expression_from_bdd<DO, T>(
+    bdd: &Bdd,
+    named_domains: &[(&str, &DO)],
+    bdd_variable_set: &BddVariableSet,
+) -> Expression<T>
+where
+    DO: SymbolicDomainOrd<T>,
+    T: Clone + Ord,
+{
+    let domain_variables = named_domains
+        .iter()
+        .flat_map(|(_, domain)| domain.raw_bdd_variables())
+        .collect::<HashSet<_>>();
+    let other_variables = bdd_variable_set
+        .variables()
+        .into_iter()
+        .filter(|var| !domain_variables.contains(var))
+        .collect::<Vec<_>>();
+
+    let levels = named_domains
+        .iter()
+        .map(|(var_name, domain)| {
+            let mut values = domain.get_all_possible_values();
+            values.sort_by(DO::cmp);
+            let variables = domain.raw_bdd_variables();
+            let encoded_values = values
+                .iter()
+                .map(|value| {
+                    variables
+                        .iter()
+                        .cloned()
+                        .zip(domain.raw_bdd_variables_encode(value))
+                        .collect()
+                })
+                .collect();
+            Level {
+                var_name,
+                variables,
+                values,
+                encoded_values,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut decoder = Decoder {
+        levels: &levels,
+        cache: HashMap::new(),
+    };
+    let expression = decoder.decode(&bdd.exists(&other_variables), 0);
+
+    let domains = levels
+        .iter()
+        .map(|level| (level.var_name.to_string(), level.values.clone()))
+        .collect();
+    expression.simplify(&domains)
+}
+
+/// Decodes the function represented by the `bit_answering_bdds` of `update_fn` into a list of
+/// terms, one for each value of the `target_domain` that can be produced by the function.
+///
+/// The conditions of the terms are mutually exclusive and the values are sorted in ascending
+/// order. Values that are never produced are omitted.
+pub fn terms_from_update_fn<DO, T>(
+    update_fn: &VariableUpdateFn,
+    target_domain: &DO,
+    named_domains: &[(&str, &DO)],
+    bdd_variable_set: &BddVariableSet,
+) -> Vec<(T, Expression<T>)>
+where
+    DO: SymbolicDomainOrd<T>,
+    T: Clone + Ord,
+{
+    let unit_set = named_domains
+        .iter()
+        .fold(bdd_variable_set.mk_true(), |acc, (_, domain)| {
+            acc.and(&domain.unit_collection(bdd_variable_set))
+        });
+
+    terms_from_conditions(
+        target_domain,
+        |value| {
+            update_fn
+                .bit_answering_bdds
+                .iter()
+                .zip(target_domain.raw_bdd_variables_encode(value))
+                .fold(unit_set.clone(), |acc, ((_, bit_bdd), bit)| {
+                    if bit {
+                        acc.and(bit_bdd)
+                    } else {
+                        acc.and_not(bit_bdd)
+                    }
+                })
+        },
+        named_domains,
+        bdd_variable_set,
+    )
+}
+
+/// Decodes the `condition` under which a variable is updated to each value of its
+/// `target_domain` into a list of terms.
+///
+/// The values are sorted in ascending order. Values with an empty condition are omitted.
+pub fn terms_from_conditions<DO, T>(
+    target_domain: &DO,
+    condition: impl Fn(&T) -> Bdd,
+    named_domains: &[(&str, &DO)],
+    bdd_variable_set: &BddVariableSet,
+) -> Vec<(T, Expression<T>)>
+where
+    DO: SymbolicDomainOrd<T>,
+    T: Clone + Ord,
+{
+    let mut values = target_domain.get_all_possible_values();
+    values.sort_by(DO::cmp);
+
+    values
+        .into_iter()
+        .filter_map(|value| {
+            let condition = condition(&value);
+            (!condition.is_false()).then(|| {
+                let expression = expression_from_bdd(&condition, named_domains, bdd_variable_set);
+                (value, expression)
+            })
+        })
+        .collect()
+}
+
+struct Level<'a, T> {
+    var_name: &'a str,
+    variables: Vec<BddVariable>,
+    /// sorted in ascending order
+    values: Vec<T>,
+    /// `encoded_values[i]` is the encoding of `values[i]`
+    encoded_values: Vec<Vec<(BddVariable, bool)>>,
+}
+
+struct Decoder<'a, 'b, T> {
+    levels: &'b [Level<'a, T>],
+    cache: HashMap<(usize, Bdd), Expression<T>>,
+}
+
+impl<T: Clone + Ord> Decoder<'_, '_, T> {
+    fn decode(&mut self, bdd: &Bdd, level_idx: usize) -> Expression<T> {
+        if bdd.is_false() {
+            return Expression::mk_false();
+        }
+        if bdd.is_true() || level_idx == self.levels.len() {
+            return Expression::mk_true();
+        }
+
+        let level = &self.levels[level_idx];
+        let support = bdd.support_set();
+        if !level.variables.iter().any(|var| support.contains(var)) {
+            return self.decode(bdd, level_idx + 1);
+        }
+
+        let key = (level_idx, bdd.clone());
+        if let Some(cached) = self.cache.get(&key) {
+            return cached.clone();
+        }
+
+        // group the values that lead to the same remaining set
+        let mut groups = Vec::<(Bdd, Vec<bool>)>::new();
+        for (value_idx, encoded_value) in level.encoded_values.iter().enumerate() {
+            let cofactor = bdd.restrict(encoded_value);
+            match groups.iter_mut().find(|(it, _)| it == &cofactor) {
+                Some((_, allowed)) => allowed[value_idx] = true,
+                None => {
+                    let mut allowed = vec![false; level.values.len()];
+                    allowed[value_idx] = true;
+                    groups.push((cofactor, allowed));
+                }
+            }
+        }
+
+        let result = if groups.len() == 1 {
+            self.decode(&groups[0].0, level_idx + 1)
+        } else {
+            let disjuncts = groups
+                .iter()
+                .filter(|(cofactor, _)| !cofactor.is_false())
+                .map(|(cofactor, allowed)| {
+                    Expression::And(vec![
+                        values_to_expression(level.var_name, &level.values, allowed),
+                        self.decode(cofactor, level_idx + 1),
+                    ])
+                })
+                .collect();
+            Expression::Or(disjuncts).propagate_constants()
+        };
+
+        self.cache.insert(key, result.clone());
+        result
+    }
+}
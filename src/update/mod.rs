@@ -1,2 +1,8 @@
+pub mod expression_from_bdd;
+pub mod parametrized_update_function;
+pub mod persistence;
+pub mod perturbation;
+pub mod state;
 pub mod unprocessed_variable_update_function;
 pub mod update_fn;
+pub mod variable_ordering;
@@ -0,0 +1,108 @@
+use std::collections::BTreeSet;
+
+use crate::expression_components::expression::Expression;
+
+use super::unprocessed_variable_update_function::UnprocessedVariableUpdateFn;
+
+/// An unknown (uninterpreted) function symbol of the given system variables.
+///
+/// Its value is an arbitrary value of the target variable of the update function in which
+/// it is used, possibly different for every valuation of the `arguments`. A symbol without
+/// arguments is simply an unknown constant. The same symbol can be shared by multiple
+/// update functions (of variables with the same domain), in which case it has the same
+/// meaning in all of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UninterpretedFn {
+    pub name: String,
+    pub arguments: Vec<String>,
+}
+
+impl UninterpretedFn {
+    pub fn new(name: String, arguments: Vec<String>) -> Self {
+        Self { name, arguments }
+    }
+
+    /// An unknown constant, i.e. a function without arguments.
+    pub fn parameter(name: String) -> Self {
+        Self::new(name, Vec::new())
+    }
+}
+
+/// The value a term of a [ParametrizedVariableUpdateFn] updates its target variable to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TermOutput<T> {
+    Value(T),
+    Unknown(UninterpretedFn),
+}
+
+/// Like [UnprocessedVariableUpdateFn], but the outputs of the terms (and the default) may
+/// be unknown. A system built from such update functions represents a whole family of
+/// models, one for every interpretation of the [UninterpretedFn]-s.
+#[derive(Clone, Debug)]
+pub struct ParametrizedVariableUpdateFn<T> {
+    pub input_vars_names: Vec<String>,
+    pub target_var_name: String,
+    pub terms: Vec<(TermOutput<T>, Expression<T>)>,
+    pub default: TermOutput<T>,
+    /// Values of the target variable that do not appear as known outputs. The unknown
+    /// outputs range over the whole domain of the variable, so it has to be given
+    /// explicitly if it cannot be inferred from the known outputs.
+    pub values: Vec<T>,
+}
+
+impl<T> ParametrizedVariableUpdateFn<T> {
+    pub fn new(
+        input_vars_names: Vec<String>,
+        target_var_name: String,
+        terms: Vec<(TermOutput<T>, Expression<T>)>,
+        default: TermOutput<T>,
+        values: Vec<T>,
+    ) -> Self {
+        Self {
+            input_vars_names,
+            target_var_name,
+            terms,
+            default,
+            values,
+        }
+    }
+
+    /// The outputs of all terms, followed by the default.
+    pub fn outputs(&self) -> impl Iterator<Item = &TermOutput<T>> {
+        self.terms
+            .iter()
+            .map(|(output, _)| output)
+            .chain([&self.default])
+    }
+
+    /// The names of all variables this update function depends on, i.e. the inputs,
+    /// the variables in the conditions of the terms and the arguments of the unknown outputs.
+    pub fn variables(&self) -> BTreeSet<&str> {
+        let arguments = self.outputs().flat_map(|output| match output {
+            TermOutput::Value(_) => [].iter(),
+            TermOutput::Unknown(function) => function.arguments.iter(),
+        });
+        self.input_vars_names
+            .iter()
+            .chain(arguments)
+            .map(|name| name.as_str())
+            .chain(self.terms.iter().flat_map(|(_, term)| term.variables()))
+            .collect()
+    }
+}
+
+impl<T> From<UnprocessedVariableUpdateFn<T>> for ParametrizedVariableUpdateFn<T> {
+    fn from(update_fn: UnprocessedVariableUpdateFn<T>) -> Self {
+        Self::new(
+            update_fn.input_vars_names,
+            update_fn.target_var_name,
+            update_fn
+                .terms
+                .into_iter()
+                .map(|(output, term)| (TermOutput::Value(output), term))
+                .collect(),
+            TermOutput::Value(update_fn.default),
+            Vec::new(),
+        )
+    }
+}
@@ -0,0 +1,656 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    io::{BufRead, ErrorKind, Read, Write},
+};
+
+use biodivine_lib_bdd::{Bdd, BddPointer, BddVariable, BddVariableSet, BddVariableSetBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::symbolic_domains::symbolic_domain::{
+    BinaryIntegerDomain, BooleanDomain, EnumeratedDomain, GrayCodeIntegerDomain, IntegerEncoding,
+    MixedIntegerDomain, PetriNetIntegerDomain, SymbolicDomainEq, SymbolicDomainOrd,
+    UnaryIntegerDomain, UnsignedInteger,
+};
+
+use super::update_fn::{ParameterInfo, SmartSystemUpdateFn, VarInfo};
+
+/// The version of the formats written by [SmartSystemUpdateFn::write_compiled] and
+/// [SmartSystemUpdateFn::write_states]. Files written in a different version are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+const COMPILED_SYSTEM: &str = "compiled-system";
+const STATE_SET: &str = "state-set";
+
+#[derive(Error, Debug)]
+pub enum PersistenceError {
+    Io(#[from] std::io::Error),
+    InvalidHeader(#[from] serde_json::Error),
+    /// The file does not contain the expected kind of data.
+    UnexpectedContent {
+        expected: String,
+        found: String,
+    },
+    UnsupportedVersion(u32),
+    /// The file was created from a different model (or a different version of the model).
+    ModelChanged {
+        expected: u64,
+        found: u64,
+    },
+    /// The file was created for a system with a different type of domains.
+    DomainMismatch {
+        expected: String,
+        found: String,
+    },
+    /// The saved domains or BDDs do not fit the domains recreated when loading.
+    LayoutMismatch(String),
+}
+
+impl Display for PersistenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PersistenceError::Io(error) => write!(f, "IO error: {}", error),
+            PersistenceError::InvalidHeader(error) => write!(f, "Invalid header: {}", error),
+            PersistenceError::UnexpectedContent { expected, found } => {
+                write!(f, "Expected a {} file, found {}", expected, found)
+            }
+            PersistenceError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported format version {} (expected {})",
+                version, FORMAT_VERSION
+            ),
+            PersistenceError::ModelChanged { expected, found } => write!(
+                f,
+                "Saved for model {:016x}, but the model is {:016x}",
+                found, expected
+            ),
+            PersistenceError::DomainMismatch { expected, found } => {
+                write!(f, "Saved with domain {}, expected {}", found, expected)
+            }
+            PersistenceError::LayoutMismatch(message) => {
+                write!(f, "The saved layout does not match: {}", message)
+            }
+        }
+    }
+}
+
+/// A domain which can be saved as a part of a system (see
+/// [SmartSystemUpdateFn::write_compiled]) and recreated when the system is loaded.
+pub trait PersistentDomain<T>: SymbolicDomainEq<T> + Sized {
+    /// Identifies the type of the domain in the saved files. Unlike [std::any::type_name],
+    /// it does not change between versions of Rust or when the type is moved.
+    const DOMAIN_TYPE: &'static str;
+
+    /// The encoding of the values, for the domains which can encode them in more ways.
+    fn integer_encoding(&self) -> Option<IntegerEncoding> {
+        None
+    }
+
+    /// Recreates a saved domain from its `values` and its [Self::integer_encoding].
+    fn restore(
+        builder: &mut BddVariableSetBuilder,
+        name: &str,
+        values: &[T],
+        _encoding: Option<IntegerEncoding>,
+    ) -> Self {
+        Self::new_from_values(builder, name, values)
+    }
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for UnaryIntegerDomain<T> {
+    const DOMAIN_TYPE: &'static str = "unary";
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for PetriNetIntegerDomain<T> {
+    const DOMAIN_TYPE: &'static str = "petri-net";
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for BinaryIntegerDomain<T> {
+    const DOMAIN_TYPE: &'static str = "binary";
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for GrayCodeIntegerDomain<T> {
+    const DOMAIN_TYPE: &'static str = "gray-code";
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for BooleanDomain<T> {
+    const DOMAIN_TYPE: &'static str = "boolean";
+}
+
+impl<T: Clone + PartialEq + Debug> PersistentDomain<T> for EnumeratedDomain<T> {
+    const DOMAIN_TYPE: &'static str = "enumerated";
+}
+
+impl<T: UnsignedInteger> PersistentDomain<T> for MixedIntegerDomain<T> {
+    const DOMAIN_TYPE: &'static str = "mixed";
+
+    fn integer_encoding(&self) -> Option<IntegerEncoding> {
+        Some(self.encoding())
+    }
+
+    fn restore(
+        builder: &mut BddVariableSetBuilder,
+        name: &str,
+        values: &[T],
+        encoding: Option<IntegerEncoding>,
+    ) -> Self {
+        let max_value = values.iter().max();
+        match (encoding, max_value) {
+            // a corrupt Boolean encoding of a larger domain is reported as a layout mismatch
+            (Some(IntegerEncoding::Boolean), Some(max_value)) if max_value.to_usize() > 1 => {
+                Self::new(builder, name, max_value)
+            }
+            (Some(encoding), Some(max_value)) => {
+                Self::new_with_encoding(builder, name, max_value, encoding)
+            }
+            _ => Self::new_from_values(builder, name, values),
+        }
+    }
+}
+
+/// A stable (64-bit FNV-1a) hash of the `source` of a model, e.g. the contents of its SBML
+/// file, used to detect that a saved system no longer matches its model.
+pub fn model_hash(source: &[u8]) -> u64 {
+    source.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The first fields of every header, checked before the rest of the header is parsed.
+#[derive(Deserialize)]
+struct Preamble {
+    content: String,
+    format_version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompiledSystemHeader<T> {
+    content: String,
+    format_version: u32,
+    model_hash: u64,
+    #[serde(flatten)]
+    layout: SystemLayout<T>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateSetHeader<T> {
+    content: String,
+    format_version: u32,
+    #[serde(flatten)]
+    layout: SystemLayout<T>,
+}
+
+/// Describes how the states (and colors) of a system are encoded in BDDs.
+#[derive(Serialize, Deserialize)]
+struct SystemLayout<T> {
+    domain_type: String,
+    bdd_variables: Vec<String>,
+    variables: Vec<VariableLayout<T>>,
+    parameters: Vec<ParameterLayout<T>>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct VariableLayout<T> {
+    name: String,
+    domain: DomainLayout<T>,
+    primed_domain: DomainLayout<T>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct ParameterLayout<T> {
+    function: String,
+    arguments: Vec<T>,
+    domain: DomainLayout<T>,
+}
+
+/// The values of a domain, the indices of its BDD variables and the bits encoding every
+/// value (as given by `raw_bdd_variables_encode`), together with the
+/// [PersistentDomain::integer_encoding] needed to recreate the domain.
+#[derive(Serialize, Deserialize)]
+struct DomainLayout<T> {
+    values: Vec<T>,
+    bdd_variables: Vec<usize>,
+    encoding: Vec<Vec<bool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    integer_encoding: Option<IntegerEncoding>,
+}
+
+/// The layouts are compatible if they encode the same values using the same bits, even if
+/// the domains are of different types.
+impl<T: PartialEq> PartialEq for DomainLayout<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && self.bdd_variables == other.bdd_variables
+            && self.encoding == other.encoding
+    }
+}
+
+impl<T> DomainLayout<T> {
+    fn of<D: PersistentDomain<T>>(domain: &D) -> Self {
+        let values = domain.get_all_possible_values();
+        Self {
+            integer_encoding: domain.integer_encoding(),
+            encoding: values
+                .iter()
+                .map(|value| domain.raw_bdd_variables_encode(value))
+                .collect(),
+            bdd_variables: domain
+                .raw_bdd_variables()
+                .into_iter()
+                .map(BddVariable::to_index)
+                .collect(),
+            values,
+        }
+    }
+}
+
+impl<T: PartialEq> SystemLayout<T> {
+    /// Checks that the BDDs saved with the `saved` layout have the same meaning in this
+    /// layout. The domains may be of different types, as long as they encode all the values
+    /// using the same bits.
+    fn check_compatible(&self, saved: &Self) -> Result<(), PersistenceError> {
+        if saved.bdd_variables != self.bdd_variables {
+            return Err(PersistenceError::LayoutMismatch(format!(
+                "the BDD variables are [{}] instead of [{}]",
+                saved.bdd_variables.join(", "),
+                self.bdd_variables.join(", ")
+            )));
+        }
+        let names = |layout: &Self| {
+            layout
+                .variables
+                .iter()
+                .map(|variable| variable.name.clone())
+                .collect::<Vec<_>>()
+        };
+        if names(saved) != names(self) {
+            return Err(PersistenceError::LayoutMismatch(format!(
+                "the system variables are [{}] instead of [{}]",
+                names(saved).join(", "),
+                names(self).join(", ")
+            )));
+        }
+        if let Some((variable, _)) = self
+            .variables
+            .iter()
+            .zip(&saved.variables)
+            .find(|(variable, saved)| variable != saved)
+        {
+            return Err(PersistenceError::LayoutMismatch(format!(
+                "the domain of variable {} is encoded differently",
+                variable.name
+            )));
+        }
+        if saved.parameters != self.parameters {
+            return Err(PersistenceError::LayoutMismatch(
+                "the parameters are encoded differently".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<D, T> SmartSystemUpdateFn<D, T>
+where
+    D: PersistentDomain<T>,
+{
+    fn layout(&self) -> SystemLayout<T>
+    where
+        T: Clone,
+    {
+        SystemLayout {
+            domain_type: D::DOMAIN_TYPE.to_string(),
+            bdd_variables: self.get_bdd_variable_set().variable_names(),
+            variables: self
+                .variable_infos()
+                .iter()
+                .map(|(name, info)| VariableLayout {
+                    name: name.clone(),
+                    domain: DomainLayout::of(&info.domain),
+                    primed_domain: DomainLayout::of(&info.primed_domain),
+                })
+                .collect(),
+            parameters: self
+                .parameter_infos()
+                .iter()
+                .map(|parameter| ParameterLayout {
+                    function: parameter.function.clone(),
+                    arguments: parameter.arguments.clone(),
+                    domain: DomainLayout::of(&parameter.domain),
+                })
+                .collect(),
+        }
+    }
+
+    /// Saves a set of states (and colors) of this system, e.g. the reachable states or the
+    /// attractors, together with the layout of the system (its BDD variables and the encoding
+    /// of its domains), so that it can be loaded by [Self::read_states].
+    ///
+    /// The data starts with a single line of JSON header, followed by the BDD in the binary
+    /// format of [Bdd::write_as_bytes].
+    pub fn write_states<W: Write>(&self, output: &mut W, set: &Bdd) -> Result<(), PersistenceError>
+    where
+        T: Clone + Serialize,
+    {
+        let header = StateSetHeader {
+            content: STATE_SET.to_string(),
+            format_version: FORMAT_VERSION,
+            layout: self.layout(),
+        };
+        write_header(output, &header)?;
+        write_bdd(output, set)
+    }
+
+    /// Loads a set of states saved by [Self::write_states], possibly by another (but
+    /// compatible) system.
+    ///
+    /// Returns [PersistenceError::LayoutMismatch] if the set was saved by a system whose BDD
+    /// variables, system variables or encoding of the domains differ from this system.
+    pub fn read_states<R: BufRead>(&self, input: &mut R) -> Result<Bdd, PersistenceError>
+    where
+        T: Clone + DeserializeOwned + PartialEq,
+    {
+        let header: StateSetHeader<T> = read_header(input, STATE_SET)?;
+        self.layout().check_compatible(&header.layout)?;
+        read_bdd(input, self.get_bdd_variable_set())
+    }
+
+    /// Saves the system, i.e. its BDD variables, the layout of its domains and the transition
+    /// relations, so that it can be loaded by [Self::read_compiled] without building it again.
+    ///
+    /// The data starts with a single line of JSON header, followed by the BDDs in the binary
+    /// format of [Bdd::write_as_bytes]. The `model_hash` identifies the model the system was
+    /// built from (see [model_hash]).
+    pub fn write_compiled<W: Write>(
+        &self,
+        output: &mut W,
+        model_hash: u64,
+    ) -> Result<(), PersistenceError>
+    where
+        T: Clone + Serialize,
+    {
+        let header = CompiledSystemHeader {
+            content: COMPILED_SYSTEM.to_string(),
+            format_version: FORMAT_VERSION,
+            model_hash,
+            layout: self.layout(),
+        };
+        write_header(output, &header)?;
+        for (_, info) in self.variable_infos() {
+            write_bdd(output, &info.transition_relation)?;
+            write_bdd(output, &info.capable_of_transitioning)?;
+        }
+        Ok(())
+    }
+
+    /// Loads a system saved by [Self::write_compiled].
+    ///
+    /// If `model_hash` is given, the system must have been saved with the same hash, i.e. from
+    /// the same model. The domains are recreated from their values and encodings (see
+    /// [PersistentDomain::restore]), so they must be of the same type as when saving,
+    /// and must encode the values the same way.
+    pub fn read_compiled<R: BufRead>(
+        input: &mut R,
+        model_hash: Option<u64>,
+    ) -> Result<Self, PersistenceError>
+    where
+        T: DeserializeOwned + PartialEq,
+    {
+        let header: CompiledSystemHeader<T> = read_header(input, COMPILED_SYSTEM)?;
+        if let Some(expected) = model_hash {
+            if header.model_hash != expected {
+                return Err(PersistenceError::ModelChanged {
+                    expected,
+                    found: header.model_hash,
+                });
+            }
+        }
+        let saved = header.layout;
+        if saved.domain_type != D::DOMAIN_TYPE {
+            return Err(PersistenceError::DomainMismatch {
+                expected: D::DOMAIN_TYPE.to_string(),
+                found: saved.domain_type,
+            });
+        }
+
+        // the domains are created in the same order as when building the system, and then
+        // moved onto the saved BDD variables (which may have been reordered)
+        let mut builder = BddVariableSetBuilder::new();
+        let mut variables = saved
+            .variables
+            .iter()
+            .map(|variable| {
+                let domain = D::restore(
+                    &mut builder,
+                    &variable.name,
+                    &variable.domain.values,
+                    variable.domain.integer_encoding,
+                );
+                let primed_domain = D::restore(
+                    &mut builder,
+                    &format!("{}'", variable.name),
+                    &variable.primed_domain.values,
+                    variable.primed_domain.integer_encoding,
+                );
+                (domain, primed_domain)
+            })
+            .collect::<Vec<_>>();
+        let mut parameter_domains = saved
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(idx, parameter)| {
+                D::restore(
+                    &mut builder,
+                    &format!("{}[{}]", parameter.function, idx),
+                    &parameter.domain.values,
+                    parameter.domain.integer_encoding,
+                )
+            })
+            .collect::<Vec<_>>();
+        if builder.build().num_vars() as usize != saved.bdd_variables.len() {
+            return Err(PersistenceError::LayoutMismatch(
+                "the domains use a different number of BDD variables".to_string(),
+            ));
+        }
+
+        let layouts = saved
+            .variables
+            .iter()
+            .flat_map(|variable| [&variable.domain, &variable.primed_domain])
+            .chain(saved.parameters.iter().map(|parameter| &parameter.domain));
+        let domains = variables
+            .iter_mut()
+            .flat_map(|(domain, primed_domain)| [domain, primed_domain])
+            .chain(parameter_domains.iter_mut())
+            .collect::<Vec<_>>();
+        let mut permutation = HashMap::new();
+        for (domain, layout) in domains.iter().zip(layouts.clone()) {
+            let fresh = domain.raw_bdd_variables();
+            if fresh.len() != layout.bdd_variables.len() {
+                return Err(PersistenceError::LayoutMismatch(format!(
+                    "a domain uses {} BDD variables instead of {}",
+                    fresh.len(),
+                    layout.bdd_variables.len()
+                )));
+            }
+            for (fresh, target) in fresh.into_iter().zip(&layout.bdd_variables) {
+                permutation.insert(fresh, BddVariable::from_index(*target));
+            }
+        }
+        let mut targets = permutation.values().collect::<Vec<_>>();
+        targets.sort();
+        targets.dedup();
+        if targets.len() != saved.bdd_variables.len()
+            || targets
+                .last()
+                .is_some_and(|last| last.to_index() >= saved.bdd_variables.len())
+        {
+            return Err(PersistenceError::LayoutMismatch(
+                "the domains do not use all the BDD variables exactly once".to_string(),
+            ));
+        }
+        for (domain, layout) in domains.into_iter().zip(layouts) {
+            domain.rename_bdd_variables(&permutation);
+            let encoding = layout
+                .values
+                .iter()
+                .map(|value| domain.raw_bdd_variables_encode(value))
+                .collect::<Vec<_>>();
+            if domain.get_all_possible_values() != layout.values || encoding != layout.encoding {
+                return Err(PersistenceError::LayoutMismatch(
+                    "a domain encodes its values differently".to_string(),
+                ));
+            }
+        }
+
+        let bdd_variable_set = BddVariableSet::new(
+            &saved
+                .bdd_variables
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+        );
+        let variables = saved
+            .variables
+            .into_iter()
+            .zip(variables)
+            .map(|(layout, (domain, primed_domain))| {
+                let transition_relation = read_bdd(input, &bdd_variable_set)?;
+                let capable_of_transitioning = read_bdd(input, &bdd_variable_set)?;
+                Ok((
+                    layout.name.clone(),
+                    VarInfo {
+                        primed_name: format!("{}'", layout.name),
+                        domain,
+                        primed_domain,
+                        transition_relation,
+                        capable_of_transitioning,
+                        _marker: std::marker::PhantomData,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, PersistenceError>>()?;
+        let parameters = saved
+            .parameters
+            .into_iter()
+            .zip(parameter_domains)
+            .map(|(layout, domain)| ParameterInfo {
+                function: layout.function,
+                arguments: layout.arguments,
+                domain,
+            })
+            .collect();
+
+        Ok(Self::from_parts(variables, parameters, bdd_variable_set))
+    }
+}
+
+/// Writes the `header` as a single line of JSON.
+fn write_header<W: Write, H: Serialize>(
+    output: &mut W,
+    header: &H,
+) -> Result<(), PersistenceError> {
+    serde_json::to_writer(&mut *output, header)?;
+    output.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads a single line of JSON header, checking that it describes the `expected` content
+/// in the current [FORMAT_VERSION].
+fn read_header<R: BufRead, H: DeserializeOwned>(
+    input: &mut R,
+    expected: &str,
+) -> Result<H, PersistenceError> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    let preamble: Preamble = serde_json::from_str(&line)?;
+    if preamble.content != expected {
+        return Err(PersistenceError::UnexpectedContent {
+            expected: expected.to_string(),
+            found: preamble.content,
+        });
+    }
+    if preamble.format_version != FORMAT_VERSION {
+        return Err(PersistenceError::UnsupportedVersion(
+            preamble.format_version,
+        ));
+    }
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Writes the number of nodes of the `bdd`, followed by the nodes, so that several BDDs can
+/// follow each other.
+fn write_bdd<W: Write>(output: &mut W, bdd: &Bdd) -> Result<(), PersistenceError> {
+    output.write_all(&(bdd.size() as u64).to_le_bytes())?;
+    bdd.write_as_bytes(output)?;
+    Ok(())
+}
+
+/// Reads a BDD written by [write_bdd] and checks that it uses the given variables.
+///
+/// The data is not trusted, so a corrupt or truncated input results in an error rather than
+/// in an invalid BDD (or an attempt to allocate an arbitrary amount of memory).
+fn read_bdd<R: Read>(
+    input: &mut R,
+    bdd_variable_set: &BddVariableSet,
+) -> Result<Bdd, PersistenceError> {
+    let mut size = [0u8; 8];
+    input.read_exact(&mut size)?;
+    let size = u64::from_le_bytes(size);
+    // every node takes 10 bytes, see [Bdd::write_as_bytes]
+    let length = size
+        .checked_mul(10)
+        .ok_or_else(|| invalid_data(format!("a BDD cannot have {} nodes", size)))?;
+    let mut nodes = Vec::new();
+    input.take(length).read_to_end(&mut nodes)?;
+    if nodes.len() as u64 != length {
+        return Err(std::io::Error::new(
+            ErrorKind::UnexpectedEof,
+            format!("a BDD has {} bytes instead of {}", nodes.len(), length),
+        )
+        .into());
+    }
+    let bdd = Bdd::read_as_bytes(&mut nodes.as_slice())?;
+    check_bdd_structure(&bdd)?;
+    if bdd.num_vars() != bdd_variable_set.num_vars() {
+        return Err(PersistenceError::LayoutMismatch(format!(
+            "a BDD uses {} variables instead of {}",
+            bdd.num_vars(),
+            bdd_variable_set.num_vars()
+        )));
+    }
+    Ok(bdd)
+}
+
+/// Checks that the nodes of the `bdd` form a valid (ordered) BDD, i.e. that it has the
+/// terminal nodes and every other node only points to the nodes before it, which test
+/// later variables.
+fn check_bdd_structure(bdd: &Bdd) -> Result<(), PersistenceError> {
+    let size = bdd.size();
+    if size == 0 {
+        return Err(invalid_data("a BDD has no nodes".to_string()).into());
+    }
+    let terminal_var = bdd.var_of(BddPointer::zero());
+    if size > 1 && bdd.var_of(BddPointer::one()) != terminal_var {
+        return Err(invalid_data("the terminal nodes of a BDD differ".to_string()).into());
+    }
+    for index in 2..size {
+        let node = BddPointer::from_index(index);
+        let var = bdd.var_of(node);
+        let is_valid_child =
+            |child: BddPointer| child.to_index() < index && var < bdd.var_of(child);
+        if var >= terminal_var
+            || !is_valid_child(bdd.low_link_of(node))
+            || !is_valid_child(bdd.high_link_of(node))
+        {
+            return Err(invalid_data(format!("the BDD node {} is invalid", index)).into());
+        }
+    }
+    Ok(())
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,139 @@
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+
+use thiserror::Error;
+
+use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
+
+use super::update_fn::SmartSystemUpdateFn;
+
+/// An intervention on a single variable of a [SmartSystemUpdateFn].
+///
+/// A perturbed variable is updated to the closest value allowed by the perturbation, i.e.
+/// its original update is clamped into the allowed interval. The states in which the
+/// variable is outside the interval remain in the state space, but they are transient,
+/// as the variable can immediately update into the interval.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Perturbation<T> {
+    /// The variable is fixed to its lowest value.
+    Knockout,
+    /// The variable is fixed to its highest value.
+    OverExpression,
+    /// The variable is fixed to the given value.
+    Fixed(T),
+    /// The variable is restricted to the values `min..=max`.
+    Interval { min: T, max: T },
+}
+
+#[derive(Error, Debug)]
+pub enum PerturbationError {
+    UnknownVariable(String),
+    /// The value is not in the domain of the variable.
+    InvalidValue {
+        variable: String,
+        value: String,
+    },
+    /// The interval does not contain any value.
+    EmptyInterval(String),
+}
+
+impl Display for PerturbationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PerturbationError::UnknownVariable(name) => {
+                write!(f, "Variable {} is not a system variable", name)
+            }
+            PerturbationError::InvalidValue { variable, value } => {
+                write!(
+                    f,
+                    "Value {} is not in the domain of variable {}",
+                    value, variable
+                )
+            }
+            PerturbationError::EmptyInterval(variable) => {
+                write!(
+                    f,
+                    "Variable {} cannot be restricted to an empty interval",
+                    variable
+                )
+            }
+        }
+    }
+}
+
+impl<D, T> SmartSystemUpdateFn<D, T>
+where
+    D: SymbolicDomainOrd<T>,
+    T: Clone + PartialEq + Display,
+{
+    /// Applies the `perturbation` to the given variable in place, by rewriting its transition
+    /// relation. The other variables, as well as the encoding of the states, are unchanged,
+    /// so any [Bdd] computed for the original system remains valid for the perturbed one.
+    ///
+    /// Perturbations compose, i.e. perturbing an already perturbed variable clamps its
+    /// perturbed update once more.
+    ///
+    /// [Bdd]: biodivine_lib_bdd::Bdd
+    pub fn perturb(
+        &mut self,
+        variable: &str,
+        perturbation: &Perturbation<T>,
+    ) -> Result<(), PerturbationError> {
+        let mut values = self
+            .get_domain(variable)
+            .ok_or_else(|| PerturbationError::UnknownVariable(variable.to_string()))?
+            .get_all_possible_values();
+        values.sort_by(D::cmp);
+        let check = |value: &T| {
+            if values.contains(value) {
+                Ok(value.clone())
+            } else {
+                Err(PerturbationError::InvalidValue {
+                    variable: variable.to_string(),
+                    value: value.to_string(),
+                })
+            }
+        };
+
+        let (min, max) = match perturbation {
+            Perturbation::Knockout => (values[0].clone(), values[0].clone()),
+            Perturbation::OverExpression => {
+                let highest = values.last().expect("the domain is not empty");
+                (highest.clone(), highest.clone())
+            }
+            Perturbation::Fixed(value) => {
+                let value = check(value)?;
+                (value.clone(), value)
+            }
+            Perturbation::Interval { min, max } => (check(min)?, check(max)?),
+        };
+        if D::cmp(&min, &max) == Ordering::Greater {
+            return Err(PerturbationError::EmptyInterval(variable.to_string()));
+        }
+
+        self.map_update_fn(variable, |value| {
+            if D::cmp(value, &min) == Ordering::Less {
+                min.clone()
+            } else if D::cmp(value, &max) == Ordering::Greater {
+                max.clone()
+            } else {
+                value.clone()
+            }
+        });
+        Ok(())
+    }
+
+    /// A copy of this system with the `perturbations` applied (see [Self::perturb]).
+    pub fn perturbed(
+        &self,
+        perturbations: &HashMap<String, Perturbation<T>>,
+    ) -> Result<Self, PerturbationError>
+    where
+        D: Clone,
+    {
+        let mut perturbed = self.clone();
+        for (variable, perturbation) in perturbations {
+            perturbed.perturb(variable, perturbation)?;
+        }
+        Ok(perturbed)
+    }
+}
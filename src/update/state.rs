@@ -0,0 +1,141 @@
+use std::fmt::Display;
+
+use biodivine_lib_bdd::{Bdd, BddPartialValuation, OwnedBddSatisfyingValuations};
+use thiserror::Error;
+
+use crate::symbolic_domains::symbolic_domain::{DomainError, SymbolicDomain};
+
+/// A single state of a [SmartSystemUpdateFn], i.e. the values of all its system variables.
+///
+/// The values are indexed by the position of the variable in
+/// [SmartSystemUpdateFn::get_system_variables] (see [SmartSystemUpdateFn::variable_index]).
+///
+/// [SmartSystemUpdateFn]: crate::update::update_fn::SmartSystemUpdateFn
+/// [SmartSystemUpdateFn::get_system_variables]: crate::update::update_fn::SmartSystemUpdateFn::get_system_variables
+/// [SmartSystemUpdateFn::variable_index]: crate::update::update_fn::SmartSystemUpdateFn::variable_index
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct State<T> {
+    values: Vec<T>,
+}
+
+impl<T> State<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        Self { values }
+    }
+
+    /// The value of the variable with the given index.
+    pub fn get(&self, variable_index: usize) -> Option<&T> {
+        self.values.get(variable_index)
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StateError {
+    /// The value of a system variable is not given.
+    MissingVariable(String),
+    /// A value is given for a variable which is not a system variable.
+    UnknownVariable(String),
+    /// The state does not have one value per system variable.
+    WrongVariableCount { expected: usize, actual: usize },
+    /// The value is not in the domain of the variable.
+    InvalidValue { variable: String, value: String },
+    /// The comparison is not supported by the domain of the variable.
+    UnsupportedComparison {
+        variable: String,
+        #[source]
+        source: DomainError,
+    },
+}
+
+impl Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::MissingVariable(name) => {
+                write!(f, "The value of variable {} is missing", name)
+            }
+            StateError::UnknownVariable(name) => {
+                write!(f, "Variable {} is not a system variable", name)
+            }
+            StateError::WrongVariableCount { expected, actual } => write!(
+                f,
+                "Expected a state with {} variables, but it has {}",
+                expected, actual
+            ),
+            StateError::InvalidValue { variable, value } => {
+                write!(
+                    f,
+                    "Value {} is not in the domain of variable {}",
+                    value, variable
+                )
+            }
+            StateError::UnsupportedComparison { variable, source } => write!(
+                f,
+                "Cannot restrict the value of variable {}: {}",
+                variable, source
+            ),
+        }
+    }
+}
+
+/// Iterates over the states of a [Bdd], decoding them one at a time.
+///
+/// Created by [SmartSystemUpdateFn::iter_states].
+///
+/// [SmartSystemUpdateFn::iter_states]: crate::update::update_fn::SmartSystemUpdateFn::iter_states
+pub struct StateIterator<'a, D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    domains: Vec<&'a D>,
+    valuations: OwnedBddSatisfyingValuations,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, D, T> StateIterator<'a, D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    /// The `projected_set` must only depend on the variables of the `domains`, and every other
+    /// variable must be fixed to `false`, so that every state corresponds to exactly one
+    /// satisfying valuation.
+    pub(crate) fn new(domains: Vec<&'a D>, projected_set: Bdd) -> Self {
+        Self {
+            domains,
+            valuations: projected_set.into_sat_valuations(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<D, T> Iterator for StateIterator<'_, D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    type Item = State<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let valuation = BddPartialValuation::from(self.valuations.next()?);
+        let values = self
+            .domains
+            .iter()
+            .map(|domain| domain.decode_bits(&valuation))
+            .collect();
+        Some(State::new(values))
+    }
+}
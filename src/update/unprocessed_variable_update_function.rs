@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use crate::expression_components::expression::Expression;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UnprocessedVariableUpdateFn<T> {
     pub input_vars_names: Vec<String>,
     pub target_var_name: String,
@@ -23,3 +25,30 @@ impl<T> UnprocessedVariableUpdateFn<T> {
         }
     }
 }
+
+impl<T: Clone + Ord> UnprocessedVariableUpdateFn<T> {
+    /// The value the target variable is updated to in the given `state`, i.e. the value of
+    /// the first term whose condition holds, or the `default` if there is no such term.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value of a variable used in some condition is missing from the `state`.
+    pub fn evaluate(&self, state: &HashMap<String, T>) -> T {
+        self.evaluate_with(&|variable| state.get(variable))
+    }
+
+    /// Same as [UnprocessedVariableUpdateFn::evaluate], but the value of every variable is
+    /// given by `value_of`.
+    pub fn evaluate_with<'a, F>(&self, value_of: &F) -> T
+    where
+        F: Fn(&str) -> Option<&'a T>,
+        T: 'a,
+    {
+        self.terms
+            .iter()
+            .find(|(_, condition)| condition.evaluate_with(value_of))
+            .map(|(value, _)| value)
+            .unwrap_or(&self.default)
+            .clone()
+    }
+}
@@ -1,19 +1,84 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+};
 
 use biodivine_lib_bdd::{
     Bdd, BddPartialValuation, BddVariable, BddVariableSet, BddVariableSetBuilder,
 };
 
 use crate::{
-    expression_components::{expression::Expression, proposition::Proposition},
-    symbolic_domains::symbolic_domain::{SymbolicDomain, SymbolicDomainOrd},
-    update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    expression_components::{
+        expression::Expression,
+        proposition::{ComparisonOperator, Proposition},
+    },
+    symbolic_domains::symbolic_domain::{
+        DomainError, IntegerEncoding, MixedIntegerDomain, SymbolicDomain, SymbolicDomainEq,
+        SymbolicDomainOrd, UnsignedInteger,
+    },
+    update::{
+        expression_from_bdd::{expression_from_bdd, terms_from_conditions, terms_from_update_fn},
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput},
+        state::{State, StateError, StateIterator},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        variable_ordering::{regulators_of, VariableOrderError, VariableOrdering},
+    },
 };
 
 use self::variable_update_fn::VariableUpdateFn;
 use debug_ignore::DebugIgnore;
+use num_bigint::BigInt;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum UpdateFnError {
+    /// A proposition uses a comparison that is not supported by the domain of its variable.
+    UnsupportedProposition {
+        variable: String,
+        #[source]
+        source: DomainError,
+    },
+    /// The requested variable ordering does not match the system variables.
+    InvalidVariableOrder(#[from] VariableOrderError),
+    /// An unknown function is used with different numbers of arguments, with arguments
+    /// of different domains, or in variables with different domains.
+    InconsistentParameter(String),
+    /// An argument of an unknown function is not a system variable.
+    UnknownArgument { function: String, variable: String },
+    /// No value of the variable is known, e.g. because all outputs of its update function
+    /// are unknown and [ParametrizedVariableUpdateFn::values] is empty.
+    UnknownDomain(String),
+}
+
+impl Display for UpdateFnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateFnError::UnsupportedProposition { variable, source } => write!(
+                f,
+                "Cannot encode a proposition on variable {}: {}",
+                variable, source
+            ),
+            UpdateFnError::InvalidVariableOrder(source) => {
+                write!(f, "Invalid variable order: {}", source)
+            }
+            UpdateFnError::InconsistentParameter(name) => write!(
+                f,
+                "Unknown function {} is not used consistently in all update functions",
+                name
+            ),
+            UpdateFnError::UnknownArgument { function, variable } => write!(
+                f,
+                "Argument {} of unknown function {} is not a system variable",
+                variable, function
+            ),
+            UpdateFnError::UnknownDomain(name) => {
+                write!(f, "Cannot infer the values of variable {}", name)
+            }
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct SystemUpdateFn<D, T>
@@ -28,27 +93,49 @@ where
 
 impl<DO, T> SystemUpdateFn<DO, T>
 where
-    DO: SymbolicDomainOrd<T>,
+    DO: SymbolicDomainEq<T>,
 {
     pub fn from_update_fns(
         vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
-    ) -> Self {
+    ) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_update_fns(vars_and_their_update_fns)
+            .expect("update functions should be encodable in the domains")
+    }
+
+    /// Like [Self::from_update_fns], but returns an error if some proposition cannot be encoded
+    /// in the domain of its variable (e.g. `<` used with an [EnumeratedDomain]).
+    ///
+    /// [EnumeratedDomain]: crate::symbolic_domains::symbolic_domain::EnumeratedDomain
+    pub fn try_from_update_fns(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+    {
         let named_update_fns_sorted = {
-            let mut to_be_sorted = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
+            let mut to_be_sorted = vars_and_their_update_fns
+                .into_iter()
+                .map(|(var_name, update_fn)| {
+                    (var_name, ParametrizedVariableUpdateFn::from(update_fn))
+                })
+                .collect::<Vec<_>>();
             to_be_sorted.sort_unstable_by_key(|(var_name, _)| var_name.clone());
             to_be_sorted
         };
 
         let (symbolic_domains, bdd_variable_set) = {
-            let max_values = find_max_values::<DO, T>(&named_update_fns_sorted);
+            let domain_values = collect_domain_values(&named_update_fns_sorted);
             let (symbolic_domains, variable_set_builder) = named_update_fns_sorted.iter().fold(
                 (Vec::new(), BddVariableSetBuilder::new()),
                 |(mut domains, mut variable_set), (var_name, _update_fn)| {
-                    let max_value = max_values
+                    let values = domain_values
                         .get(var_name.as_str())
-                        .expect("max value always present");
+                        .expect("values always present");
 
-                    let domain = DO::new(&mut variable_set, var_name, max_value);
+                    let domain = DO::new_from_values(&mut variable_set, var_name, values);
                     domains.push(domain);
                     (domains, variable_set)
                 },
@@ -66,14 +153,15 @@ where
         let update_fns = named_update_fns_sorted
             .iter()
             .map(|(var_name, update_fn)| {
-                VariableUpdateFn::from_update_fn(
+                VariableUpdateFn::try_from_parametrized_update_fn(
                     update_fn,
                     var_name,
                     &bdd_variable_set,
                     &named_symbolic_domains,
+                    &[],
                 )
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
         let the_triple = named_update_fns_sorted
             .into_iter()
@@ -82,11 +170,11 @@ where
             .map(|(((var_name, _), update_fn), domain)| (var_name, (update_fn, domain)))
             .collect::<Vec<_>>();
 
-        Self {
+        Ok(Self {
             update_fns: the_triple,
             bdd_variable_set: bdd_variable_set.into(),
             _marker: std::marker::PhantomData,
-        }
+        })
     }
 
     fn get_update_fn_and_domain_of(&self, variable_name: &str) -> Option<&(VariableUpdateFn, DO)> {
@@ -127,21 +215,18 @@ where
             .map(|value| domain.raw_bdd_variables_encode(&value))
             .collect::<Vec<_>>();
 
-        let unit_collection = self
-            .update_fns
-            .iter()
-            .fold(self.bdd_variable_set.mk_true(), |acc, (_, (_, domain))| {
-                acc.and(&domain.unit_collection(&self.bdd_variable_set))
-            });
+        let unit_collection = self.update_fns.iter().fold(
+            self.bdd_variable_set.mk_true(),
+            |acc, (_, (_, domain))| {
+                and_unit_collection(&acc, &domain.unit_collection(&self.bdd_variable_set))
+            },
+        );
 
         let unpruned_res = each_allowed_value_bit_encoded.into_iter().fold(
             self.bdd_variable_set.mk_false(),
             |acc, val_bits| {
-                let any_state_capable_of_transitioning_into_target_value = update_fn
-                    .bit_answering_bdds
-                    .iter()
-                    .zip(&val_bits)
-                    .fold(
+                let any_state_capable_of_transitioning_into_target_value = and_unit_collection(
+                    &update_fn.bit_answering_bdds.iter().zip(&val_bits).fold(
                         self.bdd_variable_set.mk_true(),
                         |acc, ((_, bdd), val_bit)| {
                             if *val_bit {
@@ -150,8 +235,9 @@ where
                                 acc.and_not(bdd)
                             }
                         },
-                    )
-                    .and(&unit_collection);
+                    ),
+                    &unit_collection,
+                );
 
                 let those_from_source_capable_of_transitioning_into_target_value =
                     source_states_set.and(&any_state_capable_of_transitioning_into_target_value);
@@ -173,7 +259,7 @@ where
             },
         );
 
-        unpruned_res.and(&unit_collection)
+        and_unit_collection(&unpruned_res, &unit_collection)
     }
 
     /// Like `successors_async`, but a state that "transitions" to itself under
@@ -228,22 +314,24 @@ where
 
             let those_from_source_with_target_value = source_states.select(filter.as_slice());
 
-            let possible_predecessors = those_from_source_with_target_value
-                .exists(
+            // keep only valid states
+            let possible_predecessors = and_unit_collection(
+                &those_from_source_with_target_value.exists(
                     filter
                         .iter()
                         .map(|(bdd_var, _)| *bdd_var)
                         .collect::<Vec<_>>()
                         .as_slice(),
-                )
-                .and(&domain.unit_collection(&self.bdd_variable_set)); // keep only valid states
+                ),
+                &domain.unit_collection(&self.bdd_variable_set),
+            );
 
-            let unit_set = self
-                .update_fns
-                .iter()
-                .fold(self.bdd_variable_set.mk_true(), |acc, (_, (_, domain))| {
-                    acc.and(&domain.unit_collection(&self.bdd_variable_set))
-                });
+            let unit_set = self.update_fns.iter().fold(
+                self.bdd_variable_set.mk_true(),
+                |acc, (_, (_, domain))| {
+                    and_unit_collection(&acc, &domain.unit_collection(&self.bdd_variable_set))
+                },
+            );
 
             let any_state_capable_of_transitioning_into_target_value =
                 update_fn.bit_answering_bdds.iter().zip(&val_bits).fold(
@@ -278,9 +366,21 @@ where
             .and(&self.those_states_capable_of_transitioning_under(variable_name))
     }
 
-    fn those_states_capable_of_transitioning_under(&self, _variable_name: &str) -> Bdd {
-        // todo this should be stored in a field; built during construction
-        todo!()
+    fn those_states_capable_of_transitioning_under(&self, variable_name: &str) -> Bdd {
+        let (update_fn, domain) = self
+            .get_update_fn_and_domain_of(variable_name)
+            .expect("unknown variable");
+        // the value changes iff some bit of the encoding differs from its updated value
+        let changed = update_fn.bit_answering_bdds.iter().fold(
+            self.bdd_variable_set.mk_false(),
+            |acc, (bdd_variable, bit_answering_bdd)| {
+                acc.or(&self
+                    .bdd_variable_set
+                    .mk_var(*bdd_variable)
+                    .xor(bit_answering_bdd))
+            },
+        );
+        and_unit_collection(&changed, &domain.unit_collection(&self.bdd_variable_set))
     }
 
     pub fn encode_one(&self, variable_name: &str, value: &T) -> Bdd {
@@ -290,22 +390,67 @@ where
         domain.encode_one(&self.bdd_variable_set, value)
     }
 
+    pub fn get_bdd_variable_set(&self) -> &BddVariableSet {
+        &self.bdd_variable_set
+    }
+
     pub fn bdd_to_dot_string(&self, bdd: &Bdd) -> String {
         bdd.to_dot_string(&self.bdd_variable_set, false)
     }
 }
 
-struct VarInfo<D, T>
+impl<DO, T> SystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T>,
+{
+    /// Decodes the update function of the given variable into a list of `(value, condition)`
+    /// terms, or `None` if the variable is unknown. See [terms_from_update_fn] for details.
+    pub fn update_fn_terms(&self, variable_name: &str) -> Option<Vec<(T, Expression<T>)>>
+    where
+        T: Clone + Ord,
+    {
+        let (update_fn, domain) = self.get_update_fn_and_domain_of(variable_name)?;
+        let named_domains = self
+            .update_fns
+            .iter()
+            .map(|(var_name, (_, domain))| (var_name.as_str(), domain))
+            .collect::<Vec<_>>();
+        Some(terms_from_update_fn(
+            update_fn,
+            domain,
+            &named_domains,
+            &self.bdd_variable_set,
+        ))
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct VarInfo<D, T>
 where
     D: SymbolicDomain<T>,
 {
-    primed_name: String,
-    domain: D,
-    primed_domain: D,
-    transition_relation: Bdd,
-    _marker: std::marker::PhantomData<T>,
+    pub(crate) primed_name: String,
+    pub(crate) domain: D,
+    pub(crate) primed_domain: D,
+    pub(crate) transition_relation: Bdd,
+    /// the states (and colors) in which the update changes the value of the variable
+    pub(crate) capable_of_transitioning: Bdd,
+    pub(crate) _marker: std::marker::PhantomData<T>,
 }
 
+/// A single unknown value of an unknown function, i.e. its value for the given `arguments`
+/// (one row of its function table).
+#[derive(Clone)]
+pub(crate) struct ParameterInfo<D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    pub(crate) function: String,
+    pub(crate) arguments: Vec<T>,
+    pub(crate) domain: D,
+}
+
+#[derive(Clone)]
 pub struct SmartSystemUpdateFn<D, T>
 where
     D: SymbolicDomain<T>,
@@ -313,6 +458,8 @@ where
     /// maps variable name to its index in the `variables_transition_relation_and_domain` vector to allow for fast access while keeping the vector sorted
     mapper: HashMap<String, usize>,
     variables_transition_relation_and_domain: Vec<(String, VarInfo<D, T>)>,
+    /// empty, unless the system was created from [ParametrizedVariableUpdateFn]-s
+    parameters: Vec<ParameterInfo<D, T>>,
     bdd_variable_set: BddVariableSet,
     _marker: std::marker::PhantomData<T>,
 }
@@ -339,6 +486,38 @@ impl<D, T> SmartSystemUpdateFn<D, T>
 where
     D: SymbolicDomain<T>,
 {
+    /// Assembles a system from its already encoded variables (sorted according to the
+    /// desired order of the system variables) and parameters.
+    pub(crate) fn from_parts(
+        variables_transition_relation_and_domain: Vec<(String, VarInfo<D, T>)>,
+        parameters: Vec<ParameterInfo<D, T>>,
+        bdd_variable_set: BddVariableSet,
+    ) -> Self {
+        let mapper = variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+            .fold(HashMap::new(), |mut acc, (idx, (var_name, _))| {
+                acc.insert(var_name.to_owned(), idx);
+                acc
+            });
+
+        Self {
+            mapper,
+            variables_transition_relation_and_domain,
+            parameters,
+            bdd_variable_set,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub(crate) fn variable_infos(&self) -> &[(String, VarInfo<D, T>)] {
+        &self.variables_transition_relation_and_domain
+    }
+
+    pub(crate) fn parameter_infos(&self) -> &[ParameterInfo<D, T>] {
+        &self.parameters
+    }
+
     /// Returns a list of [BddVariable]-s corresponding to the encoding of the standard
     /// (i.e. "un-primed") system variables.
     pub fn standard_variables(&self) -> Vec<BddVariable> {
@@ -375,8 +554,8 @@ where
         &self.bdd_variable_set
     }
 
-    /// The list of system variables, sorted in ascending order (i.e. the order in which they
-    /// also appear within the BDDs).1
+    /// The list of system variables in the order in which they appear within the BDDs
+    /// (alphabetical, unless a different [VariableOrdering] was used).
     pub fn get_system_variables(&self) -> Vec<String> {
         self.variables_transition_relation_and_domain
             .iter()
@@ -395,19 +574,121 @@ where
     /// encoding contains some invalid values, these need to be excluded.
     ///
     /// Note that this only concerns the "standard" system variables. The resulting BDD
-    /// does not depend on the "primed" system variables.
+    /// does not depend on the "primed" system variables. If the system has parameters,
+    /// all the valid colors are included (see [Self::unit_colors]).
     pub fn unit_vertex_set(&self) -> Bdd {
         self.variables_transition_relation_and_domain
             .iter()
-            .fold(self.bdd_variable_set.mk_true(), |acc, it| {
-                acc.and(&it.1.domain.unit_collection(&self.bdd_variable_set))
+            .fold(self.unit_colors(), |acc, it| {
+                and_unit_collection(&acc, &it.1.domain.unit_collection(&self.bdd_variable_set))
+            })
+    }
+
+    /// The [BddVariable]-s encoding the parameters, i.e. the unknown values of the functions
+    /// used in [ParametrizedVariableUpdateFn]-s. Empty if the system has no parameters.
+    pub fn parameter_variables(&self) -> Vec<BddVariable> {
+        self.parameters
+            .iter()
+            .flat_map(|parameter| parameter.domain.raw_bdd_variables())
+            .collect()
+    }
+
+    /// The name of the unknown function and the values of its arguments for every parameter,
+    /// i.e. for every row of the function tables.
+    pub fn parameters(&self) -> Vec<(&str, &[T])> {
+        self.parameters
+            .iter()
+            .map(|parameter| (parameter.function.as_str(), parameter.arguments.as_slice()))
+            .collect()
+    }
+
+    /// The set of all valid colors, i.e. interpretations of the unknown functions, excluding
+    /// the invalid values of the parameter domains. It is `true` if there are no parameters.
+    pub fn unit_colors(&self) -> Bdd {
+        self.parameters
+            .iter()
+            .fold(self.bdd_variable_set.mk_true(), |acc, parameter| {
+                and_unit_collection(
+                    &acc,
+                    &parameter.domain.unit_collection(&self.bdd_variable_set),
+                )
             })
     }
 
-    /// Compute an (approximate) count of state in the given `set` using the encoding of `system`.
+    /// The colors of the states in the (colored) `set`.
+    pub fn colors(&self, set: &Bdd) -> Bdd {
+        let state_variables = self
+            .standard_variables()
+            .into_iter()
+            .chain(self.primed_variables())
+            .collect::<Vec<_>>();
+        and_unit_collection(&set.exists(&state_variables), &self.unit_colors())
+    }
+
+    /// Compute an (approximate) count of the colors which appear in the given `set`.
+    pub fn count_colors(&self, set: &Bdd) -> f64 {
+        self.count_projected(set, &self.parameter_variables())
+    }
+
+    /// The set of colors in which the unknown `function` has the `value` for the given
+    /// `arguments`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system has no such parameter.
+    pub fn encode_parameter(&self, function: &str, arguments: &[T], value: &T) -> Bdd
+    where
+        T: PartialEq,
+    {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.function == function && parameter.arguments == arguments)
+            .expect("unknown parameter")
+            .domain
+            .encode_one(&self.bdd_variable_set, value)
+    }
+
+    /// Compute an (approximate) count of states in the given `set`.
+    ///
+    /// The `set` is projected onto the [Self::standard_variables], so it may also depend on
+    /// other variables (e.g. the primed ones). Invalid values of the domains are not counted.
     pub fn count_states(&self, set: &Bdd) -> f64 {
-        let symbolic_var_count = self.variables_transition_relation_and_domain.len() as i32;
-        set.cardinality() / 2.0f64.powi(symbolic_var_count)
+        self.count_projected(set, &self.standard_variables())
+    }
+
+    /// Same as [Self::count_states], but with exact unbounded integers.
+    pub fn count_states_exact(&self, set: &Bdd) -> BigInt {
+        self.count_projected_exact(set, &self.standard_variables())
+    }
+
+    /// Compute an (approximate) count of the distinct valuations of `variables` which appear
+    /// in the given `set` (i.e. the size of the projection of `set` onto `variables`).
+    ///
+    /// The `set` is first restricted to the [Self::unit_vertex_set], so that invalid values
+    /// of the domains are not counted.
+    pub fn count_projected(&self, set: &Bdd, variables: &[BddVariable]) -> f64 {
+        let (projected, abstracted_count) = self.project_onto(set, variables);
+        projected.cardinality() / 2.0f64.powi(abstracted_count as i32)
+    }
+
+    /// Same as [Self::count_projected], but with exact unbounded integers.
+    pub fn count_projected_exact(&self, set: &Bdd, variables: &[BddVariable]) -> BigInt {
+        let (projected, abstracted_count) = self.project_onto(set, variables);
+        projected.exact_cardinality() >> abstracted_count
+    }
+
+    /// Restricts the `set` to valid states and existentially abstracts all [BddVariable]-s
+    /// except `variables`. Also returns the number of abstracted variables.
+    fn project_onto(&self, set: &Bdd, variables: &[BddVariable]) -> (Bdd, usize) {
+        let retained = variables.iter().collect::<HashSet<_>>();
+        let abstracted = self
+            .bdd_variable_set
+            .variables()
+            .into_iter()
+            .filter(|var| !retained.contains(var))
+            .collect::<Vec<_>>();
+        let valid = and_unit_collection(set, &self.unit_vertex_set());
+        (valid.exists(&abstracted), abstracted.len())
     }
 
     /// Compute a [Bdd] which represents a single (un-primed) state within the given symbolic `set`.
@@ -429,18 +710,253 @@ where
         self.bdd_variable_set.mk_conjunctive_clause(&state_data)
     }
 
+    /// The index of the given system variable, i.e. its position within
+    /// [Self::get_system_variables] and within every [State] of this system.
+    pub fn variable_index(&self, variable_name: &str) -> Option<usize> {
+        self.mapper.get(variable_name).copied()
+    }
+
+    /// The value of the given system variable in the `state`.
+    pub fn state_value<'a>(&self, state: &'a State<T>, variable_name: &str) -> Option<&'a T> {
+        self.variable_index(variable_name)
+            .and_then(|idx| state.get(idx))
+    }
+
+    /// Iterates over all (valid) states in the given `set`. The states are decoded lazily.
+    ///
+    /// Similar to [Self::count_states], the `set` is projected onto the
+    /// [Self::standard_variables], so the primed variables (or any other extra variables)
+    /// do not produce duplicate states.
+    pub fn iter_states(&self, set: &Bdd) -> StateIterator<'_, D, T> {
+        let (projected, _) = self.project_onto(set, &self.standard_variables());
+        let standard_variables = self.standard_variables();
+        let other_variables_unset = self
+            .bdd_variable_set
+            .variables()
+            .into_iter()
+            .filter(|var| !standard_variables.contains(var))
+            .map(|var| (var, false))
+            .collect::<Vec<_>>();
+        StateIterator::new(
+            self.standard_domains(),
+            projected.select(&other_variables_unset),
+        )
+    }
+
+    /// Decode a single (arbitrary) state from the given `set`, or `None` if it is empty.
+    pub fn pick_state(&self, set: &Bdd) -> Option<State<T>> {
+        self.iter_states(set).next()
+    }
+
+    /// Encode the `state` into a [Bdd] over the standard variables.
+    ///
+    /// Returns an error if the `state` does not have exactly one value per system variable.
+    pub fn encode_state(&self, state: &State<T>) -> Result<Bdd, StateError> {
+        if state.len() != self.variables_transition_relation_and_domain.len() {
+            return Err(StateError::WrongVariableCount {
+                expected: self.variables_transition_relation_and_domain.len(),
+                actual: state.len(),
+            });
+        }
+        let mut valuation = BddPartialValuation::empty();
+        for ((_, info), value) in self
+            .variables_transition_relation_and_domain
+            .iter()
+            .zip(state.values())
+        {
+            info.domain.encode_bits(&mut valuation, value);
+        }
+        Ok(self.bdd_variable_set.mk_conjunctive_clause(&valuation))
+    }
+
+    /// Encode the set of `states` into a single [Bdd] (see [Self::encode_state]).
+    pub fn encode_states<'a>(
+        &self,
+        states: impl IntoIterator<Item = &'a State<T>>,
+    ) -> Result<Bdd, StateError>
+    where
+        T: 'a,
+    {
+        states
+            .into_iter()
+            .try_fold(self.bdd_variable_set.mk_false(), |acc, state| {
+                Ok(acc.or(&self.encode_state(state)?))
+            })
+    }
+
+    /// Converts a map from variable names to their values into a [State].
+    ///
+    /// Returns an error if a system variable is missing or if an unknown variable is present.
+    pub fn state_from_map(&self, values: &HashMap<String, T>) -> Result<State<T>, StateError>
+    where
+        T: Clone,
+    {
+        if let Some(unknown) = values.keys().find(|name| !self.mapper.contains_key(*name)) {
+            return Err(StateError::UnknownVariable(unknown.clone()));
+        }
+        self.variables_transition_relation_and_domain
+            .iter()
+            .map(|(name, _)| {
+                values
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| StateError::MissingVariable(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(State::new)
+    }
+
+    /// Converts the `state` into a map from variable names to their values.
+    pub fn state_to_map(&self, state: &State<T>) -> HashMap<String, T>
+    where
+        T: Clone,
+    {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .zip(state.values())
+            .map(|((name, _), value)| (name.clone(), value.clone()))
+            .collect()
+    }
+
     pub fn log_percent(set: &Bdd, universe: &Bdd) -> f64 {
         set.cardinality().log2() / universe.cardinality().log2() * 100.0
     }
+
+    /// The total number of nodes of the transition relations of all variables. Useful for
+    /// comparing different [VariableOrdering]s of the same system.
+    pub fn transition_relations_size(&self) -> usize {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .map(|(_, info)| info.transition_relation.size())
+            .sum()
+    }
 }
 
 impl<DO, T> SmartSystemUpdateFn<DO, T>
 where
-    DO: SymbolicDomainOrd<T>,
+    DO: SymbolicDomainEq<T>,
 {
     pub fn from_update_fns(
         vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
-    ) -> Self {
+    ) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_update_fns(vars_and_their_update_fns)
+            .expect("update functions should be encodable in the domains")
+    }
+
+    /// Like [Self::from_update_fns], but returns an error if some proposition cannot be encoded
+    /// in the domain of its variable (e.g. `<` used with an [EnumeratedDomain]).
+    ///
+    /// [EnumeratedDomain]: crate::symbolic_domains::symbolic_domain::EnumeratedDomain
+    pub fn try_from_update_fns(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_update_fns_with_ordering(
+            vars_and_their_update_fns,
+            &VariableOrdering::default(),
+        )
+    }
+
+    /// Like [Self::from_update_fns], but the BDD variables are ordered according to the
+    /// `ordering` instead of alphabetically.
+    pub fn from_update_fns_with_ordering(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        ordering: &VariableOrdering,
+    ) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_update_fns_with_ordering(vars_and_their_update_fns, ordering)
+            .expect("update functions should be encodable in the domains")
+    }
+
+    /// Like [Self::try_from_update_fns], but the BDD variables are ordered according to the
+    /// `ordering` instead of alphabetically. Also returns an error if the `ordering` is not
+    /// valid for the given variables.
+    pub fn try_from_update_fns_with_ordering(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        ordering: &VariableOrdering,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_parametrized_update_fns_with_ordering(
+            into_parametrized(vars_and_their_update_fns),
+            ordering,
+        )
+    }
+
+    /// Creates a system which represents a whole family of models, one for every
+    /// interpretation of the [UninterpretedFn]-s used in the update functions. Every such
+    /// interpretation (a color) is encoded by the values of extra parameter BDD variables,
+    /// so that [Self::successors_async] and [Self::predecessors_async] work with colored
+    /// sets of states, i.e. sets of (state, color) pairs.
+    ///
+    /// [UninterpretedFn]: crate::update::parametrized_update_function::UninterpretedFn
+    pub fn from_parametrized_update_fns(
+        vars_and_their_update_fns: HashMap<String, ParametrizedVariableUpdateFn<T>>,
+    ) -> Self
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_parametrized_update_fns(vars_and_their_update_fns)
+            .expect("update functions should be encodable in the domains")
+    }
+
+    /// Like [Self::from_parametrized_update_fns], but returns an error if some proposition
+    /// cannot be encoded or the unknown functions are not used consistently.
+    pub fn try_from_parametrized_update_fns(
+        vars_and_their_update_fns: HashMap<String, ParametrizedVariableUpdateFn<T>>,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_parametrized_update_fns_with_ordering(
+            vars_and_their_update_fns,
+            &VariableOrdering::default(),
+        )
+    }
+
+    /// Like [Self::try_from_parametrized_update_fns], but the BDD variables of the system
+    /// variables are ordered according to the `ordering`. The parameters always follow
+    /// the system variables.
+    pub fn try_from_parametrized_update_fns_with_ordering(
+        vars_and_their_update_fns: HashMap<String, ParametrizedVariableUpdateFn<T>>,
+        ordering: &VariableOrdering,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+    {
+        Self::try_from_update_fns_with_domains(
+            vars_and_their_update_fns,
+            ordering,
+            |builder, _var_name, bdd_var_name, values| {
+                DO::new_from_values(builder, bdd_var_name, values)
+            },
+        )
+    }
+
+    /// Like [Self::try_from_update_fns_with_ordering], but the domains are created using
+    /// `new_domain`. It receives the name of the system variable, the name under which the
+    /// domain creates its BDD variables (differs for the primed variable and the parameters)
+    /// and the values the variable can take.
+    ///
+    /// The domains of the parameters are created with the name of the (first) variable
+    /// whose update function uses them, so that they are encoded the same way as the variable.
+    fn try_from_update_fns_with_domains<F>(
+        vars_and_their_update_fns: HashMap<String, ParametrizedVariableUpdateFn<T>>,
+        ordering: &VariableOrdering,
+        mut new_domain: F,
+    ) -> Result<Self, UpdateFnError>
+    where
+        T: Clone + PartialEq,
+        F: FnMut(&mut BddVariableSetBuilder, &str, &str, &[T]) -> DO,
+    {
         vars_and_their_update_fns.iter().for_each(|(name, _)| {
             if name.contains('\'') {
                 panic!("variable name cannot contain the prime symbol \"'\" (tick) - it is reserved for inner usage")
@@ -448,37 +964,100 @@ where
         });
 
         let named_update_fns_sorted = {
-            let mut to_be_sorted = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
-            to_be_sorted.sort_by_key(|(var_name, _)| var_name.clone());
-            to_be_sorted
+            let sorted_names = {
+                let regulators = vars_and_their_update_fns
+                    .iter()
+                    .map(|(target, update_fn)| {
+                        let variables = update_fn.variables().into_iter();
+                        let regulators =
+                            regulators_of(target, variables, &vars_and_their_update_fns);
+                        (target.as_str(), regulators)
+                    })
+                    .collect();
+                ordering.order.sort_by_regulators(&regulators)?
+            };
+            let mut update_fns = vars_and_their_update_fns;
+            sorted_names
+                .into_iter()
+                .map(|var_name| {
+                    let update_fn = update_fns.remove(&var_name).expect("known variable");
+                    (var_name, update_fn)
+                })
+                .collect::<Vec<_>>()
         };
 
-        let (named_symbolic_domains, bdd_variable_set) = {
-            let max_values = find_max_values::<DO, T>(&named_update_fns_sorted);
+        let (mut named_symbolic_domains, mut parameters, mut bdd_variable_set) = {
+            let domain_values = collect_domain_values(&named_update_fns_sorted);
             let mut bdd_variable_set_builder = BddVariableSetBuilder::new();
 
             // let (symbolic_domains, variable_set_builder) =
             let named_symbolic_domains = named_update_fns_sorted
                 .iter()
                 .map(|(var_name, _)| {
-                    let max_value = max_values
+                    let values = domain_values
                         .get(var_name.as_str())
-                        .expect("max value always present");
+                        .ok_or_else(|| UpdateFnError::UnknownDomain(var_name.clone()))?;
 
                     let original_name = var_name.clone();
                     let primed_name = format!("{}'", var_name);
 
-                    let original =
-                        DO::new(&mut bdd_variable_set_builder, &original_name, max_value);
-                    let primed = DO::new(&mut bdd_variable_set_builder, &primed_name, max_value);
+                    let original = new_domain(
+                        &mut bdd_variable_set_builder,
+                        var_name,
+                        &original_name,
+                        values,
+                    );
+                    let primed = new_domain(
+                        &mut bdd_variable_set_builder,
+                        var_name,
+                        &primed_name,
+                        values,
+                    );
 
-                    ((original_name, original), (primed_name, primed))
+                    Ok(((original_name, original), (primed_name, primed)))
                 })
-                .collect::<Vec<_>>();
+                .collect::<Result<Vec<_>, UpdateFnError>>()?;
 
-            (named_symbolic_domains, bdd_variable_set_builder.build())
+            let parameters = make_parameters(
+                &named_update_fns_sorted,
+                &named_symbolic_domains
+                    .iter()
+                    .map(|((var_name, domain), _)| (var_name.as_str(), domain))
+                    .collect(),
+                |var_name, bdd_var_name| {
+                    let values = &domain_values[var_name];
+                    new_domain(
+                        &mut bdd_variable_set_builder,
+                        var_name,
+                        bdd_var_name,
+                        values,
+                    )
+                },
+            )?;
+
+            (
+                named_symbolic_domains,
+                parameters,
+                bdd_variable_set_builder.build(),
+            )
         };
 
+        if ordering.interleave_bits {
+            let domains_and_primed_domains = named_symbolic_domains
+                .iter_mut()
+                .map(|((_, domain), (_, primed_domain))| (domain, primed_domain))
+                .collect();
+            let parameter_domains = parameters
+                .iter_mut()
+                .map(|parameter| &mut parameter.domain)
+                .collect();
+            bdd_variable_set = interleave_domain_bits(
+                domains_and_primed_domains,
+                parameter_domains,
+                &bdd_variable_set,
+            );
+        }
+
         let named_symbolic_domains_map = named_symbolic_domains
             .iter()
             .flat_map(|((var_name, domain), (primed_var_name, primed_domain))| {
@@ -488,24 +1067,28 @@ where
                 ]
             })
             .collect::<HashMap<_, _>>();
-        let update_fns = named_update_fns_sorted.iter().map(|(var_name, update_fn)| {
-            (
-                var_name,
-                VariableUpdateFn::from_update_fn(
+        let update_fns = named_update_fns_sorted
+            .iter()
+            .map(|(var_name, update_fn)| {
+                VariableUpdateFn::try_from_parametrized_update_fn(
                     update_fn,
                     var_name,
                     &bdd_variable_set,
                     &named_symbolic_domains_map,
-                ),
-            )
-        });
+                    &parameters,
+                )
+                .map(|update_fn| (var_name, update_fn))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
+        // the parameters are included, so that the relations only contain valid colors
         let unit_set = named_symbolic_domains
             .iter()
-            .fold(bdd_variable_set.mk_true(), |acc, ((_name, domain), _)| {
-                acc.and(&domain.unit_collection(&bdd_variable_set))
+            .map(|((_name, domain), _)| domain)
+            .chain(parameters.iter().map(|parameter| &parameter.domain))
+            .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                and_unit_collection(&acc, &domain.unit_collection(&bdd_variable_set))
             });
-
         let unprimed_var_names_and_their_primed_unit_collection = named_symbolic_domains
             .iter()
             .map(|((unprimed_var_name, _), (_, primed_domain))| {
@@ -545,7 +1128,7 @@ where
                     .expect("always present");
 
                 // ensure output only valid values
-                relation.and(specific_primed_unit_set)
+                and_unit_collection(&relation, specific_primed_unit_set)
             })
             .collect::<Vec<_>>();
 
@@ -554,6 +1137,12 @@ where
             .zip(relations)
             .map(
                 |(((var_name, domain), (primed_var_name, primed_domain)), relation_bdd)| {
+                    let capable_of_transitioning = states_capable_of_transitioning(
+                        &relation_bdd,
+                        &domain,
+                        &primed_domain,
+                        &bdd_variable_set,
+                    );
                     (
                         var_name,
                         VarInfo {
@@ -561,6 +1150,7 @@ where
                             domain,
                             primed_domain,
                             transition_relation: relation_bdd,
+                            capable_of_transitioning,
                             _marker: std::marker::PhantomData,
                         },
                     )
@@ -568,20 +1158,11 @@ where
             )
             .collect::<Vec<_>>();
 
-        let mapper = variables_transition_relation_and_domain
-            .iter()
-            .enumerate()
-            .fold(HashMap::new(), |mut acc, (idx, (var_name, _))| {
-                acc.insert(var_name.to_owned(), idx);
-                acc
-            });
-
-        Self {
-            mapper,
+        Ok(Self::from_parts(
             variables_transition_relation_and_domain,
+            parameters,
             bdd_variable_set,
-            _marker: std::marker::PhantomData,
-        }
+        ))
     }
 
     pub fn successors_async(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
@@ -615,18 +1196,14 @@ where
     /// of another state from `source_states`).
     pub fn successors_async_exclude_loops(
         &self,
-        _transition_variable_name: &str,
-        _source_states: &Bdd,
+        transition_variable_name: &str,
+        source_states: &Bdd,
     ) -> Bdd {
-        // todo better to directly construct the specific no_loop_transition_bdd during construction
-        // todo probably have some common, underlying method that would accept the transition bdd
-        // todo the two public methods would then just pass in the proper transition bdd
-        // self.successors_async(
-        //     transition_variable_name,
-        //     &source_states
-        //         .and(&self.those_states_capable_of_transitioning_under(transition_variable_name)),
-        // )
-        todo!()
+        self.successors_async(
+            transition_variable_name,
+            &source_states
+                .and(self.those_states_capable_of_transitioning_under(transition_variable_name)),
+        )
     }
 
     pub fn predecessors_async(
@@ -664,15 +1241,11 @@ where
     /// of another state from `source_states`).
     pub fn predecessors_async_exclude_loops(
         &self,
-        _variable_name: &str,
-        _source_states: &Bdd,
+        variable_name: &str,
+        source_states: &Bdd,
     ) -> Bdd {
-        // todo better to directly construct the specific no_loop_transition_bdd during construction
-        // todo probably have some common, underlying method that would accept the transition bdd
-        // todo the two public methods would then just pass in the proper transition bdd
-        // self.predecessors_async(transition_variable_name, source_states)
-        //     .and(&self.those_states_capable_of_transitioning_under(transition_variable_name))
-        todo!()
+        self.predecessors_async(variable_name, source_states.clone())
+            .and(self.those_states_capable_of_transitioning_under(variable_name))
     }
 
     fn get_transition_relation_and_domain(&self, variable_name: &str) -> Option<&VarInfo<DO, T>> {
@@ -681,9 +1254,11 @@ where
             .map(|idx| &self.variables_transition_relation_and_domain[*idx].1)
     }
 
-    fn those_states_capable_of_transitioning_under(&self, _variable_name: &str) -> Bdd {
-        // todo this should be stored in a field; built during construction
-        todo!()
+    fn those_states_capable_of_transitioning_under(&self, variable_name: &str) -> &Bdd {
+        &self
+            .get_transition_relation_and_domain(variable_name)
+            .expect("unknown variable")
+            .capable_of_transitioning
     }
 
     pub fn encode_one(&self, variable_name: &str, value: &T) -> Bdd {
@@ -693,11 +1268,414 @@ where
         domain.encode_one(&self.bdd_variable_set, value)
     }
 
+    /// The set of states satisfying all the `constraints`, e.g. `p = 1` and `q >= 2` for the
+    /// partial specification `{p: 1, q: >=2}` (see [parse_partial_assignment]). The variables
+    /// without constraints are free.
+    /// If the system has parameters, the set contains all the valid colors.
+    ///
+    /// Returns an error if a constraint uses an unknown variable, a value outside the domain
+    /// of its variable, or a comparison which is not supported by the domain.
+    ///
+    /// [parse_partial_assignment]: crate::prelude::proposition::parse_partial_assignment
+    pub fn initial_states<'a>(
+        &self,
+        constraints: impl IntoIterator<Item = &'a Proposition<T>>,
+    ) -> Result<Bdd, StateError>
+    where
+        T: PartialEq + Display + 'a,
+    {
+        constraints
+            .into_iter()
+            .try_fold(self.unit_vertex_set(), |acc, constraint| {
+                let variable = &constraint.variable;
+                let domain = self
+                    .get_domain(variable)
+                    .ok_or_else(|| StateError::UnknownVariable(variable.clone()))?;
+                // the domains cannot encode values they do not contain
+                if !domain.get_all_possible_values().contains(&constraint.value) {
+                    return Err(StateError::InvalidValue {
+                        variable: variable.clone(),
+                        value: constraint.value.to_string(),
+                    });
+                }
+                let allowed = domain
+                    .encode_comparison(
+                        &self.bdd_variable_set,
+                        constraint.comparison_operator,
+                        &constraint.value,
+                    )
+                    .map_err(|source| StateError::UnsupportedComparison {
+                        variable: variable.clone(),
+                        source,
+                    })?;
+                Ok(acc.and(&allowed))
+            })
+    }
+
+    /// The set of states in which the variables have the given `levels`, e.g. the
+    /// `initialLevel`-s of an SBML model (see [load_initial_levels]). See
+    /// [Self::initial_states].
+    ///
+    /// [load_initial_levels]: crate::prelude::load_initial_levels
+    pub fn initial_states_from_levels(&self, levels: &HashMap<String, T>) -> Result<Bdd, StateError>
+    where
+        T: Clone + PartialEq + Display,
+    {
+        let constraints = levels
+            .iter()
+            .map(|(variable, level)| {
+                Proposition::new(ComparisonOperator::Eq, variable.clone(), level.clone())
+            })
+            .collect::<Vec<_>>();
+        self.initial_states(&constraints)
+    }
+
+    /// Replaces the update function `f` of the given variable by `map ∘ f`, i.e. wherever
+    /// the variable was updated to `value`, it is updated to `map(value)` instead. Only the
+    /// transition relation of the variable is rewritten, the rest of the system is unchanged.
+    ///
+    /// The `map` must only return values of the domain of the variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the variable is not a system variable.
+    pub(crate) fn map_update_fn(&mut self, variable_name: &str, map: impl Fn(&T) -> T) {
+        let idx = *self.mapper.get(variable_name).expect("unknown variable");
+        let info = &self.variables_transition_relation_and_domain[idx].1;
+        let primed_variables = info.primed_domain.raw_bdd_variables();
+        let relation = info
+            .primed_domain
+            .get_all_possible_values()
+            .into_iter()
+            .fold(self.bdd_variable_set.mk_false(), |acc, value| {
+                // the states (and colors) in which the variable is updated to `value`
+                let condition = info
+                    .transition_relation
+                    .and(
+                        &info
+                            .primed_domain
+                            .encode_one(&self.bdd_variable_set, &value),
+                    )
+                    .exists(&primed_variables);
+                let mapped = info
+                    .primed_domain
+                    .encode_one(&self.bdd_variable_set, &map(&value));
+                acc.or(&condition.and(&mapped))
+            });
+        let capable_of_transitioning = states_capable_of_transitioning(
+            &relation,
+            &info.domain,
+            &info.primed_domain,
+            &self.bdd_variable_set,
+        );
+
+        let info = &mut self.variables_transition_relation_and_domain[idx].1;
+        info.transition_relation = relation;
+        info.capable_of_transitioning = capable_of_transitioning;
+    }
+
     pub fn bdd_to_dot_string(&self, bdd: &Bdd) -> String {
         bdd.to_dot_string(&self.bdd_variable_set, false)
     }
 }
 
+impl<DO, T> SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T>,
+{
+    /// Maps every system variable to the sorted list of values it can attain.
+    ///
+    /// This is the form of domains expected by [Expression::simplify].
+    pub fn variable_domains(&self) -> HashMap<String, Vec<T>> {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .map(|(var_name, var_info)| {
+                let mut values = var_info.domain.get_all_possible_values();
+                values.sort_by(DO::cmp);
+                (var_name.clone(), values)
+            })
+            .collect()
+    }
+
+    /// Decodes the set of states into an equivalent formula over the system variables.
+    /// See [expression_from_bdd] for details.
+    pub fn bdd_to_expression(&self, set: &Bdd) -> Expression<T>
+    where
+        T: Clone + Ord,
+    {
+        expression_from_bdd(
+            set,
+            &self.standard_variables_names_and_domains(),
+            &self.bdd_variable_set,
+        )
+    }
+
+    /// Decodes the update function of the given variable into a list of `(value, condition)`
+    /// terms, one for each value the variable can be updated to, or `None` if the variable
+    /// is unknown. The conditions are mutually exclusive and the values are sorted in
+    /// ascending order. See [terms_from_conditions] for details.
+    ///
+    /// For a system with parameters, a condition contains the states in which the variable
+    /// is updated to the value for at least one color.
+    pub fn update_fn_terms(&self, variable_name: &str) -> Option<Vec<(T, Expression<T>)>>
+    where
+        T: Clone + Ord,
+    {
+        let VarInfo {
+            primed_domain,
+            transition_relation,
+            ..
+        } = self.get_transition_relation_and_domain(variable_name)?;
+
+        Some(terms_from_conditions(
+            primed_domain,
+            |value| {
+                transition_relation
+                    .and(&primed_domain.encode_one(&self.bdd_variable_set, value))
+                    .exists(&primed_domain.raw_bdd_variables())
+            },
+            &self.standard_variables_names_and_domains(),
+            &self.bdd_variable_set,
+        ))
+    }
+}
+
+impl<T> SmartSystemUpdateFn<MixedIntegerDomain<T>, T>
+where
+    T: UnsignedInteger,
+{
+    /// Like [Self::from_update_fns], but the encoding of each variable is chosen by `encoding`,
+    /// given the name of the variable and its maximal value. Use [IntegerEncoding::default_for]
+    /// to keep the default choice for some of the variables.
+    pub fn from_update_fns_with_encoding<F>(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        encoding: F,
+    ) -> Self
+    where
+        F: Fn(&str, &T) -> IntegerEncoding,
+    {
+        Self::try_from_update_fns_with_domains(
+            into_parametrized(vars_and_their_update_fns),
+            &VariableOrdering::default(),
+            |builder, var_name, bdd_var_name, values| {
+                let max_value = values
+                    .iter()
+                    .max()
+                    .expect("at least one value should be given");
+                MixedIntegerDomain::new_with_encoding(
+                    builder,
+                    bdd_var_name,
+                    max_value,
+                    encoding(var_name, max_value),
+                )
+            },
+        )
+        .expect("integer domains support all comparisons")
+    }
+}
+
+/// Reorders the variables of the `bdd_variable_set` such that the bits of every domain are
+/// interleaved with the bits of its primed copy and renames the variables of the domains
+/// accordingly. Returns the reordered variable set.
+fn interleave_domain_bits<'a, D, T>(
+    domains_and_primed_domains: Vec<(&'a mut D, &'a mut D)>,
+    parameter_domains: Vec<&'a mut D>,
+    bdd_variable_set: &BddVariableSet,
+) -> BddVariableSet
+where
+    D: SymbolicDomain<T> + 'a,
+{
+    // the parameters are kept after the system variables
+    let interleaved = domains_and_primed_domains
+        .iter()
+        .flat_map(|(domain, primed_domain)| {
+            let bits = domain.raw_bdd_variables();
+            let primed_bits = primed_domain.raw_bdd_variables();
+            (0..bits.len().max(primed_bits.len()))
+                .flat_map(move |idx| [bits.get(idx).copied(), primed_bits.get(idx).copied()])
+                .flatten()
+        })
+        .chain(
+            parameter_domains
+                .iter()
+                .flat_map(|domain| domain.raw_bdd_variables()),
+        )
+        .collect::<Vec<_>>();
+
+    let mut builder = BddVariableSetBuilder::new();
+    let permutation = interleaved
+        .into_iter()
+        .map(|var| {
+            let name = bdd_variable_set.name_of(var);
+            (var, builder.make_variable(name.as_str()))
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (domain, primed_domain) in domains_and_primed_domains {
+        domain.rename_bdd_variables(&permutation);
+        primed_domain.rename_bdd_variables(&permutation);
+    }
+    for domain in parameter_domains {
+        domain.rename_bdd_variables(&permutation);
+    }
+
+    builder.build()
+}
+
+fn into_parametrized<T>(
+    vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+) -> HashMap<String, ParametrizedVariableUpdateFn<T>> {
+    vars_and_their_update_fns
+        .into_iter()
+        .map(|(var_name, update_fn)| (var_name, update_fn.into()))
+        .collect()
+}
+
+/// Creates a parameter for every valuation of the arguments of every unknown function used
+/// in the update functions, i.e. for every row of its function table. The domains of the
+/// parameters are created by `new_domain`, given the name of the target variable of the
+/// (first) update function using the function and the name of the BDD variables.
+fn make_parameters<D, T, F>(
+    named_update_fns: &[(String, ParametrizedVariableUpdateFn<T>)],
+    named_symbolic_domains: &HashMap<&str, &D>,
+    mut new_domain: F,
+) -> Result<Vec<ParameterInfo<D, T>>, UpdateFnError>
+where
+    D: SymbolicDomainEq<T>,
+    T: Clone + PartialEq,
+    F: FnMut(&str, &str) -> D,
+{
+    let mut parameters = Vec::new();
+    // the target variable and the arguments of the first usage of every function
+    let mut signatures = HashMap::<&str, (&str, &[String])>::new();
+    for (var_name, update_fn) in named_update_fns {
+        for output in update_fn.outputs() {
+            let TermOutput::Unknown(function) = output else {
+                continue;
+            };
+            let argument_domains = function
+                .arguments
+                .iter()
+                .map(|argument| {
+                    named_symbolic_domains
+                        .get(argument.as_str())
+                        .copied()
+                        .ok_or_else(|| UpdateFnError::UnknownArgument {
+                            function: function.name.clone(),
+                            variable: argument.clone(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some((first_target, first_arguments)) = signatures.get(function.name.as_str()) {
+                let consistent = same_encoding(
+                    named_symbolic_domains[first_target],
+                    named_symbolic_domains[var_name.as_str()],
+                ) && first_arguments.len() == argument_domains.len()
+                    && first_arguments
+                        .iter()
+                        .zip(&argument_domains)
+                        .all(|(first, domain)| {
+                            same_values(
+                                &named_symbolic_domains[first.as_str()].get_all_possible_values(),
+                                &domain.get_all_possible_values(),
+                            )
+                        });
+                if !consistent {
+                    return Err(UpdateFnError::InconsistentParameter(function.name.clone()));
+                }
+                continue;
+            }
+            signatures.insert(&function.name, (var_name, &function.arguments));
+
+            let rows =
+                argument_domains
+                    .iter()
+                    .fold(vec![Vec::new()], |rows: Vec<Vec<T>>, domain| {
+                        let values = domain.get_all_possible_values();
+                        rows.into_iter()
+                            .flat_map(|row| {
+                                values.iter().map(move |value| {
+                                    let mut row = row.clone();
+                                    row.push(value.clone());
+                                    row
+                                })
+                            })
+                            .collect()
+                    });
+            for (row_idx, arguments) in rows.into_iter().enumerate() {
+                // brackets cannot appear in SBML identifiers, so this does not clash with
+                // the BDD variables of the system variables
+                let bdd_var_name = format!("{}[{}]", function.name, row_idx);
+                parameters.push(ParameterInfo {
+                    function: function.name.clone(),
+                    arguments,
+                    domain: new_domain(var_name, &bdd_var_name),
+                });
+            }
+        }
+    }
+    Ok(parameters)
+}
+
+fn same_values<T: PartialEq>(a: &[T], b: &[T]) -> bool {
+    a.len() == b.len() && a.iter().all(|value| b.contains(value))
+}
+
+/// Whether the two domains have the same values, encoded by the same bits.
+fn same_encoding<D, T>(a: &D, b: &D) -> bool
+where
+    D: SymbolicDomainEq<T>,
+    T: PartialEq,
+{
+    let values = a.get_all_possible_values();
+    same_values(&values, &b.get_all_possible_values())
+        && values
+            .iter()
+            .all(|value| a.raw_bdd_variables_encode(value) == b.raw_bdd_variables_encode(value))
+}
+
+/// The states of the `transition_relation` of a variable (with the given `domain`) in which
+/// the updated value of the variable differs from the current one.
+fn states_capable_of_transitioning<D, T>(
+    transition_relation: &Bdd,
+    domain: &D,
+    primed_domain: &D,
+    bdd_variable_set: &BddVariableSet,
+) -> Bdd
+where
+    D: SymbolicDomain<T>,
+{
+    let unchanged = domain
+        .raw_bdd_variables()
+        .into_iter()
+        .zip(primed_domain.raw_bdd_variables())
+        .fold(bdd_variable_set.mk_true(), |acc, (unprimed, primed)| {
+            acc.and(
+                &bdd_variable_set
+                    .mk_var(unprimed)
+                    .iff(&bdd_variable_set.mk_var(primed)),
+            )
+        });
+    transition_relation
+        .and_not(&unchanged)
+        .exists(&primed_domain.raw_bdd_variables())
+}
+
+/// Restricts the `set` to the valid values of a domain given by its `unit_collection`.
+///
+/// The unit collection of domains without invalid values (e.g. the [BooleanDomain]) is `true`.
+/// In that case, the conjunction is skipped, as [Bdd::and] would still copy the whole `set`.
+///
+/// [BooleanDomain]: crate::symbolic_domains::symbolic_domain::BooleanDomain
+fn and_unit_collection(set: &Bdd, unit_collection: &Bdd) -> Bdd {
+    if unit_collection.is_true() {
+        set.clone()
+    } else {
+        set.and(unit_collection)
+    }
+}
+
 fn find_bdd_variables_prime<D, T>(
     target_variable: &BddVariable,
     target_sym_dom: &D,
@@ -716,27 +1694,27 @@ where
         .expect("should be present")
 }
 
-fn find_max_values<DO, T>(
-    vars_and_their_update_fns: &[(String, UnprocessedVariableUpdateFn<T>)],
-) -> HashMap<&str, &T>
+/// Collects the values of every variable that appear in the update functions, i.e. the values
+/// the variable can be updated to and the values it is compared with. The values are listed
+/// in the order of their first occurrence.
+fn collect_domain_values<T>(
+    vars_and_their_update_fns: &[(String, ParametrizedVariableUpdateFn<T>)],
+) -> HashMap<&str, Vec<T>>
 where
-    DO: SymbolicDomainOrd<T>,
+    T: Clone + PartialEq,
 {
-    let max_outputs =
+    let outputs =
         vars_and_their_update_fns
             .iter()
             .fold(HashMap::new(), |mut acc, (var_name, update_fn)| {
-                let max_value = update_fn
-                    .terms
-                    .iter()
-                    .map(|(val, _)| val)
-                    .chain(Some(&update_fn.default))
-                    .max_by(|x, y| DO::cmp(x, y))
-                    .expect("default value always present");
-                // no balls
-                // // SAFETY: there is always at least the default value
-                // let max_value = unsafe { max_value_option.unwrap_unchecked() };
-                acc.insert(var_name.as_str(), max_value);
+                update_fn
+                    .outputs()
+                    .filter_map(|output| match output {
+                        TermOutput::Value(val) => Some(val),
+                        TermOutput::Unknown(_) => None,
+                    })
+                    .chain(&update_fn.values)
+                    .for_each(|val| insert_value(&mut acc, var_name, val));
                 acc
             });
 
@@ -746,52 +1724,43 @@ where
     vars_and_their_update_fns
         .iter()
         .flat_map(|(_var_name, update_fn)| update_fn.terms.iter().map(|(_, expr)| expr))
-        .fold(max_outputs, |mut acc, expr| {
-            update_max::<DO, T>(&mut acc, expr);
+        .fold(outputs, |mut acc, expr| {
+            insert_compared_values(&mut acc, expr);
             acc
         })
 }
 
-fn update_max<'a, DO, T>(acc: &mut HashMap<&'a str, &'a T>, expr: &'a Expression<T>)
+fn insert_compared_values<'a, T>(acc: &mut HashMap<&'a str, Vec<T>>, expr: &'a Expression<T>)
 where
-    DO: SymbolicDomainOrd<T>,
+    T: Clone + PartialEq,
 {
     match expr {
         Expression::Terminal(proposition) => {
-            update_from_proposition::<DO, T>(acc, proposition);
+            insert_value(acc, &proposition.variable, &proposition.value);
         }
         Expression::Not(expression) => {
-            update_max::<DO, T>(acc, expression);
+            insert_compared_values(acc, expression);
         }
         Expression::And(clauses) | Expression::Or(clauses) => {
             clauses
                 .iter()
-                .for_each(|clause| update_max::<DO, T>(acc, clause));
+                .for_each(|clause| insert_compared_values(acc, clause));
         }
         Expression::Xor(lhs, rhs) | Expression::Implies(lhs, rhs) => {
-            update_max::<DO, T>(acc, lhs);
-            update_max::<DO, T>(acc, rhs);
+            insert_compared_values(acc, lhs);
+            insert_compared_values(acc, rhs);
         }
     }
 }
 
-fn update_from_proposition<'a, DO, T>(
-    acc: &mut HashMap<&'a str, &'a T>,
-    proposition: &'a Proposition<T>,
-) where
-    DO: SymbolicDomainOrd<T>,
+fn insert_value<'a, T>(acc: &mut HashMap<&'a str, Vec<T>>, var_name: &'a str, value: &T)
+where
+    T: Clone + PartialEq,
 {
-    let Proposition {
-        variable, value, ..
-    } = proposition;
-
-    acc.entry(variable.as_str())
-        .and_modify(|old_val| {
-            if DO::cmp(old_val, value) == std::cmp::Ordering::Less {
-                *old_val = value
-            }
-        })
-        .or_insert(value);
+    let values = acc.entry(var_name).or_default();
+    if !values.contains(value) {
+        values.push(value.clone());
+    }
 }
 
 pub mod variable_update_fn {
@@ -800,14 +1769,18 @@ pub mod variable_update_fn {
     use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
 
     use crate::{
-        expression_components::{
-            expression::Expression,
-            proposition::{ComparisonOperator as CmpOp, Proposition},
+        expression_components::{expression::Expression, proposition::Proposition},
+        symbolic_domains::symbolic_domain::{SymbolicDomainEq, SymbolicDomainOrd},
+        update::{
+            parametrized_update_function::{
+                ParametrizedVariableUpdateFn as ParametrizedFn, TermOutput, UninterpretedFn,
+            },
+            unprocessed_variable_update_function::UnprocessedVariableUpdateFn as UnprocessedFn,
         },
-        symbolic_domains::symbolic_domain::SymbolicDomainOrd,
-        update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn as UnprocessedFn,
     };
 
+    use super::{ParameterInfo, UpdateFnError};
+
     #[derive(Debug)]
     pub struct VariableUpdateFn {
         pub bit_answering_bdds: Vec<(BddVariable, Bdd)>,
@@ -822,21 +1795,66 @@ pub mod variable_update_fn {
             named_symbolic_domains: &HashMap<&str, &DO>,
         ) -> Self
         where
-            DO: SymbolicDomainOrd<T>,
+            DO: SymbolicDomainEq<T>,
+            T: Clone,
         {
-            let UnprocessedFn { terms, default, .. } = update_fn;
+            Self::try_from_update_fn(
+                update_fn,
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+            )
+            .expect("update function should be encodable in the domains")
+        }
+
+        /// Like [Self::from_update_fn], but returns an error if some proposition cannot be
+        /// encoded in the domain of its variable.
+        pub fn try_from_update_fn<DO, T>(
+            update_fn: &UnprocessedFn<T>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Result<Self, UpdateFnError>
+        where
+            DO: SymbolicDomainEq<T>,
+            T: Clone,
+        {
+            Self::try_from_parametrized_update_fn(
+                &ParametrizedFn::from(update_fn.clone()),
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+                &[],
+            )
+        }
+
+        /// Like [Self::try_from_update_fn], but the outputs may be unknown. The bits of an
+        /// unknown output are given by the `parameters` of its function.
+        pub(crate) fn try_from_parametrized_update_fn<DO, T>(
+            update_fn: &ParametrizedFn<T>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+            parameters: &[ParameterInfo<DO, T>],
+        ) -> Result<Self, UpdateFnError>
+        where
+            DO: SymbolicDomainEq<T>,
+        {
+            let ParametrizedFn { terms, default, .. } = update_fn;
 
             let (outputs, bdd_conds): (Vec<_>, Vec<_>) = terms
                 .iter()
                 .map(|(val, match_condition)| {
-                    let match_condition_bdd = bdd_from_expression(
+                    let match_condition_bdd = try_bdd_from_expression(
                         match_condition,
                         named_symbolic_domains,
                         bdd_variable_set,
-                    );
-                    (val, match_condition_bdd)
+                    )?;
+                    Ok((val, match_condition_bdd))
                 })
-                .chain(Some((default, bdd_variable_set.mk_true())))
+                .chain(Some(Ok((default, bdd_variable_set.mk_true()))))
+                .collect::<Result<Vec<_>, UpdateFnError>>()?
+                .into_iter()
                 .unzip();
 
             let (_, values_mutally_exclusive_terms) = bdd_conds.into_iter().fold(
@@ -855,32 +1873,97 @@ pub mod variable_update_fn {
                 .get(target_variable_name)
                 .expect("must know the domain of the target variable");
 
+            // `bit_matrix[row][bit]` is the value of the bit of the output of the row
             let bit_matrix = outputs
                 .into_iter()
-                .map(|output| target_domain.raw_bdd_variables_encode(output))
-                .collect::<Vec<_>>();
+                .map(|output| match output {
+                    TermOutput::Value(value) => target_domain
+                        .raw_bdd_variables_encode(value)
+                        .into_iter()
+                        .map(|bit| {
+                            if bit {
+                                bdd_variable_set.mk_true()
+                            } else {
+                                bdd_variable_set.mk_false()
+                            }
+                        })
+                        .collect(),
+                    TermOutput::Unknown(function) => unknown_output_bits(
+                        function,
+                        named_symbolic_domains,
+                        bdd_variable_set,
+                        parameters,
+                    ),
+                })
+                .collect::<Vec<Vec<_>>>();
 
             let bit_answering_bdds = (0..bit_matrix[0].len()).map(|bit_idx| {
                 (0..bit_matrix.len()).fold(bdd_variable_set.mk_false(), |acc, row_idx| {
-                    if bit_matrix[row_idx][bit_idx] {
+                    let bit = &bit_matrix[row_idx][bit_idx];
+                    if bit.is_true() {
                         acc.or(&values_mutally_exclusive_terms[row_idx])
-                    } else {
+                    } else if bit.is_false() {
                         acc
+                    } else {
+                        acc.or(&values_mutally_exclusive_terms[row_idx].and(bit))
                     }
                 })
             });
 
-            Self {
+            Ok(Self {
                 bit_answering_bdds: target_domain
                     .raw_bdd_variables()
                     .into_iter()
                     .zip(bit_answering_bdds)
                     .collect(),
-            }
+            })
         }
     }
 
-    fn bdd_from_expression<DO, T>(
+    /// The bits of the value of the `function` for the arguments in the current state, i.e.
+    /// the bits of the parameter of the matching row of its function table.
+    fn unknown_output_bits<DO, T>(
+        function: &UninterpretedFn,
+        named_symbolic_domains: &HashMap<&str, &DO>,
+        bdd_variable_set: &BddVariableSet,
+        parameters: &[ParameterInfo<DO, T>],
+    ) -> Vec<Bdd>
+    where
+        DO: SymbolicDomainEq<T>,
+    {
+        let mut rows = parameters
+            .iter()
+            .filter(|parameter| parameter.function == function.name)
+            .peekable();
+        let bits_count = rows
+            .peek()
+            .expect("every unknown function has a parameter")
+            .domain
+            .raw_bdd_variables()
+            .len();
+        rows.fold(
+            vec![bdd_variable_set.mk_false(); bits_count],
+            |mut bits, parameter| {
+                let row = function.arguments.iter().zip(&parameter.arguments).fold(
+                    bdd_variable_set.mk_true(),
+                    |acc, (argument, value)| {
+                        acc.and(
+                            &named_symbolic_domains[argument.as_str()]
+                                .encode_one(bdd_variable_set, value),
+                        )
+                    },
+                );
+                for (bit, bdd_var) in bits.iter_mut().zip(parameter.domain.raw_bdd_variables()) {
+                    *bit = bit.or(&row.and(&bdd_variable_set.mk_var(bdd_var)));
+                }
+                bits
+            },
+        )
+    }
+
+    /// Encodes the set of states satisfying the `expression` into a [Bdd], using the given
+    /// domains of the variables in the expression.
+    pub fn bdd_from_expression<DO, T>(
         expression: &Expression<T>,
         named_symbolic_domains: &HashMap<&str, &DO>,
         bdd_variable_set: &BddVariableSet,
@@ -888,55 +1971,51 @@ pub mod variable_update_fn {
     where
         DO: SymbolicDomainOrd<T>,
     {
-        match expression {
+        try_bdd_from_expression(expression, named_symbolic_domains, bdd_variable_set)
+            .expect("domains with ordering support all comparisons")
+    }
+
+    /// Like [bdd_from_expression], but also accepts domains without ordering. Returns an error
+    /// if some proposition of the `expression` is not supported by the domain of its variable.
+    pub fn try_bdd_from_expression<DO, T>(
+        expression: &Expression<T>,
+        named_symbolic_domains: &HashMap<&str, &DO>,
+        bdd_variable_set: &BddVariableSet,
+    ) -> Result<Bdd, UpdateFnError>
+    where
+        DO: SymbolicDomainEq<T>,
+    {
+        let recurse = |expression| {
+            try_bdd_from_expression(expression, named_symbolic_domains, bdd_variable_set)
+        };
+
+        Ok(match expression {
             Expression::Terminal(proposition) => {
-                bdd_from_proposition(proposition, named_symbolic_domains, bdd_variable_set)
-            }
-            Expression::Not(expression) => {
-                bdd_from_expression(expression, named_symbolic_domains, bdd_variable_set).not()
-            }
-            Expression::And(clauses) => {
-                clauses
-                    .iter()
-                    .fold(bdd_variable_set.mk_true(), |acc, clausule| {
-                        acc.and(&bdd_from_expression(
-                            clausule,
-                            named_symbolic_domains,
-                            bdd_variable_set,
-                        ))
-                    })
+                bdd_from_proposition(proposition, named_symbolic_domains, bdd_variable_set)?
             }
-            Expression::Or(clauses) => {
-                clauses
-                    .iter()
-                    .fold(bdd_variable_set.mk_false(), |acc, clausule| {
-                        acc.or(&bdd_from_expression(
-                            clausule,
-                            named_symbolic_domains,
-                            bdd_variable_set,
-                        ))
-                    })
-            }
-            Expression::Xor(lhs, rhs) => {
-                let lhs = bdd_from_expression(lhs, named_symbolic_domains, bdd_variable_set);
-                let rhs = bdd_from_expression(rhs, named_symbolic_domains, bdd_variable_set);
-                lhs.xor(&rhs)
-            }
-            Expression::Implies(lhs, rhs) => {
-                let lhs = bdd_from_expression(lhs, named_symbolic_domains, bdd_variable_set);
-                let rhs = bdd_from_expression(rhs, named_symbolic_domains, bdd_variable_set);
-                lhs.imp(&rhs)
-            }
-        }
+            Expression::Not(expression) => recurse(expression)?.not(),
+            Expression::And(clauses) => clauses
+                .iter()
+                .try_fold(bdd_variable_set.mk_true(), |acc, clausule| {
+                    Ok::<_, UpdateFnError>(acc.and(&recurse(clausule)?))
+                })?,
+            Expression::Or(clauses) => clauses
+                .iter()
+                .try_fold(bdd_variable_set.mk_false(), |acc, clausule| {
+                    Ok::<_, UpdateFnError>(acc.or(&recurse(clausule)?))
+                })?,
+            Expression::Xor(lhs, rhs) => recurse(lhs)?.xor(&recurse(rhs)?),
+            Expression::Implies(lhs, rhs) => recurse(lhs)?.imp(&recurse(rhs)?),
+        })
     }
 
     fn bdd_from_proposition<DO, T>(
         proposition: &Proposition<T>,
         named_symbolic_domains: &HashMap<&str, &DO>,
         bdd_variable_set: &BddVariableSet,
-    ) -> Bdd
+    ) -> Result<Bdd, UpdateFnError>
     where
-        DO: SymbolicDomainOrd<T>,
+        DO: SymbolicDomainEq<T>,
     {
         let target_vars_domain = named_symbolic_domains.get(proposition.variable.as_str()).unwrap_or_else(
             || panic!(
@@ -946,15 +2025,15 @@ pub mod variable_update_fn {
             )
         );
 
-        match proposition.comparison_operator {
-            CmpOp::Eq => target_vars_domain.encode_one(bdd_variable_set, &proposition.value),
-            CmpOp::Neq => target_vars_domain
-                .encode_one(bdd_variable_set, &proposition.value)
-                .not(),
-            CmpOp::Lt => target_vars_domain.encode_lt(bdd_variable_set, &proposition.value),
-            CmpOp::Leq => target_vars_domain.encode_le(bdd_variable_set, &proposition.value),
-            CmpOp::Gt => target_vars_domain.encode_gt(bdd_variable_set, &proposition.value),
-            CmpOp::Geq => target_vars_domain.encode_ge(bdd_variable_set, &proposition.value),
-        }
+        target_vars_domain
+            .encode_comparison(
+                bdd_variable_set,
+                proposition.comparison_operator,
+                &proposition.value,
+            )
+            .map_err(|source| UpdateFnError::UnsupportedProposition {
+                variable: proposition.variable.clone(),
+                source,
+            })
     }
 }
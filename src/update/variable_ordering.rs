@@ -0,0 +1,287 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
+
+use thiserror::Error;
+
+use super::unprocessed_variable_update_function::UnprocessedVariableUpdateFn;
+
+/// The order in which the system variables of a [SmartSystemUpdateFn] appear in its BDDs.
+///
+/// The primed copy of every variable is always placed right after the variable itself.
+///
+/// [SmartSystemUpdateFn]: crate::update::update_fn::SmartSystemUpdateFn
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum VariableOrder {
+    /// Sorted by the names of the variables.
+    #[default]
+    Alphabetical,
+    /// The given order, which must list every system variable exactly once.
+    Explicit(Vec<String>),
+    /// Depth-first traversal of the (undirected) regulatory graph, so that the regulators
+    /// of a variable tend to be placed close to it.
+    RegulatoryDfs,
+    /// Breadth-first traversal of the (undirected) regulatory graph.
+    RegulatoryBfs,
+    /// The FORCE heuristic (Aloul et al., 2003): starting from the alphabetical order, every
+    /// variable is repeatedly moved to the average "center of gravity" of the update functions
+    /// it appears in, as long as the total span of the update functions decreases.
+    Force,
+}
+
+/// Describes how the BDD variables of a [SmartSystemUpdateFn] are ordered.
+///
+/// [SmartSystemUpdateFn]: crate::update::update_fn::SmartSystemUpdateFn
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VariableOrdering {
+    pub order: VariableOrder,
+    /// If set, the bits of every domain are interleaved with the bits of its primed copy,
+    /// i.e. `x_v1, x'_v1, x_v2, x'_v2` instead of `x_v1, x_v2, x'_v1, x'_v2`.
+    pub interleave_bits: bool,
+}
+
+impl VariableOrdering {
+    pub fn new(order: VariableOrder, interleave_bits: bool) -> Self {
+        Self {
+            order,
+            interleave_bits,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum VariableOrderError {
+    MissingVariable(String),
+    UnknownVariable(String),
+    DuplicateVariable(String),
+}
+
+impl Display for VariableOrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VariableOrderError::MissingVariable(name) => {
+                write!(f, "Variable {} is missing from the order", name)
+            }
+            VariableOrderError::UnknownVariable(name) => {
+                write!(f, "Variable {} is not a system variable", name)
+            }
+            VariableOrderError::DuplicateVariable(name) => {
+                write!(f, "Variable {} appears in the order more than once", name)
+            }
+        }
+    }
+}
+
+impl VariableOrder {
+    /// Sorts the variables of the given update functions according to this order.
+    ///
+    /// Returns an error if an [VariableOrder::Explicit] order does not list every variable
+    /// exactly once.
+    pub fn sort_variables<T>(
+        &self,
+        update_fns: &HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    ) -> Result<Vec<String>, VariableOrderError> {
+        let regulators = update_fns
+            .iter()
+            .map(|(target, update_fn)| {
+                let variables = update_fn
+                    .input_vars_names
+                    .iter()
+                    .map(|name| name.as_str())
+                    .chain(
+                        update_fn
+                            .terms
+                            .iter()
+                            .flat_map(|(_, term)| term.variables()),
+                    );
+                (
+                    target.as_str(),
+                    regulators_of(target, variables, update_fns),
+                )
+            })
+            .collect();
+        self.sort_by_regulators(&regulators)
+    }
+
+    /// Same as [Self::sort_variables], but the update functions are given only by the system
+    /// variables they depend on (which must include the target variable itself).
+    pub(crate) fn sort_by_regulators(
+        &self,
+        regulators: &HashMap<&str, BTreeSet<&str>>,
+    ) -> Result<Vec<String>, VariableOrderError> {
+        let alphabetical = {
+            let mut names = regulators
+                .keys()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+
+        match self {
+            VariableOrder::Alphabetical => Ok(alphabetical),
+            VariableOrder::Explicit(order) => {
+                let mut seen = HashSet::new();
+                for name in order {
+                    if !regulators.contains_key(name.as_str()) {
+                        return Err(VariableOrderError::UnknownVariable(name.clone()));
+                    }
+                    if !seen.insert(name.as_str()) {
+                        return Err(VariableOrderError::DuplicateVariable(name.clone()));
+                    }
+                }
+                match alphabetical
+                    .into_iter()
+                    .find(|name| !seen.contains(name.as_str()))
+                {
+                    Some(missing) => Err(VariableOrderError::MissingVariable(missing)),
+                    None => Ok(order.clone()),
+                }
+            }
+            VariableOrder::RegulatoryDfs => {
+                Ok(graph_traversal(&alphabetical, regulators, Traversal::Dfs))
+            }
+            VariableOrder::RegulatoryBfs => {
+                Ok(graph_traversal(&alphabetical, regulators, Traversal::Bfs))
+            }
+            VariableOrder::Force => Ok(force(alphabetical, regulators)),
+        }
+    }
+}
+
+/// The system variables among `variables`, together with the `target` itself.
+pub(crate) fn regulators_of<'a, V>(
+    target: &'a str,
+    variables: impl Iterator<Item = &'a str>,
+    update_fns: &HashMap<String, V>,
+) -> BTreeSet<&'a str> {
+    variables
+        .chain([target])
+        .filter(|name| update_fns.contains_key(*name))
+        .collect()
+}
+
+#[derive(Clone, Copy)]
+enum Traversal {
+    Dfs,
+    Bfs,
+}
+
+/// Traverses the regulatory graph (ignoring the direction of the edges). The traversal starts
+/// from the alphabetically first unvisited variable and the neighbours are visited in the
+/// alphabetical order as well.
+fn graph_traversal(
+    alphabetical: &[String],
+    regulators: &HashMap<&str, BTreeSet<&str>>,
+    traversal: Traversal,
+) -> Vec<String> {
+    let mut neighbours = HashMap::<&str, BTreeSet<&str>>::new();
+    for (target, target_regulators) in regulators {
+        for regulator in target_regulators.iter().copied() {
+            if regulator != *target {
+                neighbours.entry(target).or_default().insert(regulator);
+                neighbours.entry(regulator).or_default().insert(target);
+            }
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    for root in alphabetical {
+        let mut to_visit = VecDeque::from([root.as_str()]);
+        while let Some(name) = match traversal {
+            Traversal::Dfs => to_visit.pop_back(),
+            Traversal::Bfs => to_visit.pop_front(),
+        } {
+            if !visited.insert(name) {
+                continue;
+            }
+            order.push(name.to_string());
+            let unvisited = neighbours
+                .get(name)
+                .into_iter()
+                .flatten()
+                .filter(|it| !visited.contains(*it));
+            match traversal {
+                // reversed, so that the alphabetically first neighbour is popped first
+                Traversal::Dfs => to_visit.extend(unvisited.rev()),
+                Traversal::Bfs => to_visit.extend(unvisited),
+            }
+        }
+    }
+    order
+}
+
+/// The maximal number of iterations of the FORCE heuristic.
+const FORCE_MAX_ITERATIONS: usize = 100;
+
+fn force(initial: Vec<String>, regulators: &HashMap<&str, BTreeSet<&str>>) -> Vec<String> {
+    let index_of = initial
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| (name.as_str(), idx))
+        .collect::<HashMap<_, _>>();
+    // every update function is a hyperedge connecting the variables it depends on
+    // (iterated in a fixed order, so that the floating point sums below are deterministic)
+    let hyperedges = initial
+        .iter()
+        .map(|target| {
+            regulators[target.as_str()]
+                .iter()
+                .map(|name| index_of[name])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let span = |positions: &[usize]| {
+        hyperedges
+            .iter()
+            .map(|edge| {
+                let (min, max) = edge.iter().fold((usize::MAX, 0), |(min, max), var| {
+                    (min.min(positions[*var]), max.max(positions[*var]))
+                });
+                max - min
+            })
+            .sum::<usize>()
+    };
+
+    // `positions[var]` is the position of the variable (given by its initial index)
+    let mut positions = (0..initial.len()).collect::<Vec<_>>();
+    let mut best_span = span(&positions);
+    for _ in 0..FORCE_MAX_ITERATIONS {
+        let mut gravity = vec![(0.0, 0usize); initial.len()];
+        for edge in &hyperedges {
+            let center =
+                edge.iter().map(|var| positions[*var] as f64).sum::<f64>() / edge.len() as f64;
+            for var in edge {
+                gravity[*var].0 += center;
+                gravity[*var].1 += 1;
+            }
+        }
+
+        let mut order = (0..initial.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            // every variable is in at least one hyperedge (of its own update function)
+            let target = |var: usize| gravity[var].0 / gravity[var].1 as f64;
+            target(*a)
+                .total_cmp(&target(*b))
+                .then(positions[*a].cmp(&positions[*b]))
+        });
+        let mut new_positions = vec![0; initial.len()];
+        for (position, var) in order.into_iter().enumerate() {
+            new_positions[var] = position;
+        }
+
+        let new_span = span(&new_positions);
+        if new_span >= best_span {
+            break;
+        }
+        best_span = new_span;
+        positions = new_positions;
+    }
+
+    let mut named_positions = initial.into_iter().zip(positions).collect::<Vec<_>>();
+    named_positions.sort_by_key(|(_, position)| *position);
+    named_positions.into_iter().map(|(name, _)| name).collect()
+}
@@ -1,4 +1,5 @@
 pub mod expression_parser;
+pub mod qualitative_species_parser;
 pub mod utils;
 pub mod variable_update_fn_parser;
 pub mod xml_reader;
@@ -0,0 +1,57 @@
+use std::{collections::HashMap, io::BufRead, str::FromStr};
+
+use super::{
+    utils::{consume_the_rest_of_element, map_list, StartElementWrapper, XmlReadingError},
+    xml_reader::XmlReader,
+};
+
+/// Expect the current XML element to be <listOfQualitativeSpecies>
+/// Loads the `initialLevel` of every contained <qualitativeSpecies>. The species without
+/// an `initialLevel` are not present in the result, i.e. their initial value is not
+/// restricted.
+#[allow(clippy::result_large_err)]
+pub fn load_initial_levels<XR, BR, T>(xml: &mut XR) -> Result<HashMap<String, T>, XmlReadingError>
+where
+    XR: XmlReader<BR>,
+    BR: BufRead,
+    T: FromStr,
+{
+    let initial_levels = map_list(
+        xml,
+        "listOfQualitativeSpecies",
+        "qualitativeSpecies",
+        process_qualitative_species_item,
+    )?;
+
+    Ok(initial_levels.into_iter().flatten().collect())
+}
+
+#[allow(clippy::result_large_err)]
+fn process_qualitative_species_item<T: FromStr, XR: XmlReader<BR>, BR: BufRead>(
+    xml: &mut XR,
+    current: StartElementWrapper,
+) -> Result<Option<(String, T)>, XmlReadingError> {
+    let attribute = |name: &str| {
+        current
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name.local_name == name)
+            .map(|attribute| attribute.value.trim())
+    };
+
+    let id = attribute("id")
+        .ok_or_else(|| XmlReadingError::NoSuchAttribute("id".to_string()))?
+        .to_string();
+    let initial_level = attribute("initialLevel")
+        .map(|level| {
+            level
+                .parse::<T>()
+                .map_err(|_| XmlReadingError::ParsingError(level.to_string()))
+        })
+        .transpose()?;
+
+    // the species may contain annotations, notes, etc.
+    consume_the_rest_of_element(xml, "qualitativeSpecies")?;
+
+    Ok(initial_level.map(|level| (id, level)))
+}
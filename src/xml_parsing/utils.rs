@@ -275,7 +275,6 @@ where
 /// Is also capable of working with recursive elements (elements that can contain themselves).
 /// In that case, this function returns once it encounters the closing tag of the element
 /// it is called from.
-#[allow(dead_code)] // useless rn
 pub fn consume_the_rest_of_element<XR, BR>(
     xml: &mut XR,
     element_name: &str,
@@ -178,9 +178,10 @@ fn result_level_from_attributes<T: FromStr>(
         .map_err(|_| XmlReadingError::ParsingError(attribute_with_result_lvl.value.clone()))
 }
 
-/// Expect the current XML element to be <listOfFunctionTerms>
-/// Loads all contained <functionTerm> elements into a HashMap.
-fn load_all_update_fns<XR, BR, T>(
+/// Expect the current XML element to be <listOfTransitions>
+/// Loads all contained <transition> elements into a HashMap. Variables that appear only as
+/// inputs get a constant update function with the `Default` value.
+pub fn load_all_update_fns<XR, BR, T>(
     xml: &mut XR,
 ) -> Result<HashMap<String, UnprocessedVariableUpdateFn<T>>, XmlReadingError>
 where
@@ -221,13 +222,13 @@ where
     Ok(all_vars_and_their_update_fns)
 }
 
-use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
+use crate::symbolic_domains::symbolic_domain::SymbolicDomainEq;
 use crate::update::update_fn::{SmartSystemUpdateFn, SystemUpdateFn};
 
 impl<DO, T> SystemUpdateFn<DO, T>
 where
-    DO: SymbolicDomainOrd<T>,
-    T: FromStr + Default,
+    DO: SymbolicDomainEq<T>,
+    T: FromStr + Default + Clone + PartialEq,
 {
     /// Parses the <transition> XML element into a VariableUpdateFn struct.
     /// Expects the parameter `xml` to be at the start of the <transition> XML element.
@@ -243,8 +244,8 @@ where
 
 impl<DO, T> SmartSystemUpdateFn<DO, T>
 where
-    DO: SymbolicDomainOrd<T>,
-    T: FromStr + Default,
+    DO: SymbolicDomainEq<T>,
+    T: FromStr + Default + Clone + PartialEq,
 {
     /// Parses the <transition> XML element into a VariableUpdateFn struct.
     /// Expects the parameter `xml` to be at the start of the <transition> XML element.
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        basins::BasinAnalysis,
+        explicit_system::ExplicitSystem,
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{random_model, toggle_switch, RandomModelOptions},
+};
+use num_bigint::BigInt;
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+#[test]
+fn toggle_switch_basins() {
+    let system = System::from_update_fns(toggle_switch());
+    let analysis = BasinAnalysis::new(&system);
+    let basins = analysis.basins();
+    assert_eq!(basins.len(), 2);
+
+    for basin in &basins {
+        assert_eq!(system.iter_states(&basin.attractor).count(), 1);
+        // both fixed points are reachable from the other two states
+        assert_eq!(basin.weak_size, BigInt::from(3));
+        assert_eq!(basin.strong_size, BigInt::from(1));
+        assert_eq!(basin.weak_ratio, 0.75);
+        assert_eq!(basin.strong_ratio, 0.25);
+        assert_eq!(basin.strong, basin.attractor);
+    }
+    let p_high = system.encode_state(&State::new(vec![1, 0])).expect("valid");
+    assert_eq!(
+        system
+            .iter_states(&analysis.weak_basin(&p_high))
+            .collect::<HashSet<_>>(),
+        HashSet::from([
+            State::new(vec![0, 0]),
+            State::new(vec![1, 0]),
+            State::new(vec![1, 1])
+        ])
+    );
+}
+
+/// The attractors reachable from every state, computed by explicit graph search.
+fn reachable_attractors(
+    explicit: &ExplicitSystem<u8>,
+    states: &[State<u8>],
+) -> HashMap<State<u8>, HashSet<State<u8>>> {
+    let reachable = states
+        .iter()
+        .map(|state| {
+            let mut visited = HashSet::from([state.clone()]);
+            let mut stack = vec![state.clone()];
+            while let Some(state) = stack.pop() {
+                for (_, successor) in explicit.successors_of_state(&state).expect("valid") {
+                    if visited.insert(successor.clone()) {
+                        stack.push(successor);
+                    }
+                }
+            }
+            (state.clone(), visited)
+        })
+        .collect::<HashMap<_, _>>();
+    // a state is in an attractor iff it is reachable from all the states it can reach
+    reachable
+        .iter()
+        .map(|(state, targets)| {
+            let attractor_states = targets
+                .iter()
+                .filter(|target| {
+                    reachable[*target]
+                        .iter()
+                        .all(|it| reachable[it].contains(target))
+                })
+                .cloned()
+                .collect();
+            (state.clone(), attractor_states)
+        })
+        .collect()
+}
+
+fn check_basins<D: SymbolicDomainOrd<u8>>(seed: u64) {
+    let model = random_model(seed, &RandomModelOptions::default());
+    let system = SmartSystemUpdateFn::<D, u8>::from_update_fns(model.update_fns.clone());
+    let explicit = ExplicitSystem::new(model.update_fns.clone());
+    let states = system
+        .iter_states(&system.unit_vertex_set())
+        .collect::<Vec<_>>();
+    let expected = reachable_attractors(&explicit, &states);
+
+    let analysis = BasinAnalysis::new(&system);
+    let attractors = analysis.attractors();
+    let mut covered = HashSet::new();
+    for attractor in &attractors {
+        let attractor_states = system.iter_states(attractor).collect::<HashSet<_>>();
+        assert!(attractor_states
+            .iter()
+            .all(|state| covered.insert(state.clone())));
+
+        let basin = analysis.basin(attractor);
+        let weak = states
+            .iter()
+            .filter(|state| !expected[*state].is_disjoint(&attractor_states))
+            .cloned()
+            .collect::<HashSet<_>>();
+        let strong = states
+            .iter()
+            .filter(|state| expected[*state].is_subset(&attractor_states))
+            .cloned()
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            system.iter_states(&basin.weak).collect::<HashSet<_>>(),
+            weak,
+            "seed {}",
+            seed
+        );
+        assert_eq!(
+            system.iter_states(&basin.strong).collect::<HashSet<_>>(),
+            strong,
+            "seed {}",
+            seed
+        );
+        assert_eq!(basin.weak_size, BigInt::from(weak.len()));
+        assert_eq!(basin.strong_size, BigInt::from(strong.len()));
+        assert_eq!(basin.weak_ratio, weak.len() as f64 / states.len() as f64);
+    }
+    let all_attractor_states = expected.values().flatten().cloned().collect::<HashSet<_>>();
+    assert_eq!(covered, all_attractor_states, "seed {}", seed);
+}
+
+#[test]
+fn basins_match_explicit_reachability() {
+    for seed in 0..10 {
+        check_basins::<UnaryIntegerDomain>(seed);
+        check_basins::<BinaryIntegerDomain<u8>>(seed);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::{
+    prelude::{
+        expression::Expression,
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+        update_fn::{variable_update_fn::bdd_from_expression, SmartSystemUpdateFn, SystemUpdateFn},
+    },
+    test_utils::load_update_fns,
+};
+
+const MODELS: [&str; 4] = [
+    "data/manual/handbook_example.sbml",
+    "data/manual/basic_transition.sbml",
+    "data/test-models/175_SEA-URCHIN.sbml",
+    "data/test-models/178-mast-cell-activation.sbml",
+];
+
+/// Decodes the update functions of both system variants and checks that encoding the decoded
+/// terms yields exactly the states in which the variable is updated to the given value.
+fn check_update_fn_terms_round_trip<DO>(sbml_path: &str)
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let update_fns = load_update_fns(sbml_path);
+    let smart_system = SmartSystemUpdateFn::<DO, u8>::from_update_fns(update_fns.clone());
+    let dumb_system = SystemUpdateFn::<DO, u8>::from_update_fns(update_fns.clone());
+
+    let named_domains = smart_system
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let unit = smart_system.unit_vertex_set();
+    let encode = |expression: &Expression<u8>| -> Bdd {
+        bdd_from_expression(
+            expression,
+            &named_domains,
+            smart_system.get_bdd_variable_set(),
+        )
+        .and(&unit)
+    };
+
+    for var_name in smart_system.get_system_variables() {
+        let terms = smart_system
+            .update_fn_terms(&var_name)
+            .expect("variable should be known");
+        assert!(!terms.is_empty());
+
+        let mut covered = smart_system.get_bdd_variable_set().mk_false();
+        for (value, condition) in &terms {
+            let expected = smart_system
+                .predecessors_async(&var_name, smart_system.encode_one(&var_name, value))
+                .and(&unit);
+            let encoded = encode(condition);
+            assert_eq!(
+                expected, encoded,
+                "{} -> {}: {}",
+                var_name, value, condition
+            );
+            assert!(covered.and(&encoded).is_false(), "terms must be exclusive");
+            covered = covered.or(&encoded);
+        }
+        assert_eq!(covered, unit, "terms must cover all states");
+
+        // the dumb system uses its own variable set, hence compare the decoded formulas
+        let dumb_terms = dumb_system
+            .update_fn_terms(&var_name)
+            .expect("variable should be known");
+        assert_eq!(terms.len(), dumb_terms.len());
+        for ((value, condition), (dumb_value, dumb_condition)) in terms.iter().zip(&dumb_terms) {
+            assert_eq!(value, dumb_value);
+            assert_eq!(encode(condition), encode(dumb_condition));
+        }
+    }
+}
+
+#[test]
+fn update_fn_terms_of_unknown_variables() {
+    let update_fns = load_update_fns("data/test-models/175_SEA-URCHIN.sbml");
+    let smart_system =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(update_fns.clone());
+    let dumb_system = SystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(update_fns);
+    assert!(smart_system.update_fn_terms("unknown").is_none());
+    assert!(dumb_system.update_fn_terms("unknown").is_none());
+}
+
+#[test]
+fn update_fn_terms_round_trip_unary() {
+    MODELS
+        .iter()
+        .for_each(|path| check_update_fn_terms_round_trip::<UnaryIntegerDomain>(path));
+}
+
+#[test]
+fn update_fn_terms_round_trip_binary() {
+    MODELS
+        .iter()
+        .for_each(|path| check_update_fn_terms_round_trip::<BinaryIntegerDomain<u8>>(path));
+}
+
+#[test]
+fn state_sets_round_trip() {
+    let update_fns = load_update_fns("data/test-models/175_SEA-URCHIN.sbml");
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(update_fns);
+    let named_domains = system
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let unit = system.unit_vertex_set();
+
+    let mut set = system.get_bdd_variable_set().mk_false();
+    for var_name in system.get_system_variables().iter().take(6) {
+        set = set.or(&system.predecessors_async(var_name, system.encode_one(var_name, &1)));
+
+        let expression = system.bdd_to_expression(&set);
+        let encoded =
+            bdd_from_expression(&expression, &named_domains, system.get_bdd_variable_set());
+        assert_eq!(set.and(&unit), encoded.and(&unit), "{}", expression);
+    }
+
+    assert_eq!(system.bdd_to_expression(&unit), Expression::mk_true());
+    assert_eq!(
+        system.bdd_to_expression(&system.get_bdd_variable_set().mk_false()),
+        Expression::mk_false()
+    );
+}
@@ -0,0 +1,177 @@
+use biodivine_lib_bdd::{BddVariableSet, BddVariableSetBuilder};
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    proposition::{ComparisonOperator as CmpOp, Proposition},
+    symbolic_domain::{
+        BooleanDomain, MixedIntegerDomain, SymbolicDomain, SymbolicDomainEq, SymbolicDomainOrd,
+        UnaryIntegerDomain,
+    },
+    update_fn::{SmartSystemUpdateFn, SystemUpdateFn},
+};
+
+fn load_xml(sbml_path: &str) -> xml::reader::EventReader<std::io::BufReader<std::fs::File>> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    xml
+}
+
+fn variable_names(bdd_variable_set: &BddVariableSet) -> Vec<String> {
+    bdd_variable_set
+        .variables()
+        .into_iter()
+        .map(|var| bdd_variable_set.name_of(var))
+        .collect()
+}
+
+#[test]
+fn boolean_domain_matches_unary_domain() {
+    let mut builder = BddVariableSetBuilder::new();
+    builder.make_variable("before");
+    let boolean = BooleanDomain::<u8>::new(&mut builder, "x", &1);
+    let bdd_variable_set = builder.build();
+
+    let mut builder = BddVariableSetBuilder::new();
+    builder.make_variable("before");
+    let unary = UnaryIntegerDomain::<u8>::new(&mut builder, "x", &1);
+    // both domains use the same variable, hence their BDDs can be compared directly
+    assert_eq!(
+        variable_names(&builder.build()),
+        variable_names(&bdd_variable_set)
+    );
+
+    assert_eq!(boolean.raw_bdd_variables(), unary.raw_bdd_variables());
+    assert!(boolean.unit_collection(&bdd_variable_set).is_true());
+    assert_eq!(boolean.get_all_possible_values(), vec![0, 1]);
+
+    for value in [0, 1] {
+        assert_eq!(
+            boolean.encode_one(&bdd_variable_set, &value),
+            unary.encode_one(&bdd_variable_set, &value)
+        );
+        assert_eq!(
+            boolean.raw_bdd_variables_encode(&value),
+            unary.raw_bdd_variables_encode(&value)
+        );
+    }
+
+    for value in [0, 1] {
+        for operator in [
+            CmpOp::Eq,
+            CmpOp::Neq,
+            CmpOp::Lt,
+            CmpOp::Leq,
+            CmpOp::Gt,
+            CmpOp::Geq,
+        ] {
+            let encoded = boolean
+                .encode_comparison(&bdd_variable_set, operator, &value)
+                .expect("Boolean domain supports all comparisons");
+            let expected = [0, 1]
+                .into_iter()
+                .filter(|it| Proposition::new(operator, "x".to_string(), value).evaluate(it))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                boolean.decode_collection(&bdd_variable_set, &encoded),
+                expected,
+                "x {} {}",
+                operator.symbol(),
+                value
+            );
+        }
+    }
+}
+
+#[test]
+fn boolean_variables_use_boolean_domain() {
+    // all variables but one are Boolean; the remaining one has three levels
+    let sbml_path = "data/test-models/178-mast-cell-activation.sbml";
+    let mixed =
+        SmartSystemUpdateFn::<MixedIntegerDomain, u8>::try_from_xml(&mut load_xml(sbml_path))
+            .expect("should be able to parse");
+    let unary =
+        SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::try_from_xml(&mut load_xml(sbml_path))
+            .expect("should be able to parse");
+
+    // a Boolean variable is encoded using the same single variable in both systems
+    assert_eq!(
+        variable_names(mixed.get_bdd_variable_set()),
+        variable_names(unary.get_bdd_variable_set())
+    );
+    assert_eq!(mixed.unit_vertex_set(), unary.unit_vertex_set());
+
+    let unit = unary.unit_vertex_set();
+    let state = unary.pick_state_bdd(&unit);
+    for var in unary.get_system_variables() {
+        assert_eq!(
+            mixed.successors_async(&var, &unit),
+            unary.successors_async(&var, &unit),
+            "{}",
+            var
+        );
+        assert_eq!(
+            mixed.successors_async(&var, &state),
+            unary.successors_async(&var, &state),
+            "{}",
+            var
+        );
+        assert_eq!(
+            mixed.predecessors_async(&var, state.clone()),
+            unary.predecessors_async(&var, state.clone()),
+            "{}",
+            var
+        );
+    }
+}
+
+#[test]
+fn boolean_domain_in_both_systems() {
+    let sbml_path = "data/manual/handbook_example.sbml";
+    let smart = SmartSystemUpdateFn::<BooleanDomain, u8>::try_from_xml(&mut load_xml(sbml_path))
+        .expect("should be able to parse");
+    let dumb = SystemUpdateFn::<BooleanDomain, u8>::try_from_xml(&mut load_xml(sbml_path))
+        .expect("should be able to parse");
+
+    let variables = smart.get_system_variables();
+    let encode_smart = |values: &[u8]| {
+        variables
+            .iter()
+            .zip(values)
+            .fold(smart.unit_vertex_set(), |acc, (var, value)| {
+                acc.and(&smart.encode_one(var, value))
+            })
+    };
+    let encode_dumb = |values: &[u8]| {
+        variables
+            .iter()
+            .zip(values)
+            .map(|(var, value)| dumb.encode_one(var, value))
+            .reduce(|acc, it| acc.and(&it))
+            .expect("model has some variables")
+    };
+
+    for values in [[0, 0], [0, 1], [1, 0], [1, 1]] {
+        for var in &variables {
+            let successors = smart.successors_async(var, &encode_smart(&values));
+            let successor_values = variables
+                .iter()
+                .map(|it| {
+                    let decoded = smart
+                        .get_domain(it)
+                        .expect("known variable")
+                        .decode_collection(smart.get_bdd_variable_set(), &successors);
+                    assert_eq!(decoded.len(), 1, "successor should be a single state");
+                    decoded[0]
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(successors, encode_smart(&successor_values));
+            assert_eq!(
+                dumb.successors_async(var, &encode_dumb(&values)),
+                encode_dumb(&successor_values),
+                "{}",
+                var
+            );
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::{
+    prelude::{
+        control::{ControlError, ControlOptions, PhenotypeControl},
+        hctl::HctlChecker,
+        perturbation::Perturbation,
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainEq},
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{random_model, toggle_switch, RandomModelOptions},
+};
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+fn fixations(fixations: &[(&str, u8)]) -> Vec<(String, u8)> {
+    fixations
+        .iter()
+        .map(|(variable, value)| (variable.to_string(), *value))
+        .collect()
+}
+
+#[test]
+fn toggle_switch_control() {
+    let system = System::from_update_fns(toggle_switch());
+    let phenotype = system.encode_one("p", &1);
+    let control = PhenotypeControl::new(&system, &phenotype);
+    let options = ControlOptions::default();
+
+    assert_eq!(
+        control.permanent(&options).expect("valid options"),
+        [fixations(&[("p", 1)]), fixations(&[("q", 0)])]
+    );
+
+    // resetting only one of the variables leads to a state from which both attractors
+    // are reachable
+    let source = State::new(vec![0, 1]);
+    assert_eq!(
+        control.one_step(&source, &options).expect("valid source"),
+        [fixations(&[("p", 1), ("q", 0)])]
+    );
+    // but holding either of them until the system stabilizes suffices
+    assert_eq!(
+        control.temporary(&source, &options).expect("valid source"),
+        [fixations(&[("p", 1)]), fixations(&[("q", 0)])]
+    );
+    // a source already in the phenotype needs no intervention
+    assert_eq!(
+        control
+            .one_step(&State::new(vec![1, 0]), &options)
+            .expect("valid source"),
+        [fixations(&[])]
+    );
+
+    let restricted = ControlOptions {
+        max_size: 1,
+        variables: Some(vec!["q".to_string()]),
+    };
+    assert_eq!(
+        control.permanent(&restricted).expect("valid options"),
+        [fixations(&[("q", 0)])]
+    );
+    assert!(control
+        .one_step(&source, &restricted)
+        .expect("valid source")
+        .is_empty());
+}
+
+#[test]
+fn invalid_control_inputs_are_rejected() {
+    let system = System::from_update_fns(toggle_switch());
+    let control = PhenotypeControl::new(&system, &system.encode_one("p", &1));
+    let options = ControlOptions {
+        max_size: 1,
+        variables: Some(vec!["r".to_string()]),
+    };
+    assert!(matches!(
+        control.permanent(&options),
+        Err(ControlError::UnknownVariable(name)) if name == "r"
+    ));
+    assert!(matches!(
+        control.one_step(&State::new(vec![0]), &ControlOptions::default()),
+        Err(ControlError::InvalidSource(_))
+    ));
+}
+
+/// All the candidates of at most `max_size` fixations, in no particular order.
+fn all_candidates(system: &System, max_size: usize) -> Vec<Vec<(String, u8)>> {
+    let mut candidates = vec![vec![]];
+    for variable in system.get_system_variables() {
+        let values = system
+            .get_domain(&variable)
+            .expect("known variable")
+            .get_all_possible_values();
+        let extended = candidates
+            .iter()
+            .filter(|candidate| candidate.len() < max_size)
+            .flat_map(|candidate| {
+                values.iter().map(|value| {
+                    let mut candidate = candidate.clone();
+                    candidate.push((variable.clone(), *value));
+                    candidate
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.extend(extended);
+    }
+    candidates
+}
+
+fn perturbed(system: &System, fixations: &[(String, u8)]) -> System {
+    system
+        .perturbed(
+            &fixations
+                .iter()
+                .map(|(variable, value)| (variable.clone(), Perturbation::Fixed(*value)))
+                .collect(),
+        )
+        .expect("valid perturbation")
+}
+
+fn is_subset(lhs: &Bdd, rhs: &Bdd) -> bool {
+    lhs.and_not(rhs).is_false()
+}
+
+/// The inclusion-minimal candidates satisfying `is_solution`, sorted.
+fn minimal(
+    candidates: Vec<Vec<(String, u8)>>,
+    is_solution: impl Fn(&[(String, u8)]) -> bool,
+) -> Vec<Vec<(String, u8)>> {
+    let solutions = candidates
+        .into_iter()
+        .filter(|candidate| is_solution(candidate))
+        .collect::<Vec<_>>();
+    let mut minimal = solutions
+        .iter()
+        .filter(|solution| {
+            !solutions.iter().any(|other| {
+                other.len() < solution.len() && other.iter().all(|it| solution.contains(it))
+            })
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    minimal.sort();
+    minimal
+}
+
+fn sorted(mut solutions: Vec<Vec<(String, u8)>>) -> Vec<Vec<(String, u8)>> {
+    solutions.sort();
+    solutions
+}
+
+/// Compares the symbolic control with a brute force search which enumerates the attractors
+/// of every candidate explicitly.
+#[test]
+fn control_matches_explicit_attractors() {
+    let options = ControlOptions {
+        max_size: 2,
+        variables: None,
+    };
+    for seed in 0..6 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let system = System::from_update_fns(model.update_fns.clone());
+        let phenotype = system.encode_one("v0", &model.max_values["v0"]);
+        let control = PhenotypeControl::new(&system, &phenotype);
+        let source = State::new(vec![0; system.get_system_variables().len()]);
+        let source_bdd = system.encode_state(&source).expect("valid state");
+        let candidates = all_candidates(&system, options.max_size);
+
+        let expected = minimal(candidates.clone(), |fixations| {
+            let perturbed = perturbed(&system, fixations);
+            is_subset(&HctlChecker::new(&perturbed).attractor_states(), &phenotype)
+        });
+        assert_eq!(
+            sorted(control.permanent(&options).expect("valid options")),
+            expected,
+            "seed {}",
+            seed
+        );
+
+        let checker = HctlChecker::new(&system);
+        let expected = minimal(candidates.clone(), |fixations| {
+            let mut values = source.values().to_vec();
+            for (variable, value) in fixations {
+                values[system.variable_index(variable).expect("known variable")] = *value;
+            }
+            let reset = system
+                .encode_state(&State::new(values))
+                .expect("valid state");
+            is_subset(&checker.reachable_attractor_states(&reset), &phenotype)
+        });
+        assert_eq!(
+            sorted(control.one_step(&source, &options).expect("valid source")),
+            expected,
+            "seed {}",
+            seed
+        );
+
+        let expected = minimal(candidates, |fixations| {
+            let perturbed = perturbed(&system, fixations);
+            let released = HctlChecker::new(&perturbed).reachable_attractor_states(&source_bdd);
+            is_subset(&checker.reachable_attractor_states(&released), &phenotype)
+        });
+        assert_eq!(
+            sorted(control.temporary(&source, &options).expect("valid source")),
+            expected,
+            "seed {}",
+            seed
+        );
+    }
+}
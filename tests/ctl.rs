@@ -0,0 +1,293 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        ctl::{CtlChecker, CtlError, CtlFormula as Ctl},
+        explicit_system::ExplicitSystem,
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        proposition::ComparisonOperator as CmpOp,
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainEq, UnaryIntegerDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{prop, random_model, RandomModel, RandomModelOptions},
+};
+
+/// Evaluates the formula on the explicit state-transition graph, where the fixed points
+/// have a self-loop.
+struct ExplicitCtl {
+    system: ExplicitSystem<u8>,
+    states: Vec<State<u8>>,
+}
+
+impl ExplicitCtl {
+    fn new(model: &RandomModel) -> Self {
+        let system = ExplicitSystem::new(model.update_fns.clone());
+        let states = system
+            .get_system_variables()
+            .iter()
+            .fold(vec![Vec::new()], |states, name| {
+                states
+                    .into_iter()
+                    .flat_map(|values: Vec<u8>| {
+                        (0..=model.max_values[name]).map(move |value| {
+                            let mut values = values.clone();
+                            values.push(value);
+                            values
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .map(State::new)
+            .collect();
+        Self { system, states }
+    }
+
+    fn successors(&self, state: &State<u8>) -> Vec<State<u8>> {
+        let successors = self
+            .system
+            .successors_of_state(state)
+            .expect("valid state")
+            .into_iter()
+            .map(|(_, successor)| successor)
+            .collect::<Vec<_>>();
+        if successors.is_empty() {
+            vec![state.clone()]
+        } else {
+            successors
+        }
+    }
+
+    fn ex(&self, set: &HashSet<State<u8>>) -> HashSet<State<u8>> {
+        self.filter(|state| self.successors(state).iter().any(|it| set.contains(it)))
+    }
+
+    fn ax(&self, set: &HashSet<State<u8>>) -> HashSet<State<u8>> {
+        self.filter(|state| self.successors(state).iter().all(|it| set.contains(it)))
+    }
+
+    fn filter(&self, predicate: impl Fn(&State<u8>) -> bool) -> HashSet<State<u8>> {
+        self.states
+            .iter()
+            .filter(|it| predicate(it))
+            .cloned()
+            .collect()
+    }
+
+    fn fixed_point(
+        &self,
+        initial: HashSet<State<u8>>,
+        step: impl Fn(&HashSet<State<u8>>) -> HashSet<State<u8>>,
+    ) -> HashSet<State<u8>> {
+        let mut result = initial;
+        loop {
+            let next = step(&result);
+            if next == result {
+                return result;
+            }
+            result = next;
+        }
+    }
+
+    fn check(&self, formula: &Ctl<u8>) -> HashSet<State<u8>> {
+        let all = || self.states.iter().cloned().collect::<HashSet<_>>();
+        let not = |set: HashSet<State<u8>>| all().difference(&set).cloned().collect();
+        match formula {
+            Ctl::True => all(),
+            Ctl::False => HashSet::new(),
+            Ctl::Atom(proposition) => {
+                let idx = self
+                    .system
+                    .variable_index(&proposition.variable)
+                    .expect("known variable");
+                self.filter(|state| proposition.evaluate(&state.values()[idx]))
+            }
+            Ctl::Not(inner) => not(self.check(inner)),
+            Ctl::And(lhs, rhs) => &self.check(lhs) & &self.check(rhs),
+            Ctl::Or(lhs, rhs) => &self.check(lhs) | &self.check(rhs),
+            Ctl::Implies(lhs, rhs) => &not(self.check(lhs)) | &self.check(rhs),
+            Ctl::Ex(inner) => self.ex(&self.check(inner)),
+            Ctl::Ax(inner) => self.ax(&self.check(inner)),
+            Ctl::Ef(inner) => self.check(&Ctl::eu(Ctl::True, (**inner).clone())),
+            Ctl::Af(inner) => self.check(&Ctl::au(Ctl::True, (**inner).clone())),
+            Ctl::Ag(inner) => not(self.check(&Ctl::ef(Ctl::negate((**inner).clone())))),
+            Ctl::Eg(inner) => {
+                let set = self.check(inner);
+                self.fixed_point(set.clone(), |z| &set & &self.ex(z))
+            }
+            Ctl::Eu(lhs, rhs) => {
+                let (through, target) = (self.check(lhs), self.check(rhs));
+                self.fixed_point(HashSet::new(), |z| &target | &(&through & &self.ex(z)))
+            }
+            Ctl::Au(lhs, rhs) => {
+                let (through, target) = (self.check(lhs), self.check(rhs));
+                self.fixed_point(HashSet::new(), |z| &target | &(&through & &self.ax(z)))
+            }
+        }
+    }
+}
+
+fn formulas() -> Vec<Ctl<u8>> {
+    let atom = |variable: &str, operator, value| Ctl::atom(variable, operator, value);
+    vec![
+        Ctl::ex(atom("v0", CmpOp::Geq, 1)),
+        Ctl::ax(atom("v1", CmpOp::Eq, 0)),
+        Ctl::ef(atom("v0", CmpOp::Gt, 1)),
+        Ctl::af(atom("v1", CmpOp::Geq, 1)),
+        Ctl::eg(atom("v0", CmpOp::Neq, 0)),
+        Ctl::ag(atom("v2", CmpOp::Leq, 1)),
+        Ctl::eu(atom("v0", CmpOp::Geq, 1), atom("v1", CmpOp::Eq, 0)),
+        Ctl::au(atom("v1", CmpOp::Eq, 0), atom("v2", CmpOp::Geq, 1)),
+        Ctl::ag(Ctl::implies(
+            atom("v0", CmpOp::Eq, 1),
+            Ctl::af(atom("v1", CmpOp::Eq, 0)),
+        )),
+        Ctl::and(
+            Ctl::eg(Ctl::ef(atom("v2", CmpOp::Lt, 1))),
+            Ctl::negate(Ctl::or(Ctl::ax(Ctl::False), atom("v1", CmpOp::Gt, 0))),
+        ),
+        // the (possibly self-looping) fixed points
+        Ctl::ag(Ctl::and(
+            atom("v0", CmpOp::Eq, 0),
+            Ctl::and(atom("v1", CmpOp::Eq, 0), atom("v2", CmpOp::Eq, 0)),
+        )),
+    ]
+}
+
+fn check_random_models<D: SymbolicDomainEq<u8>>() {
+    for seed in 0..15 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let explicit = ExplicitCtl::new(&model);
+        let system = SmartSystemUpdateFn::<D, u8>::from_update_fns(model.update_fns.clone());
+        let checker = CtlChecker::new(&system);
+        for formula in formulas() {
+            let symbolic = checker.check(&formula).expect("valid formula");
+            let symbolic = system.iter_states(&symbolic).collect::<HashSet<_>>();
+            assert_eq!(symbolic, explicit.check(&formula), "{} {}", seed, formula);
+        }
+
+        // backward saturation agrees with `EF`, forward saturation is closed under successors
+        let target = checker
+            .check(&Ctl::atom("v0", CmpOp::Gt, 1))
+            .expect("valid formula");
+        let universe = system.unit_vertex_set();
+        assert_eq!(
+            checker.reach_bwd(&target, &universe),
+            checker.ef(&target),
+            "{}",
+            seed
+        );
+        let reachable = checker.reachable_from(&target);
+        assert!(target.imp(&reachable).is_true());
+        for variable in system.get_system_variables() {
+            let successors = system.successors_async(&variable, &reachable);
+            assert!(successors.imp(&reachable).is_true(), "{}", seed);
+        }
+    }
+}
+
+#[test]
+fn ctl_matches_explicit_semantics_unary() {
+    check_random_models::<UnaryIntegerDomain>();
+}
+
+#[test]
+fn ctl_matches_explicit_semantics_binary() {
+    check_random_models::<BinaryIntegerDomain<u8>>();
+}
+
+#[test]
+fn eventually_reaching_a_phenotype() {
+    // `p` counts down to zero, `q` is switched off once `p` is zero and on while `p` is two
+    let update_fns = HashMap::from([
+        (
+            "p".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec!["p".to_string()],
+                "p".to_string(),
+                vec![(1, prop(CmpOp::Eq, "p", 2)), (0, prop(CmpOp::Leq, "p", 1))],
+                0,
+            ),
+        ),
+        (
+            "q".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec!["p".to_string()],
+                "q".to_string(),
+                vec![(1, prop(CmpOp::Eq, "p", 2)), (0, prop(CmpOp::Eq, "p", 0))],
+                1,
+            ),
+        ),
+    ]);
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(update_fns);
+    let checker = CtlChecker::new(&system);
+
+    // from every state with p = 2, eventually q = 0
+    let property = Ctl::implies(
+        Ctl::atom("p", CmpOp::Eq, 2),
+        Ctl::af(Ctl::atom("q", CmpOp::Eq, 0)),
+    );
+    let satisfying = checker.check(&property).expect("valid formula");
+    assert_eq!(satisfying, system.unit_vertex_set());
+    let everywhere = checker.check(&Ctl::ag(property)).expect("valid formula");
+    assert_eq!(everywhere, system.unit_vertex_set());
+
+    // every state has a successor, the fixed point `p = 0, q = 0` a self-loop
+    let deadlocks = checker.check(&Ctl::ax(Ctl::False)).expect("valid formula");
+    assert!(deadlocks.is_false());
+    let stable = checker
+        .check(&Ctl::ag(Ctl::atom("q", CmpOp::Eq, 0)))
+        .expect("valid formula");
+    assert_eq!(
+        system.iter_states(&stable).collect::<Vec<_>>(),
+        [State::new(vec![0, 0])]
+    );
+}
+
+#[test]
+fn colored_ctl() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    let checker = CtlChecker::new(&system);
+    let c_on = system.encode_parameter("c", &[], &1);
+
+    let reaches_on = checker
+        .check(&Ctl::af(Ctl::atom("p", CmpOp::Eq, 1)))
+        .expect("valid formula");
+    assert_eq!(system.colors(&reaches_on), system.unit_colors());
+    assert_eq!(
+        reaches_on,
+        c_on.or(&system.encode_one("p", &1))
+            .and(&system.unit_vertex_set())
+    );
+}
+
+#[test]
+fn invalid_propositions_are_rejected() {
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(HashMap::from([(
+        "p".to_string(),
+        UnprocessedVariableUpdateFn::new(vec![], "p".to_string(), vec![], 1),
+    )]));
+    let checker = CtlChecker::new(&system);
+    let formula = Ctl::ef(Ctl::atom("r", CmpOp::Eq, 1));
+    assert!(matches!(
+        checker.check(&formula),
+        Err(CtlError::UnknownVariable(name)) if name == "r"
+    ));
+    assert_eq!(formula.to_string(), "EF (r = 1)");
+    assert_eq!(
+        Ctl::eu(Ctl::True, Ctl::negate(Ctl::atom("p", CmpOp::Geq, 1))).to_string(),
+        "E[(true) U (!(p >= 1))]"
+    );
+}
@@ -0,0 +1,45 @@
+use biodivine_lib_logical_models::test_utils::{
+    check_random_model, random_model, RandomModelOptions,
+};
+
+#[test]
+fn random_models_match_explicit_semantics() {
+    let options = RandomModelOptions::default();
+    for seed in 0..30 {
+        check_random_model(&random_model(seed, &options));
+    }
+}
+
+#[test]
+fn random_models_with_larger_domains_match_explicit_semantics() {
+    let options = RandomModelOptions {
+        variables: 2,
+        max_value: 6,
+        ..RandomModelOptions::default()
+    };
+    for seed in 0..15 {
+        check_random_model(&random_model(seed, &options));
+    }
+}
+
+#[test]
+fn random_models_are_reproducible() {
+    let options = RandomModelOptions::default();
+    let model = random_model(7, &options);
+    let again = random_model(7, &options);
+    assert_eq!(model.max_values, again.max_values);
+    assert_eq!(model.encodings, again.encodings);
+    for (name, update_fn) in &model.update_fns {
+        assert_eq!(update_fn.terms, again.update_fns[name].terms);
+        assert_eq!(update_fn.default, again.update_fns[name].default);
+        // every variable attains its maximal value
+        let max_value = model.max_values[name];
+        assert!(
+            update_fn.default == max_value
+                || update_fn
+                    .terms
+                    .iter()
+                    .any(|(output, _)| *output == max_value)
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use biodivine_lib_bdd::BddVariableSetBuilder;
+use biodivine_lib_logical_models::{
+    benchmarks::comparison_encoding::{naive_encode_lt, naive_unit_collection},
+    prelude::symbolic_domain::{BinaryIntegerDomain, GrayCodeIntegerDomain, SymbolicDomainOrd},
+};
+
+/// Checks the comparisons of domains of every size against the enumerating implementation.
+fn check_comparisons_match_naive<DO: SymbolicDomainOrd<u8>>() {
+    for max_value in (0..=40).chain([63, 64, 127, 128, 200, 255]) {
+        let mut builder = BddVariableSetBuilder::new();
+        // surround the domain by other variables to make sure they are not touched
+        builder.make_variable("before");
+        let domain = DO::new(&mut builder, "x", &max_value);
+        builder.make_variable("after");
+        let bdd_variable_set = builder.build();
+
+        let unit = domain.unit_collection(&bdd_variable_set);
+        assert_eq!(unit, naive_unit_collection(&domain, &bdd_variable_set));
+        assert_eq!(
+            domain.decode_collection(&bdd_variable_set, &unit).len(),
+            max_value as usize + 1
+        );
+
+        for bound in 0..=max_value {
+            assert_eq!(
+                domain.encode_lt(&bdd_variable_set, &bound),
+                naive_encode_lt(&domain, &bdd_variable_set, &bound),
+                "x < {} in domain with max {}",
+                bound,
+                max_value
+            );
+
+            let mut greater_or_equal = domain.decode_collection(
+                &bdd_variable_set,
+                &domain.encode_ge(&bdd_variable_set, &bound),
+            );
+            greater_or_equal.sort();
+            assert_eq!(greater_or_equal, (bound..=max_value).collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn binary_comparisons_match_naive() {
+    check_comparisons_match_naive::<BinaryIntegerDomain<u8>>();
+}
+
+#[test]
+fn gray_code_comparisons_match_naive() {
+    check_comparisons_match_naive::<GrayCodeIntegerDomain<u8>>();
+}
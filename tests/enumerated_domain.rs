@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::{Bdd, BddVariableSetBuilder};
+use biodivine_lib_logical_models::{
+    prelude::{
+        self as bio,
+        expression::Expression,
+        proposition::{ComparisonOperator as CmpOp, Proposition},
+        symbolic_domain::{
+            BinaryIntegerDomain, EnumeratedDomain, SymbolicDomain, SymbolicDomainEq,
+            SymbolicDomainOrd, UnaryIntegerDomain,
+        },
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::{SmartSystemUpdateFn, UpdateFnError},
+    },
+    test_utils::prop,
+};
+
+fn update_fn(
+    target: &str,
+    inputs: &[&str],
+    terms: Vec<(&str, Expression<String>)>,
+    default: &str,
+) -> (String, UnprocessedVariableUpdateFn<String>) {
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        inputs.iter().map(|it| it.to_string()).collect(),
+        target.to_string(),
+        terms
+            .into_iter()
+            .map(|(value, condition)| (value.to_string(), condition))
+            .collect(),
+        default.to_string(),
+    );
+    (target.to_string(), update_fn)
+}
+
+/// A protein that is activated by a signal and then moves from the cytoplasm into the nucleus,
+/// where it blocks the signal.
+fn localization_model() -> HashMap<String, UnprocessedVariableUpdateFn<String>> {
+    let location = update_fn(
+        "location",
+        &["location", "signal"],
+        vec![
+            (
+                "cytoplasm",
+                Expression::And(vec![
+                    prop(CmpOp::Eq, "location", "inactive".to_string()),
+                    prop(CmpOp::Eq, "signal", "on".to_string()),
+                ]),
+            ),
+            (
+                "nucleus",
+                prop(CmpOp::Eq, "location", "cytoplasm".to_string()),
+            ),
+            (
+                "nucleus",
+                Expression::And(vec![
+                    prop(CmpOp::Eq, "location", "nucleus".to_string()),
+                    prop(CmpOp::Neq, "signal", "off".to_string()),
+                ]),
+            ),
+        ],
+        "inactive",
+    );
+    let signal = update_fn(
+        "signal",
+        &["location"],
+        vec![("on", prop(CmpOp::Neq, "location", "nucleus".to_string()))],
+        "off",
+    );
+    HashMap::from([location, signal])
+}
+
+fn state(
+    system: &SmartSystemUpdateFn<EnumeratedDomain<String>, String>,
+    location: &str,
+    signal: &str,
+) -> Bdd {
+    system
+        .encode_one("location", &location.to_string())
+        .and(&system.encode_one("signal", &signal.to_string()))
+}
+
+#[test]
+fn categorical_model_without_ordering() {
+    let system = SmartSystemUpdateFn::<EnumeratedDomain<String>, String>::from_update_fns(
+        localization_model(),
+    );
+
+    let location_domain = system.get_domain("location").expect("known variable");
+    assert_eq!(
+        location_domain.get_all_possible_values(),
+        vec!["cytoplasm", "nucleus", "inactive"]
+    );
+    let signal_domain = system.get_domain("signal").expect("known variable");
+    assert_eq!(signal_domain.get_all_possible_values(), vec!["on", "off"]);
+
+    let unit = system.unit_vertex_set();
+    let bdd_variable_set = system.get_bdd_variable_set();
+    let mut locations = location_domain.decode_collection(bdd_variable_set, &unit);
+    locations.sort();
+    assert_eq!(locations, vec!["cytoplasm", "inactive", "nucleus"]);
+
+    let inactive_with_signal = state(&system, "inactive", "on");
+    assert_eq!(
+        system.successors_async("location", &inactive_with_signal),
+        state(&system, "cytoplasm", "on")
+    );
+    assert_eq!(
+        system.successors_async("signal", &state(&system, "nucleus", "on")),
+        state(&system, "nucleus", "off")
+    );
+    assert_eq!(
+        system.predecessors_async("location", state(&system, "inactive", "off")),
+        state(&system, "inactive", "off").or(&state(&system, "nucleus", "off"))
+    );
+}
+
+#[test]
+fn ordering_is_rejected_without_ordered_domain() {
+    let mut model = localization_model();
+    model.insert(
+        "signal".to_string(),
+        update_fn(
+            "signal",
+            &["location"],
+            vec![("on", prop(CmpOp::Lt, "location", "nucleus".to_string()))],
+            "off",
+        )
+        .1,
+    );
+
+    let result =
+        SmartSystemUpdateFn::<EnumeratedDomain<String>, String>::try_from_update_fns(model);
+    let Err(error) = result else {
+        panic!("`<` should not be supported by the enumerated domain");
+    };
+    assert!(matches!(
+        &error,
+        UpdateFnError::UnsupportedProposition { variable, .. } if variable == "location"
+    ));
+    assert!(error.to_string().contains("`<`"), "{}", error);
+}
+
+#[test]
+fn enumerated_domain_matches_binary_domain() {
+    let sbml_path = "data/test-models/148_AGS-cell-fate-decision.sbml";
+    let load = || {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open(sbml_path).expect("should be able to open file"),
+        ));
+        bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+        xml
+    };
+
+    let enumerated =
+        SmartSystemUpdateFn::<EnumeratedDomain<String>, String>::try_from_xml(&mut load())
+            .expect("should be able to parse");
+    let binary = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::try_from_xml(&mut load())
+        .expect("should be able to parse");
+    assert_eq!(
+        enumerated.get_system_variables(),
+        binary.get_system_variables()
+    );
+
+    let variables = binary.get_system_variables();
+    let initial_enumerated = variables
+        .iter()
+        .fold(enumerated.unit_vertex_set(), |acc, var| {
+            acc.and(&enumerated.encode_one(var, &"0".to_string()))
+        });
+    let initial_binary = variables.iter().fold(binary.unit_vertex_set(), |acc, var| {
+        acc.and(&binary.encode_one(var, &0))
+    });
+
+    // both encodings use the same number of bits, hence the cardinalities should match
+    for var in &variables {
+        let successors_enumerated = enumerated.successors_async(var, &initial_enumerated);
+        let successors_binary = binary.successors_async(var, &initial_binary);
+        assert_eq!(
+            successors_enumerated.cardinality(),
+            successors_binary.cardinality(),
+            "{}",
+            var
+        );
+
+        let next_enumerated = enumerated
+            .get_domain(var)
+            .expect("known variable")
+            .decode_collection(enumerated.get_bdd_variable_set(), &successors_enumerated);
+        let next_binary = binary
+            .get_domain(var)
+            .expect("known variable")
+            .decode_collection(binary.get_bdd_variable_set(), &successors_binary);
+        assert_eq!(next_enumerated, vec![next_binary[0].to_string()]);
+    }
+}
+
+#[test]
+fn ordered_domains_support_all_comparisons() {
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = <UnaryIntegerDomain as SymbolicDomainEq<u8>>::new_from_values(
+        &mut builder,
+        "x",
+        &[1, 4, 2],
+    );
+    let bdd_variable_set = builder.build();
+
+    assert_eq!(domain.get_all_possible_values(), vec![0, 1, 2, 3, 4]);
+    for operator in [CmpOp::Eq, CmpOp::Lt, CmpOp::Leq, CmpOp::Gt, CmpOp::Geq] {
+        let encoded = domain
+            .encode_comparison(&bdd_variable_set, operator, &2)
+            .expect("ordered domains support all comparisons");
+        let mut values = domain.decode_collection(&bdd_variable_set, &encoded);
+        values.sort();
+        let expected = (0..=4)
+            .filter(|value| Proposition::new(operator, "x".to_string(), 2).evaluate(value))
+            .collect::<Vec<_>>();
+        assert_eq!(values, expected, "{:?}", operator);
+    }
+    assert_eq!(
+        domain
+            .encode_comparison(&bdd_variable_set, CmpOp::Lt, &3)
+            .expect("ordered domains support all comparisons"),
+        domain.encode_lt(&bdd_variable_set, &3)
+    );
+}
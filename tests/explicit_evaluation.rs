@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        explicit_system::ExplicitSystem,
+        expression::Expression,
+        proposition::ComparisonOperator as CmpOp,
+        simulation::{simulate, SimulationOptions},
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{load_update_fns, prop},
+};
+
+fn state(p: u8, q: u8) -> HashMap<String, u8> {
+    HashMap::from([("p".to_string(), p), ("q".to_string(), q)])
+}
+
+#[test]
+fn expressions_evaluate_on_states() {
+    let p_high = prop(CmpOp::Geq, "p", 2);
+    let q_on = prop(CmpOp::Eq, "q", 1);
+
+    assert!(p_high.evaluate(&state(2, 0)));
+    assert!(!p_high.evaluate(&state(1, 0)));
+    assert!(Expression::<u8>::mk_true().evaluate(&state(0, 0)));
+    assert!(!Expression::<u8>::mk_false().evaluate(&state(0, 0)));
+
+    let and = Expression::And(vec![p_high.clone(), q_on.clone()]);
+    let or = Expression::Or(vec![p_high.clone(), q_on.clone()]);
+    let xor = Expression::Xor(Box::new(p_high.clone()), Box::new(q_on.clone()));
+    let implies = Expression::Implies(Box::new(p_high.clone()), Box::new(q_on.clone()));
+    let not = Expression::Not(Box::new(and.clone()));
+    for p in 0..=3 {
+        for q in 0..=1 {
+            let state = state(p, q);
+            let (a, b) = (p >= 2, q == 1);
+            assert_eq!(and.evaluate(&state), a && b);
+            assert_eq!(or.evaluate(&state), a || b);
+            assert_eq!(xor.evaluate(&state), a != b);
+            assert_eq!(implies.evaluate(&state), !a || b);
+            assert_eq!(not.evaluate(&state), !(a && b));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "no value given for variable r")]
+fn missing_variables_are_reported() {
+    prop(CmpOp::Eq, "r", 1).evaluate(&state(0, 0));
+}
+
+#[test]
+fn first_matching_term_wins() {
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["p".to_string(), "q".to_string()],
+        "p".to_string(),
+        vec![
+            (3, prop(CmpOp::Eq, "q", 1)),
+            (2, prop(CmpOp::Lt, "p", 2)),
+            (1, Expression::mk_true()),
+        ],
+        0,
+    );
+    assert_eq!(update_fn.evaluate(&state(0, 1)), 3);
+    assert_eq!(update_fn.evaluate(&state(0, 0)), 2);
+    assert_eq!(update_fn.evaluate(&state(3, 0)), 1);
+
+    let without_terms = UnprocessedVariableUpdateFn::new(vec![], "p".to_string(), Vec::new(), 2u8);
+    assert_eq!(without_terms.evaluate(&HashMap::new()), 2);
+}
+
+/// Compares the explicit successors of the states reachable in a few steps from the zero state
+/// with the symbolic successors.
+fn check_explicit_matches_symbolic<DO: SymbolicDomainOrd<u8>>(sbml_path: &str) {
+    let explicit = ExplicitSystem::new(load_update_fns(sbml_path));
+    let symbolic = SmartSystemUpdateFn::<DO, u8>::from_update_fns(load_update_fns(sbml_path));
+    let variables = symbolic.get_system_variables();
+    assert_eq!(explicit.get_system_variables(), variables.as_slice());
+
+    let zero = State::new(vec![0; variables.len()]);
+    let mut reached = symbolic.encode_state(&zero).expect("valid state");
+    for var in variables.iter().cycle().take(2 * variables.len()) {
+        reached = reached.or(&symbolic.successors_async(var, &reached));
+    }
+
+    for state in symbolic.iter_states(&reached).take(200) {
+        let encoded = symbolic.encode_state(&state).expect("valid state");
+        let explicit_successors = explicit
+            .successors_of_state(&state)
+            .expect("valid state")
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let symbolic_successors = variables
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, var)| {
+                symbolic
+                    .iter_states(&symbolic.successors_async(var, &encoded))
+                    .filter(|successor| *successor != state)
+                    .map(move |successor| (idx, successor))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<HashSet<_>>();
+        assert_eq!(explicit_successors, symbolic_successors, "{:?}", state);
+    }
+}
+
+#[test]
+fn explicit_successors_match_symbolic_unary() {
+    check_explicit_matches_symbolic::<UnaryIntegerDomain>(
+        "data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml",
+    );
+}
+
+#[test]
+fn explicit_successors_match_symbolic_binary() {
+    check_explicit_matches_symbolic::<BinaryIntegerDomain<u8>>(
+        "data/test-models/178-mast-cell-activation.sbml",
+    );
+}
+
+#[test]
+fn explicit_and_symbolic_simulations_agree() {
+    let sbml_path = "data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml";
+    let explicit = ExplicitSystem::new(load_update_fns(sbml_path));
+    let symbolic = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(
+        load_update_fns(sbml_path),
+    );
+    let initial = State::new(vec![0; explicit.get_system_variables().len()]);
+    let options = SimulationOptions {
+        walks: 3,
+        steps: 30,
+        seed: 42,
+        record_trajectories: true,
+        ..SimulationOptions::default()
+    };
+
+    let explicit_result = simulate(&explicit, &initial, &options).expect("valid options");
+    let symbolic_result = simulate(&symbolic, &initial, &options).expect("valid options");
+    assert_eq!(explicit_result.trajectories, symbolic_result.trajectories);
+    assert_eq!(explicit_result.visits, symbolic_result.visits);
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::{
+    prelude::{
+        expression::Expression,
+        proposition::{ComparisonOperator as CmpOp, Proposition},
+        symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+        update_fn::{variable_update_fn::bdd_from_expression, SmartSystemUpdateFn},
+    },
+    test_utils::load_update_fns,
+};
+
+const MODELS: [&str; 5] = [
+    "data/manual/handbook_example.sbml",
+    "data/manual/basic_transition.sbml",
+    "data/test-models/175_SEA-URCHIN.sbml",
+    "data/test-models/178-mast-cell-activation.sbml",
+    "data/test-models/183-alterations-in-bladder.sbml",
+];
+
+fn proposition_count(expression: &Expression<u8>) -> usize {
+    match expression {
+        Expression::Terminal(_) => 1,
+        Expression::Not(inner) => proposition_count(inner),
+        Expression::And(clauses) | Expression::Or(clauses) => {
+            clauses.iter().map(proposition_count).sum()
+        }
+        Expression::Xor(lhs, rhs) | Expression::Implies(lhs, rhs) => {
+            proposition_count(lhs) + proposition_count(rhs)
+        }
+    }
+}
+
+/// Checks that all rewritten forms of every function term in the model are equivalent
+/// to the original expression within the admissible states of the model.
+fn check_rewrites_are_equivalent<DO>(sbml_path: &str)
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let update_fns = load_update_fns(sbml_path);
+    let system = SmartSystemUpdateFn::<DO, u8>::from_update_fns(update_fns.clone());
+    let domains = system.variable_domains();
+    let named_domains = system
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let unit = system.unit_vertex_set();
+    let encode = |expression: &Expression<u8>| -> Bdd {
+        bdd_from_expression(expression, &named_domains, system.get_bdd_variable_set()).and(&unit)
+    };
+
+    for update_fn in update_fns.values() {
+        for (_, condition) in &update_fn.terms {
+            let expected = encode(condition);
+            let simplified = condition.simplify(&domains);
+            assert_eq!(expected, encode(&simplified), "{:?}", condition);
+            assert!(proposition_count(&simplified) <= proposition_count(condition));
+
+            if proposition_count(condition) <= 12 {
+                assert_eq!(expected, encode(&condition.to_dnf(&domains)));
+                assert_eq!(expected, encode(&condition.to_cnf(&domains)));
+            }
+        }
+    }
+}
+
+#[test]
+fn rewrites_are_equivalent_unary() {
+    MODELS
+        .iter()
+        .for_each(|path| check_rewrites_are_equivalent::<UnaryIntegerDomain>(path));
+}
+
+#[test]
+fn rewrites_are_equivalent_binary() {
+    MODELS
+        .iter()
+        .for_each(|path| check_rewrites_are_equivalent::<BinaryIntegerDomain<u8>>(path));
+}
+
+#[test]
+fn redundant_structure_is_removed() {
+    let prop = |variable: &str, operator, value| {
+        Expression::Terminal(Proposition::new(operator, variable.to_string(), value))
+    };
+    let domains = HashMap::from([
+        ("x".to_string(), vec![0u8, 1, 2, 3]),
+        ("y".to_string(), vec![0u8, 1]),
+    ]);
+
+    // `x >= 0` is a tautology and `!!(y == 1)` is just `y == 1`
+    let expression = Expression::And(vec![
+        prop("x", CmpOp::Geq, 0),
+        Expression::Not(Box::new(Expression::Not(Box::new(prop("y", CmpOp::Eq, 1))))),
+    ]);
+    assert_eq!(expression.simplify(&domains), prop("y", CmpOp::Eq, 1));
+
+    // nested conjunctions on the same variable are merged into a single interval
+    let expression = Expression::And(vec![
+        prop("x", CmpOp::Gt, 0),
+        Expression::And(vec![prop("x", CmpOp::Lt, 3), prop("y", CmpOp::Neq, 0)]),
+    ]);
+    assert_eq!(
+        expression.simplify(&domains),
+        Expression::And(vec![
+            Expression::And(vec![prop("x", CmpOp::Geq, 1), prop("x", CmpOp::Leq, 2)]),
+            prop("y", CmpOp::Eq, 1),
+        ])
+        .simplify(&domains),
+    );
+
+    // contradictions are detected
+    let expression = Expression::And(vec![prop("x", CmpOp::Lt, 1), prop("x", CmpOp::Gt, 2)]);
+    assert_eq!(expression.simplify(&domains), Expression::mk_false());
+
+    // `x == 1 | x != 1` is a tautology
+    let expression = Expression::Or(vec![prop("x", CmpOp::Eq, 1), prop("x", CmpOp::Neq, 1)]);
+    assert_eq!(expression.simplify(&domains), Expression::mk_true());
+    assert_eq!(expression.to_cnf(&domains), Expression::mk_true());
+
+    // `(x <= 1 | y == 1) & x >= 1` in DNF
+    let expression = Expression::And(vec![
+        Expression::Or(vec![prop("x", CmpOp::Leq, 1), prop("y", CmpOp::Eq, 1)]),
+        prop("x", CmpOp::Geq, 1),
+    ]);
+    assert_eq!(
+        expression.to_dnf(&domains),
+        Expression::Or(vec![
+            prop("x", CmpOp::Eq, 1),
+            Expression::And(vec![prop("y", CmpOp::Eq, 1), prop("x", CmpOp::Geq, 1)]),
+        ])
+    );
+}
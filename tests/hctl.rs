@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        ctl::CtlError,
+        explicit_system::ExplicitSystem,
+        expression::Expression,
+        hctl::{HctlChecker, HctlError, HctlFormula as Hctl},
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        proposition::ComparisonOperator as CmpOp,
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, UnaryIntegerDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{prop, random_model, RandomModelOptions},
+};
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+fn both(lhs: Expression<u8>, rhs: Expression<u8>) -> Expression<u8> {
+    Expression::And(vec![lhs, rhs])
+}
+
+/// `p` oscillates while `q = 0` and keeps its value while `q = 1`, which never changes.
+/// That is, `{p = 0, q = 0} <-> {p = 1, q = 0}` is a cyclic attractor, while both states
+/// with `q = 1` are fixed points.
+fn oscillation() -> System {
+    System::from_update_fns(HashMap::from([
+        (
+            "p".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![],
+                "p".to_string(),
+                vec![
+                    (1, both(prop(CmpOp::Eq, "q", 0), prop(CmpOp::Eq, "p", 0))),
+                    (1, both(prop(CmpOp::Eq, "q", 1), prop(CmpOp::Eq, "p", 1))),
+                ],
+                0,
+            ),
+        ),
+        (
+            "q".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![],
+                "q".to_string(),
+                vec![(1, prop(CmpOp::Eq, "q", 1))],
+                0,
+            ),
+        ),
+    ]))
+}
+
+fn states(system: &System, states: &[[u8; 2]]) -> HashSet<State<u8>> {
+    let result = states
+        .iter()
+        .map(|values| State::new(values.to_vec()))
+        .collect::<HashSet<_>>();
+    assert!(result
+        .iter()
+        .all(|state| system.encode_state(state).is_ok()));
+    result
+}
+
+#[test]
+fn queries_are_parsed() {
+    let parse = |query: &str| query.parse::<Hctl<u8>>().expect("valid query");
+
+    let attractor_with_p = Hctl::exists(
+        "x",
+        Hctl::jump(
+            "x",
+            Hctl::and(
+                Hctl::ag(Hctl::ef(Hctl::state_variable("x"))),
+                Hctl::atom("p", CmpOp::Geq, 1),
+            ),
+        ),
+    );
+    assert_eq!(parse("∃x. @x. (AG EF x & p >= 1)"), attractor_with_p);
+    assert_eq!(parse("exists x: at x: AG EF x & p >= 1"), attractor_with_p);
+    assert_eq!(parse(&attractor_with_p.to_string()), attractor_with_p);
+    assert_eq!(parse("↓x. AG EF x"), Hctl::attractor());
+
+    // `&` binds tighter than `|`, which binds tighter than `=>` (right associative)
+    let (p, q, r) = (
+        Hctl::atom("p", CmpOp::Eq, 0),
+        Hctl::atom("q", CmpOp::Neq, 1),
+        Hctl::atom("r", CmpOp::Lt, 2),
+    );
+    assert_eq!(
+        parse("p = 0 | q != 1 & !r < 2 => EX p == 0 => q != 1"),
+        Hctl::implies(
+            Hctl::or(p.clone(), Hctl::and(q.clone(), Hctl::negate(r.clone()))),
+            Hctl::implies(Hctl::ex(p.clone()), q.clone()),
+        )
+    );
+    let until = Hctl::eu(p.clone(), Hctl::au(Hctl::True, r.clone()));
+    assert_eq!(parse("E[p = 0 U A[true U r < 2]]"), until);
+    assert_eq!(parse(&until.to_string()), until);
+    // keywords can still be used as variable names within propositions
+    assert_eq!(parse("EF = 1"), Hctl::atom("EF", CmpOp::Eq, 1));
+}
+
+#[test]
+fn invalid_queries_are_rejected() {
+    let position = |query: &str| match query.parse::<Hctl<u8>>() {
+        Err(HctlError::Parse { position, .. }) => position,
+        other => panic!("{} parsed as {:?}", query, other),
+    };
+    assert_eq!(position("EF (p >= )"), 9);
+    assert_eq!(position("p >= 256"), 5);
+    assert_eq!(position("↓x AG EF x"), 5);
+    assert_eq!(position("E[p = 0 | q = 1]"), 15);
+    assert_eq!(position("p = 1 q = 1"), 6);
+    assert_eq!(position("p = 1 # q"), 6);
+    assert_eq!(position("(p = 1"), 6);
+}
+
+#[test]
+fn attractor_queries() {
+    let system = oscillation();
+    let checker = HctlChecker::new(&system);
+    let query = |query: &str| {
+        let result = checker.check_query(query).expect("valid query");
+        system.iter_states(&result).collect::<HashSet<_>>()
+    };
+    let everywhere = states(&system, &[[0, 0], [0, 1], [1, 0], [1, 1]]);
+
+    assert_eq!(
+        system
+            .iter_states(&checker.attractor_states())
+            .collect::<HashSet<_>>(),
+        everywhere
+    );
+    assert_eq!(query("↓x. AX x"), states(&system, &[[0, 1], [1, 1]]));
+    // there is an attractor in which `p >= 1`, so the query holds in every state
+    assert_eq!(query("∃x. @x. (AG EF x & p >= 1)"), everywhere);
+    // there is a cyclic attractor, but it is only reachable from the states with `q = 0`
+    assert_eq!(query("∃x. @x. (AG EF x & !AX x)"), everywhere);
+    // every attractor reachable from the state is a fixed point
+    assert_eq!(
+        query("AG (↓x. (AG EF x => AX x))"),
+        states(&system, &[[0, 1], [1, 1]])
+    );
+    // no state can reach all the others
+    assert_eq!(query("∃x. ∀y. @x. EF y"), HashSet::new());
+    // all states are in attractors, so every reachable state can reach back
+    assert_eq!(query("↓x. ∀y. (@y. EF x) => EF y"), everywhere);
+    assert_eq!(query("↓x. ∃y. !(@y. EF x) & EF y"), HashSet::new());
+}
+
+#[test]
+fn attractors_match_explicit_semantics() {
+    for seed in 0..8 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let explicit = ExplicitSystem::new(model.update_fns.clone());
+        let system = System::from_update_fns(model.update_fns.clone());
+        let checker = HctlChecker::new(&system);
+        let all = system
+            .iter_states(&system.unit_vertex_set())
+            .collect::<Vec<_>>();
+
+        let reachable_from = |state: &State<u8>| {
+            let mut reachable = HashSet::from([state.clone()]);
+            let mut stack = vec![state.clone()];
+            while let Some(state) = stack.pop() {
+                for (_, successor) in explicit.successors_of_state(&state).expect("valid state") {
+                    if reachable.insert(successor.clone()) {
+                        stack.push(successor);
+                    }
+                }
+            }
+            reachable
+        };
+        let reachable = all
+            .iter()
+            .map(|state| (state.clone(), reachable_from(state)))
+            .collect::<HashMap<_, _>>();
+        let expected = all
+            .iter()
+            .filter(|state| {
+                reachable[*state]
+                    .iter()
+                    .all(|it| reachable[it].contains(state))
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        let attractors = checker.attractor_states();
+        assert_eq!(
+            system.iter_states(&attractors).collect::<HashSet<_>>(),
+            expected,
+            "{}",
+            seed
+        );
+        // the symbolic decomposition agrees with the binder
+        assert_eq!(
+            attractors,
+            checker.check(&Hctl::attractor()).expect("valid formula"),
+            "{}",
+            seed
+        );
+    }
+}
+
+#[test]
+fn colored_attractor_queries() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    let checker = HctlChecker::new(&system);
+    let c_on = system.encode_parameter("c", &[], &1);
+
+    // the only attractor is the fixed point `p = c`
+    assert_eq!(
+        checker.attractor_states(),
+        c_on.iff(&system.encode_one("p", &1))
+            .and(&system.unit_vertex_set())
+    );
+    let result = checker
+        .check_query("∃x. @x. (AG EF x & p = 1)")
+        .expect("valid query");
+    assert_eq!(result, c_on.and(&system.unit_vertex_set()));
+}
+
+#[test]
+fn invalid_formulas_are_rejected() {
+    let system = oscillation();
+    let checker = HctlChecker::new(&system);
+    assert!(matches!(
+        checker.check_query("↓x. EF y"),
+        Err(HctlError::UnboundStateVariable(name)) if name == "y"
+    ));
+    assert!(matches!(
+        checker.check_query("@x. p = 1"),
+        Err(HctlError::UnboundStateVariable(name)) if name == "x"
+    ));
+    assert!(matches!(
+        checker.check_query("∃x. @x. r = 1"),
+        Err(HctlError::Ctl(CtlError::UnknownVariable(name))) if name == "r"
+    ));
+}
@@ -0,0 +1,264 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_bdd::Bdd;
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    ctl::CtlChecker,
+    expression::Expression,
+    hctl::HctlChecker,
+    parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+    proposition::{
+        parse_partial_assignment, ComparisonOperator as CmpOp, Proposition, PropositionParseError,
+    },
+    state::{State, StateError},
+    symbolic_domain::{BinaryIntegerDomain, EnumeratedDomain, UnaryIntegerDomain},
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::SmartSystemUpdateFn,
+};
+use biodivine_lib_logical_models::test_utils::{random_model, RandomModelOptions};
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+/// `p` counts down from two to zero, `q` is on while `p >= 1` and `r` keeps its value.
+/// Initially, `p = 2` and `q = 0`, while `r` is not specified.
+fn load() -> (HashMap<String, u8>, System) {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open("data/manual/initial_levels.sbml")
+            .expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfQualitativeSpecies").expect("should be able to find");
+    let levels = bio::load_initial_levels(&mut xml).expect("should be able to parse");
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let system = System::try_from_xml(&mut xml).expect("should be able to parse");
+    (levels, system)
+}
+
+fn states(system: &System, set: &Bdd) -> HashSet<State<u8>> {
+    system.iter_states(set).collect()
+}
+
+fn constraint(variable: &str, operator: CmpOp, value: u8) -> Proposition<u8> {
+    Proposition::new(operator, variable.to_string(), value)
+}
+
+#[test]
+fn initial_levels_are_loaded() {
+    let (levels, system) = load();
+    assert_eq!(
+        levels,
+        HashMap::from([("p".to_string(), 2), ("q".to_string(), 0)])
+    );
+
+    // `r` has no initial level, so it is free
+    let initial = system
+        .initial_states_from_levels(&levels)
+        .expect("valid levels");
+    assert_eq!(
+        states(&system, &initial),
+        HashSet::from([State::new(vec![2, 0, 0]), State::new(vec![2, 0, 1])])
+    );
+}
+
+#[test]
+fn partial_specifications() {
+    let (_, system) = load();
+    let initial = system
+        .initial_states(&[
+            constraint("p", CmpOp::Geq, 1),
+            constraint("r", CmpOp::Eq, 1),
+        ])
+        .expect("valid constraints");
+    assert_eq!(system.count_states(&initial), 4.0);
+    assert!(system
+        .iter_states(&initial)
+        .all(|state| state.values()[0] >= 1 && state.values()[2] == 1));
+
+    // without constraints, every state is initial
+    let all = system.initial_states(&[]).expect("no constraints");
+    assert_eq!(all, system.unit_vertex_set());
+    // constraints on the same variable are combined
+    let initial = system
+        .initial_states(&[
+            constraint("p", CmpOp::Leq, 1),
+            constraint("p", CmpOp::Neq, 0),
+        ])
+        .expect("valid constraints");
+    assert_eq!(system.count_states(&initial), 4.0);
+}
+
+#[test]
+fn invalid_specifications_are_rejected() {
+    let (_, system) = load();
+    assert!(matches!(
+        system.initial_states(&[constraint("s", CmpOp::Eq, 1)]),
+        Err(StateError::UnknownVariable(name)) if name == "s"
+    ));
+    assert!(matches!(
+        system.initial_states_from_levels(&HashMap::from([("p".to_string(), 3)])),
+        Err(StateError::InvalidValue { variable, value }) if variable == "p" && value == "3"
+    ));
+
+    let categorical =
+        SmartSystemUpdateFn::<EnumeratedDomain<String>, String>::from_update_fns(HashMap::from([
+            (
+                "location".to_string(),
+                UnprocessedVariableUpdateFn::new(
+                    vec![],
+                    "location".to_string(),
+                    vec![(
+                        "nucleus".to_string(),
+                        Expression::Terminal(Proposition::new(
+                            CmpOp::Eq,
+                            "location".to_string(),
+                            "nucleus".to_string(),
+                        )),
+                    )],
+                    "cytoplasm".to_string(),
+                ),
+            ),
+        ]));
+    let before_nucleus = Proposition::new(CmpOp::Lt, "location".to_string(), "nucleus".to_string());
+    assert!(matches!(
+        categorical.initial_states(&[before_nucleus]),
+        Err(StateError::UnsupportedComparison { variable, .. }) if variable == "location"
+    ));
+}
+
+#[test]
+fn reachability_from_initial_states() {
+    let (levels, system) = load();
+    let initial = system
+        .initial_states_from_levels(&levels)
+        .expect("valid levels");
+
+    let reachable = CtlChecker::new(&system).reachable_from(&initial);
+    // `q` may lag behind `p`, so every combination of `p` and `q` is reachable
+    assert_eq!(system.count_states(&reachable), 12.0);
+    let attractors = HctlChecker::new(&system).reachable_attractor_states(&initial);
+    assert_eq!(
+        states(&system, &attractors),
+        HashSet::from([State::new(vec![0, 0, 0]), State::new(vec![0, 0, 1])])
+    );
+
+    let initial = initial.and(
+        &system
+            .initial_states(&[constraint("r", CmpOp::Eq, 1)])
+            .expect("valid constraints"),
+    );
+    let attractors = HctlChecker::new(&system).reachable_attractor_states(&initial);
+    assert_eq!(
+        states(&system, &attractors),
+        HashSet::from([State::new(vec![0, 0, 1])])
+    );
+}
+
+#[test]
+fn reachable_attractors_on_random_models() {
+    for seed in 0..10 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let system = System::from_update_fns(model.update_fns.clone());
+        let checker = HctlChecker::new(&system);
+        let attractors = checker.attractor_states();
+        for value in 0..=model.max_values["v0"] {
+            let initial = system
+                .initial_states(&[constraint("v0", CmpOp::Eq, value)])
+                .expect("valid constraints");
+            let reachable = CtlChecker::new(&system).reachable_from(&initial);
+            assert_eq!(
+                checker.reachable_attractor_states(&initial),
+                attractors.and(&reachable),
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn initial_states_contain_all_colors() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    let initial = system
+        .initial_states(&[Proposition::new(CmpOp::Eq, "p".to_string(), 0)])
+        .expect("valid constraints");
+    assert_eq!(system.colors(&initial), system.unit_colors());
+
+    // the fixed point `p = 0` is only reachable for `c = 0`
+    let attractors = HctlChecker::new(&system).reachable_attractor_states(&initial);
+    let c_on = system.encode_parameter("c", &[], &1);
+    assert_eq!(
+        attractors,
+        system
+            .encode_one("p", &0)
+            .and(&c_on.not())
+            .or(&system.encode_one("p", &1).and(&c_on))
+            .and(&system.unit_vertex_set())
+    );
+}
+
+#[test]
+fn partial_specifications_are_parsed() {
+    assert_eq!(
+        parse_partial_assignment::<u8>("{p: 1, q: >=2, r:!= 0}").expect("valid"),
+        vec![
+            constraint("p", CmpOp::Eq, 1),
+            constraint("q", CmpOp::Geq, 2),
+            constraint("r", CmpOp::Neq, 0),
+        ]
+    );
+    assert_eq!(
+        parse_partial_assignment::<u8>(" { } ").expect("valid"),
+        vec![]
+    );
+    assert_eq!(
+        "q>=2".parse::<Proposition<u8>>().expect("valid"),
+        constraint("q", CmpOp::Geq, 2)
+    );
+    assert_eq!(
+        "p < 1".parse::<Proposition<u8>>().expect("valid"),
+        constraint("p", CmpOp::Lt, 1)
+    );
+
+    assert!(matches!(
+        parse_partial_assignment::<u8>("p: 1"),
+        Err(PropositionParseError::InvalidAssignment(_))
+    ));
+    assert!(matches!(
+        parse_partial_assignment::<u8>("{p 1}"),
+        Err(PropositionParseError::InvalidAssignment(_))
+    ));
+    assert!(matches!(
+        parse_partial_assignment::<u8>("{p: high}"),
+        Err(PropositionParseError::InvalidValue(value)) if value == "high"
+    ));
+    assert!(matches!(
+        parse_partial_assignment::<u8>("{p q: 1}"),
+        Err(PropositionParseError::InvalidVariable(name)) if name == "p q"
+    ));
+    assert!(matches!(
+        "p 1".parse::<Proposition<u8>>(),
+        Err(PropositionParseError::InvalidProposition(_))
+    ));
+
+    // the parsed specification restricts the initial states
+    let (_, system) = load();
+    let initial = system
+        .initial_states(&parse_partial_assignment("{p: 2, q: <1}").expect("valid"))
+        .expect("valid constraints");
+    assert_eq!(
+        initial,
+        system
+            .initial_states(&[constraint("p", CmpOp::Eq, 2), constraint("q", CmpOp::Lt, 1)])
+            .expect("valid constraints")
+    );
+}
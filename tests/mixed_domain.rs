@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        expression::Expression,
+        proposition::{ComparisonOperator as CmpOp, Proposition},
+        symbolic_domain::{IntegerEncoding, MixedIntegerDomain, SymbolicDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::ComputationStep,
+};
+
+/// A Boolean switch which enables a counter that cycles through `0..=max`.
+fn switch_and_counter(max: u8) -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    let switch = UnprocessedVariableUpdateFn::new(
+        vec!["counter".to_string()],
+        "switch".to_string(),
+        vec![(
+            1,
+            Expression::Terminal(Proposition::new(CmpOp::Lt, "counter".to_string(), max)),
+        )],
+        0,
+    );
+    let counter = UnprocessedVariableUpdateFn::new(
+        vec!["switch".to_string(), "counter".to_string()],
+        "counter".to_string(),
+        (0..max)
+            .map(|value| {
+                let condition = Expression::And(vec![
+                    Expression::Terminal(Proposition::new(CmpOp::Eq, "switch".to_string(), 1)),
+                    Expression::Terminal(Proposition::new(CmpOp::Eq, "counter".to_string(), value)),
+                ]);
+                (value + 1, condition)
+            })
+            .collect(),
+        0,
+    );
+    HashMap::from([
+        ("switch".to_string(), switch),
+        ("counter".to_string(), counter),
+    ])
+}
+
+fn encoding_of(system: &SmartSystemUpdateFn<MixedIntegerDomain, u8>, var: &str) -> IntegerEncoding {
+    system.get_domain(var).expect("known variable").encoding()
+}
+
+#[test]
+fn default_encoding_depends_on_domain_size() {
+    let system =
+        SmartSystemUpdateFn::<MixedIntegerDomain, u8>::from_update_fns(switch_and_counter(12));
+    assert_eq!(encoding_of(&system, "switch"), IntegerEncoding::Boolean);
+    assert_eq!(encoding_of(&system, "counter"), IntegerEncoding::Binary);
+    // four bits for the counter, one for the switch
+    assert_eq!(system.standard_variables().len(), 5);
+}
+
+#[test]
+fn encoding_is_chosen_per_variable() {
+    for encoding in [
+        IntegerEncoding::Unary,
+        IntegerEncoding::Binary,
+        IntegerEncoding::GrayCode,
+        IntegerEncoding::PetriNet,
+    ] {
+        let system = SmartSystemUpdateFn::from_update_fns_with_encoding(
+            switch_and_counter(12),
+            |var_name, max_value| match var_name {
+                "counter" => encoding,
+                _ => IntegerEncoding::default_for(max_value),
+            },
+        );
+        assert_eq!(encoding_of(&system, "counter"), encoding);
+        assert_eq!(encoding_of(&system, "switch"), IntegerEncoding::Boolean);
+
+        let domain = system.get_domain("counter").expect("known variable");
+        let variables = system.get_bdd_variable_set();
+
+        let mut reached = system
+            .encode_one("switch", &1)
+            .and(&system.encode_one("counter", &0));
+        for var in ["counter", "switch"].iter().cycle().take(40) {
+            reached = reached.or(&system.successors_async(var, &reached));
+        }
+        let mut counter_values = domain.decode_collection(variables, &reached);
+        counter_values.sort();
+        counter_values.dedup();
+        assert_eq!(
+            counter_values,
+            (0..=12).collect::<Vec<_>>(),
+            "{:?}",
+            encoding
+        );
+
+        let stopped = reached.and(&system.encode_one("counter", &12));
+        let mut switch_values = system
+            .get_domain("switch")
+            .expect("known variable")
+            .decode_collection(variables, &system.successors_async("switch", &stopped));
+        switch_values.sort();
+        switch_values.dedup();
+        assert_eq!(switch_values, vec![0], "{:?}", encoding);
+    }
+}
+
+#[test]
+fn mixed_encoding_matches_single_encodings() {
+    let mut cmp = ComputationStep::new("data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml");
+    cmp.initialize();
+    for _ in 0..10 {
+        if cmp.can_initialize() {
+            break;
+        }
+        cmp.perform_fwd_step();
+        cmp.check_consistency();
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        expression::Expression,
+        proposition::ComparisonOperator as CmpOp,
+        reduction::{reduce, ReductionOptions},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    },
+    test_utils::prop,
+};
+
+fn update_fn(
+    target: &str,
+    inputs: &[&str],
+    terms: Vec<(u8, Expression<u8>)>,
+    default: u8,
+) -> (String, UnprocessedVariableUpdateFn<u8>) {
+    (
+        target.to_string(),
+        UnprocessedVariableUpdateFn::new(
+            inputs.iter().map(|it| it.to_string()).collect(),
+            target.to_string(),
+            terms,
+            default,
+        ),
+    )
+}
+
+/// `input` is constant, `a` is fully determined by `input`, `b` and `c` form a feedback loop
+/// and `out` only reads `b`.
+fn example_model() -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    HashMap::from([
+        update_fn("input", &[], vec![], 1),
+        update_fn("a", &["input"], vec![(1, prop(CmpOp::Geq, "input", 1))], 0),
+        update_fn(
+            "b",
+            &["a", "c"],
+            vec![
+                (
+                    2,
+                    Expression::And(vec![prop(CmpOp::Eq, "a", 1), prop(CmpOp::Eq, "c", 0)]),
+                ),
+                (1, prop(CmpOp::Geq, "c", 1)),
+            ],
+            0,
+        ),
+        update_fn("c", &["b"], vec![(1, prop(CmpOp::Geq, "b", 1))], 0),
+        update_fn("out", &["b"], vec![(1, prop(CmpOp::Eq, "b", 2))], 0),
+    ])
+}
+
+#[test]
+fn constants_and_outputs_are_removed() {
+    let (reduced, report) = reduce(example_model(), ReductionOptions::default());
+
+    assert_eq!(
+        report.constants,
+        vec![("input".to_string(), 1), ("a".to_string(), 1)]
+    );
+    assert_eq!(report.unused_outputs, vec!["out".to_string()]);
+    assert!(report.eliminated_intermediates.is_empty());
+
+    let mut remaining = reduced.keys().cloned().collect::<Vec<_>>();
+    remaining.sort();
+    assert_eq!(remaining, vec!["b".to_string(), "c".to_string()]);
+
+    let b = &reduced["b"];
+    assert_eq!(b.input_vars_names, vec!["c".to_string()]);
+    assert_eq!(
+        b.terms,
+        vec![(2, prop(CmpOp::Eq, "c", 0)), (1, prop(CmpOp::Geq, "c", 1))]
+    );
+}
+
+#[test]
+fn intermediate_variables_are_eliminated() {
+    let options = ReductionOptions {
+        eliminate_intermediates: true,
+        ..Default::default()
+    };
+    let (reduced, report) = reduce(example_model(), options);
+
+    assert_eq!(report.eliminated_intermediates, vec!["b".to_string()]);
+    assert_eq!(reduced.keys().collect::<Vec<_>>(), vec!["c"]);
+
+    // `c` now reads its own value through the eliminated `b`; since `b >= 1` holds in both
+    // of the terms of `b`, `c` is updated to `1` unconditionally.
+    let c = &reduced["c"];
+    let satisfied = |value: u8| {
+        c.terms
+            .iter()
+            .find(|(_, condition)| {
+                evaluate(condition, value).expect("only propositions on `c` remain")
+            })
+            .map(|(output, _)| *output)
+            .unwrap_or(c.default)
+    };
+    assert_eq!(satisfied(0), 1);
+    assert_eq!(satisfied(1), 1);
+}
+
+fn evaluate(expression: &Expression<u8>, c_value: u8) -> Option<bool> {
+    Some(match expression {
+        Expression::Terminal(proposition) if proposition.variable == "c" => {
+            proposition.evaluate(&c_value)
+        }
+        Expression::Terminal(_) => return None,
+        Expression::Not(inner) => !evaluate(inner, c_value)?,
+        Expression::And(clauses) => clauses
+            .iter()
+            .map(|it| evaluate(it, c_value))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .all(|it| it),
+        Expression::Or(clauses) => clauses
+            .iter()
+            .map(|it| evaluate(it, c_value))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .any(|it| it),
+        Expression::Xor(lhs, rhs) => evaluate(lhs, c_value)? ^ evaluate(rhs, c_value)?,
+        Expression::Implies(lhs, rhs) => !evaluate(lhs, c_value)? || evaluate(rhs, c_value)?,
+    })
+}
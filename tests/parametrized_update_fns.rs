@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::{
+    prelude::{
+        explicit_system::ExplicitSystem,
+        expression::Expression,
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        proposition::ComparisonOperator as CmpOp,
+        simulation::{simulate, SimulationError, SimulationOptions},
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, UnaryIntegerDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::{SmartSystemUpdateFn, UpdateFnError},
+    },
+    test_utils::prop,
+};
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+fn unknown(name: &str, arguments: &[&str]) -> TermOutput<u8> {
+    TermOutput::Unknown(UninterpretedFn::new(
+        name.to_string(),
+        arguments.iter().map(|it| it.to_string()).collect(),
+    ))
+}
+
+fn parametrized(
+    target: &str,
+    terms: Vec<(TermOutput<u8>, Expression<u8>)>,
+    default: TermOutput<u8>,
+    values: Vec<u8>,
+) -> (String, ParametrizedVariableUpdateFn<u8>) {
+    let update_fn =
+        ParametrizedVariableUpdateFn::new(vec![], target.to_string(), terms, default, values);
+    (target.to_string(), update_fn)
+}
+
+/// `x := 2 if y == 2, else f(y)` and `y := g if x >= 1, else 0`, with `x, y` in `0..=2`.
+fn two_unknown_functions() -> HashMap<String, ParametrizedVariableUpdateFn<u8>> {
+    HashMap::from([
+        parametrized(
+            "x",
+            vec![(TermOutput::Value(2), prop(CmpOp::Eq, "y", 2))],
+            unknown("f", &["y"]),
+            vec![],
+        ),
+        parametrized(
+            "y",
+            vec![(unknown("g", &[]), prop(CmpOp::Geq, "x", 1))],
+            TermOutput::Value(0),
+            vec![1, 2],
+        ),
+    ])
+}
+
+/// The concrete model for the interpretation `f(0) = f0, f(1) = f1, f(2) = f2` and `g`.
+fn instantiate(f: [u8; 3], g: u8) -> ExplicitSystem<u8> {
+    let concrete = |target: &str, terms, default| {
+        let update_fn =
+            UnprocessedVariableUpdateFn::new(vec![], target.to_string(), terms, default);
+        (target.to_string(), update_fn)
+    };
+    ExplicitSystem::new(HashMap::from([
+        concrete(
+            "x",
+            vec![
+                (2, prop(CmpOp::Eq, "y", 2)),
+                (f[0], prop(CmpOp::Eq, "y", 0)),
+                (f[1], prop(CmpOp::Eq, "y", 1)),
+            ],
+            f[2],
+        ),
+        concrete("y", vec![(g, prop(CmpOp::Geq, "x", 1))], 0),
+    ]))
+}
+
+#[test]
+fn parameters_form_function_tables() {
+    let system = System::from_parametrized_update_fns(two_unknown_functions());
+    assert_eq!(system.get_system_variables(), ["x", "y"]);
+
+    let mut parameters = system.parameters();
+    parameters.sort();
+    assert_eq!(
+        parameters,
+        [("f", [0].as_slice()), ("f", &[1]), ("f", &[2]), ("g", &[]),]
+    );
+    // every parameter has three valid values, although the binary encoding allows four
+    assert_eq!(system.count_colors(&system.unit_colors()), 81.0);
+    assert_eq!(system.count_colors(&system.unit_vertex_set()), 81.0);
+    assert_eq!(system.count_states(&system.unit_vertex_set()), 9.0);
+    assert_eq!(system.parameter_variables().len(), 8);
+}
+
+#[test]
+fn every_color_behaves_as_its_concrete_model() {
+    let system = System::from_parametrized_update_fns(two_unknown_functions());
+    let states = (0..=2)
+        .flat_map(|x| (0..=2).map(move |y| State::new(vec![x, y])))
+        .collect::<Vec<_>>();
+    let encode = |state: &State<u8>| system.encode_state(state).expect("valid state");
+
+    for f in (0..27u8).map(|idx| [idx % 3, idx / 3 % 3, idx / 9]) {
+        for g in 0..=2 {
+            let color = (0..3)
+                .map(|arg| system.encode_parameter("f", &[arg], &f[arg as usize]))
+                .fold(system.encode_parameter("g", &[], &g), |acc, it| {
+                    acc.and(&it)
+                });
+            let concrete = instantiate(f, g);
+
+            for (idx, variable) in ["x", "y"].into_iter().enumerate() {
+                let mut expected_predecessors = HashMap::<State<u8>, Bdd>::new();
+                for state in &states {
+                    let mut values = state.values().to_vec();
+                    values[idx] = concrete.next_value(state, idx);
+                    let target = State::new(values);
+
+                    let successors = system.successors_async(variable, &encode(state).and(&color));
+                    assert_eq!(successors, encode(&target).and(&color), "{:?} {:?}", f, g);
+
+                    let predecessors = expected_predecessors
+                        .entry(target)
+                        .or_insert_with(|| system.get_bdd_variable_set().mk_false());
+                    *predecessors = predecessors.or(&encode(state));
+                }
+
+                for state in &states {
+                    let expected = expected_predecessors
+                        .remove(state)
+                        .unwrap_or_else(|| system.get_bdd_variable_set().mk_false());
+                    let predecessors =
+                        system.predecessors_async(variable, encode(state).and(&color));
+                    assert_eq!(predecessors, expected.and(&color), "{:?} {:?}", f, g);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn colored_successors_split_by_color() {
+    // `p := c` and `q := c`, i.e. both variables share the same unknown constant
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([
+            parametrized("p", vec![], unknown("c", &[]), vec![0, 1]),
+            parametrized("q", vec![], unknown("c", &[]), vec![0, 1]),
+        ]),
+    );
+    assert_eq!(system.count_colors(&system.unit_colors()), 2.0);
+
+    let zero = system
+        .encode_state(&State::new(vec![0, 0]))
+        .expect("valid state");
+    let c_on = system.encode_parameter("c", &[], &1);
+    let successors = system.successors_async("p", &zero);
+    assert_eq!(system.colors(&successors), system.unit_colors());
+    assert_eq!(
+        successors.and(&c_on),
+        system
+            .encode_state(&State::new(vec![1, 0]))
+            .expect("valid state")
+            .and(&c_on)
+    );
+    assert_eq!(successors.and(&c_on.not()), zero.and(&c_on.not()));
+
+    // the shared constant has the same value in both update functions
+    let both = system.successors_async("q", &successors);
+    assert_eq!(system.count_states(&both), 2.0);
+    assert_eq!(system.colors(&both.and(&zero)), c_on.not());
+}
+
+#[test]
+fn systems_without_parameters_have_a_single_color() {
+    let update_fns = HashMap::from([(
+        "p".to_string(),
+        UnprocessedVariableUpdateFn::new(vec![], "p".to_string(), vec![], 1u8),
+    )]);
+    let system = System::from_update_fns(update_fns.clone());
+    assert!(system.parameters().is_empty());
+    assert!(system.unit_colors().is_true());
+    assert_eq!(system.count_colors(&system.unit_vertex_set()), 1.0);
+
+    let parametrized = System::from_parametrized_update_fns(
+        update_fns
+            .into_iter()
+            .map(|(name, update_fn)| (name, update_fn.into()))
+            .collect(),
+    );
+    let all = parametrized.unit_vertex_set();
+    assert_eq!(
+        parametrized.count_states(&parametrized.successors_async("p", &all)),
+        1.0
+    );
+}
+
+#[test]
+fn only_a_single_color_is_simulated() {
+    let system = System::from_parametrized_update_fns(two_unknown_functions());
+    let options = SimulationOptions::default();
+    let initial = State::new(vec![0, 0]);
+    assert!(matches!(
+        simulate(&system, &initial, &options),
+        Err(SimulationError::MultipleColors { count }) if count == 81.into()
+    ));
+
+    // `p := 1`, without any unknown functions
+    let system = System::from_parametrized_update_fns(HashMap::from([parametrized(
+        "p",
+        vec![],
+        TermOutput::Value(1),
+        vec![0],
+    )]));
+    let result = simulate(&system, &State::new(vec![0]), &options).expect("a single color");
+    assert_eq!(result.fixed_point_walks, options.walks);
+    assert_eq!(
+        result.final_state_frequencies(),
+        vec![(State::new(vec![1]), 1.0)]
+    );
+}
+
+#[test]
+fn inconsistent_parameters_are_rejected() {
+    let arity = System::try_from_parametrized_update_fns(HashMap::from([
+        parametrized("p", vec![], unknown("f", &["q"]), vec![0, 1]),
+        parametrized("q", vec![], unknown("f", &[]), vec![0, 1]),
+    ]));
+    assert!(matches!(arity, Err(UpdateFnError::InconsistentParameter(name)) if name == "f"));
+
+    let domains = System::try_from_parametrized_update_fns(HashMap::from([
+        parametrized("p", vec![], unknown("c", &[]), vec![0, 1]),
+        parametrized("q", vec![], unknown("c", &[]), vec![0, 1, 2]),
+    ]));
+    assert!(matches!(domains, Err(UpdateFnError::InconsistentParameter(name)) if name == "c"));
+
+    let argument = System::try_from_parametrized_update_fns(HashMap::from([parametrized(
+        "p",
+        vec![],
+        unknown("f", &["r"]),
+        vec![0, 1],
+    )]));
+    assert!(matches!(
+        argument,
+        Err(UpdateFnError::UnknownArgument { function, variable }) if function == "f" && variable == "r"
+    ));
+
+    let domain = System::try_from_parametrized_update_fns(HashMap::from([parametrized(
+        "p",
+        vec![],
+        unknown("c", &[]),
+        vec![],
+    )]));
+    assert!(matches!(domain, Err(UpdateFnError::UnknownDomain(name)) if name == "p"));
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        expression::Expression,
+        percolation::percolate,
+        proposition::ComparisonOperator as CmpOp,
+        state::StateError,
+        symbolic_domain::{BinaryIntegerDomain, UnaryIntegerDomain},
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{prop, random_model, RandomModelOptions},
+};
+
+type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+fn update_fn(target: &str, terms: Vec<(u8, Expression<u8>)>) -> UnprocessedVariableUpdateFn<u8> {
+    UnprocessedVariableUpdateFn::new(vec![], target.to_string(), terms, 0)
+}
+
+fn assignment(values: &[(&str, u8)]) -> HashMap<String, u8> {
+    values
+        .iter()
+        .map(|(variable, value)| (variable.to_string(), *value))
+        .collect()
+}
+
+/// `a` and `d` keep their values, `b` copies `a` and `c` is `b | d`, while `e` is always `1`,
+/// which is however not apparent from its update function.
+fn cascade() -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    HashMap::from([
+        (
+            "a".to_string(),
+            update_fn("a", vec![(1, prop(CmpOp::Eq, "a", 1))]),
+        ),
+        (
+            "b".to_string(),
+            update_fn("b", vec![(1, prop(CmpOp::Eq, "a", 1))]),
+        ),
+        (
+            "c".to_string(),
+            update_fn(
+                "c",
+                vec![(
+                    1,
+                    Expression::Or(vec![prop(CmpOp::Eq, "b", 1), prop(CmpOp::Eq, "d", 1)]),
+                )],
+            ),
+        ),
+        (
+            "d".to_string(),
+            update_fn("d", vec![(1, prop(CmpOp::Eq, "d", 1))]),
+        ),
+        (
+            "e".to_string(),
+            update_fn(
+                "e",
+                vec![(
+                    1,
+                    Expression::Or(vec![prop(CmpOp::Eq, "a", 1), prop(CmpOp::Neq, "a", 1)]),
+                )],
+            ),
+        ),
+    ])
+}
+
+#[test]
+fn cascade_percolation() {
+    let model = cascade();
+    let system = System::from_update_fns(model.clone());
+    let percolated = |fixed: &[(&str, u8)]| {
+        let fixed = assignment(fixed);
+        (
+            percolate(&model, &fixed),
+            system
+                .percolate_assignment(&fixed)
+                .expect("valid assignment"),
+        )
+    };
+
+    let (explicit, symbolic) = percolated(&[("a", 1)]);
+    assert_eq!(
+        explicit,
+        assignment(&[("a", 1), ("b", 1), ("c", 1), ("e", 1)])
+    );
+    assert_eq!(symbolic, explicit);
+
+    // `c` still depends on `d`
+    let (explicit, symbolic) = percolated(&[("a", 0)]);
+    assert_eq!(explicit, assignment(&[("a", 0), ("b", 0), ("e", 1)]));
+    assert_eq!(symbolic, explicit);
+    let (explicit, symbolic) = percolated(&[("a", 0), ("d", 0)]);
+    assert_eq!(
+        explicit,
+        assignment(&[("a", 0), ("b", 0), ("c", 0), ("d", 0), ("e", 1)])
+    );
+    assert_eq!(symbolic, explicit);
+
+    // only the symbolic percolation sees that `e` is constant
+    let (explicit, symbolic) = percolated(&[]);
+    assert_eq!(explicit, assignment(&[]));
+    assert_eq!(symbolic, assignment(&[("e", 1)]));
+
+    // the fixed values are kept even if they are not stable
+    let (explicit, symbolic) = percolated(&[("e", 0)]);
+    assert_eq!(explicit, assignment(&[("e", 0)]));
+    assert_eq!(symbolic, explicit);
+}
+
+#[test]
+fn invalid_assignments_are_rejected() {
+    let system = System::from_update_fns(cascade());
+    assert!(matches!(
+        system.percolate_assignment(&assignment(&[("x", 0)])),
+        Err(StateError::UnknownVariable(name)) if name == "x"
+    ));
+    assert!(matches!(
+        system.percolate_assignment(&assignment(&[("a", 2)])),
+        Err(StateError::InvalidValue { variable, value }) if variable == "a" && value == "2"
+    ));
+}
+
+#[test]
+fn percolation_is_consistent_on_random_models() {
+    for seed in 0..20 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let unary = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(
+            model.update_fns.clone(),
+        );
+        let binary = System::from_update_fns(model.update_fns.clone());
+
+        for variable in ["v0", "v1"] {
+            for value in 0..=model.max_values[variable] {
+                let fixed = assignment(&[(variable, value)]);
+                let explicit = percolate(&model.update_fns, &fixed);
+                let symbolic = binary.percolate_assignment(&fixed).expect("valid");
+                assert_eq!(
+                    unary.percolate_assignment(&fixed).expect("valid"),
+                    symbolic,
+                    "seed {}",
+                    seed
+                );
+                // the syntactic percolation is weaker, but it never disagrees
+                assert!(
+                    explicit
+                        .iter()
+                        .all(|(variable, value)| symbolic.get(variable) == Some(value)),
+                    "seed {}: {:?} {:?}",
+                    seed,
+                    explicit,
+                    symbolic
+                );
+
+                // the percolated variables cannot leave their values within the subspace
+                let subspace = binary.initial_states_from_levels(&symbolic).expect("valid");
+                for (variable, value) in &symbolic {
+                    if !fixed.contains_key(variable) {
+                        let updated = binary.successors_async(variable, &subspace);
+                        assert!(updated
+                            .and_not(&binary.encode_one(variable, value))
+                            .is_false());
+                    }
+                }
+            }
+        }
+    }
+}
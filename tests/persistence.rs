@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        hctl::HctlChecker,
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        persistence::{model_hash, PersistenceError, PersistentDomain},
+        symbolic_domain::{
+            BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+            UnaryIntegerDomain,
+        },
+        update_fn::SmartSystemUpdateFn,
+        variable_ordering::{VariableOrder, VariableOrdering},
+    },
+    test_utils::{random_model, RandomModel, RandomModelOptions},
+};
+
+/// Saves and loads the system, and checks that the loaded system has the same semantics.
+fn round_trip<D: PersistentDomain<u8>>(system: &SmartSystemUpdateFn<D, u8>) {
+    let mut saved = Vec::new();
+    system
+        .write_compiled(&mut saved, 42)
+        .expect("writing to memory");
+    let loaded = SmartSystemUpdateFn::<D, u8>::read_compiled(&mut saved.as_slice(), Some(42))
+        .expect("loading the saved system");
+
+    assert_eq!(loaded.get_system_variables(), system.get_system_variables());
+    assert_eq!(
+        loaded.get_bdd_variable_set().variable_names(),
+        system.get_bdd_variable_set().variable_names()
+    );
+    assert_eq!(loaded.parameters(), system.parameters());
+    assert_eq!(loaded.unit_vertex_set(), system.unit_vertex_set());
+    let all = system.unit_vertex_set();
+    for variable in system.get_system_variables() {
+        assert_eq!(
+            loaded
+                .get_domain(&variable)
+                .map(|it| it.get_all_possible_values()),
+            system
+                .get_domain(&variable)
+                .map(|it| it.get_all_possible_values())
+        );
+        assert_eq!(
+            loaded.successors_async(&variable, &all),
+            system.successors_async(&variable, &all)
+        );
+        assert_eq!(
+            loaded.predecessors_async_exclude_loops(&variable, &all),
+            system.predecessors_async_exclude_loops(&variable, &all)
+        );
+        for value in system
+            .get_domain(&variable)
+            .unwrap()
+            .get_all_possible_values()
+        {
+            assert_eq!(
+                loaded.encode_one(&variable, &value),
+                system.encode_one(&variable, &value)
+            );
+        }
+    }
+}
+
+fn round_trip_with<D: PersistentDomain<u8>>(model: &RandomModel, ordering: &VariableOrdering) {
+    round_trip(
+        &SmartSystemUpdateFn::<D, u8>::from_update_fns_with_ordering(
+            model.update_fns.clone(),
+            ordering,
+        ),
+    );
+}
+
+#[test]
+fn compiled_systems_round_trip() {
+    let orderings = [
+        VariableOrdering::default(),
+        VariableOrdering::new(VariableOrder::RegulatoryDfs, true),
+    ];
+    for seed in 0..10 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        for ordering in &orderings {
+            round_trip_with::<UnaryIntegerDomain>(&model, ordering);
+            round_trip_with::<BinaryIntegerDomain<u8>>(&model, ordering);
+            round_trip_with::<GrayCodeIntegerDomain<u8>>(&model, ordering);
+            round_trip_with::<PetriNetIntegerDomain>(&model, ordering);
+        }
+    }
+}
+
+#[test]
+fn colored_systems_round_trip() {
+    // `p := f(q)` and `q := f(p)`, where `f` is an unknown function
+    let unknown = |target: &str, argument: &str| {
+        ParametrizedVariableUpdateFn::new(
+            vec![argument.to_string()],
+            target.to_string(),
+            vec![],
+            TermOutput::Unknown(UninterpretedFn::new(
+                "f".to_string(),
+                vec![argument.to_string()],
+            )),
+            vec![0, 1, 2],
+        )
+    };
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_parametrized_update_fns(
+        HashMap::from([
+            ("p".to_string(), unknown("p", "q")),
+            ("q".to_string(), unknown("q", "p")),
+        ]),
+    );
+    assert!(!system.parameters().is_empty());
+    round_trip(&system);
+}
+
+#[test]
+fn incompatible_files_are_rejected() {
+    let model = random_model(0, &RandomModelOptions::default());
+    let system =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(model.update_fns);
+    let mut saved = Vec::new();
+    system
+        .write_compiled(&mut saved, 42)
+        .expect("writing to memory");
+    type Binary = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+
+    assert!(Binary::read_compiled(&mut saved.as_slice(), None).is_ok());
+    assert!(matches!(
+        Binary::read_compiled(&mut saved.as_slice(), Some(7)),
+        Err(PersistenceError::ModelChanged {
+            expected: 7,
+            found: 42
+        })
+    ));
+    assert!(matches!(
+        SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::read_compiled(&mut saved.as_slice(), None),
+        Err(PersistenceError::DomainMismatch { .. })
+    ));
+
+    let text = String::from_utf8_lossy(&saved).to_string();
+    assert!(text.contains("\"domain_type\":\"binary\""));
+    let newer = text.replacen("\"format_version\":1", "\"format_version\":2", 1);
+    assert!(matches!(
+        Binary::read_compiled(&mut newer.as_bytes(), None),
+        Err(PersistenceError::UnsupportedVersion(2))
+    ));
+    let other = text.replacen("compiled-system", "state-set", 1);
+    assert!(matches!(
+        Binary::read_compiled(&mut other.as_bytes(), None),
+        Err(PersistenceError::UnexpectedContent { .. })
+    ));
+    assert!(matches!(
+        Binary::read_compiled(&mut &saved[..saved.len() - 5], None),
+        Err(PersistenceError::Io(_))
+    ));
+    assert!(matches!(
+        Binary::read_compiled(&mut "not a header\n".as_bytes(), None),
+        Err(PersistenceError::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn domains_with_other_encodings_round_trip() {
+    let model = random_model(1, &RandomModelOptions::default());
+    let default = SmartSystemUpdateFn::<MixedIntegerDomain<u8>, u8>::from_update_fns(
+        model.update_fns.clone(),
+    );
+    round_trip(&default);
+
+    // the domains are recreated with the saved encodings, not with the default ones
+    let encoded = SmartSystemUpdateFn::<MixedIntegerDomain<u8>, u8>::from_update_fns_with_encoding(
+        model.update_fns,
+        |name, _| model.encodings[name],
+    );
+    round_trip(&encoded);
+    let mut saved = Vec::new();
+    encoded
+        .write_compiled(&mut saved, 0)
+        .expect("writing to memory");
+    let loaded = SmartSystemUpdateFn::<MixedIntegerDomain<u8>, u8>::read_compiled(
+        &mut saved.as_slice(),
+        None,
+    )
+    .expect("loading the saved system");
+    for variable in encoded.get_system_variables() {
+        assert_eq!(
+            loaded.get_domain(&variable).map(|it| it.encoding()),
+            Some(model.encodings[&variable])
+        );
+    }
+}
+
+#[test]
+fn corrupt_bdds_are_rejected() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(
+        random_model(4, &RandomModelOptions::default()).update_fns,
+    );
+    let mut saved = Vec::new();
+    system
+        .write_compiled(&mut saved, 0)
+        .expect("writing to memory");
+    let bdd_start = saved
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .expect("header")
+        + 1;
+    let with_node_count = |count: u64| {
+        let mut corrupt = saved.clone();
+        corrupt[bdd_start..bdd_start + 8].copy_from_slice(&count.to_le_bytes());
+        corrupt
+    };
+    let is_rejected = |data: &[u8]| {
+        matches!(
+            SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::read_compiled(&mut &data[..], None),
+            Err(PersistenceError::Io(_))
+        )
+    };
+    // the size of the first saved BDD
+    let mut size = [0u8; 8];
+    size.copy_from_slice(&saved[bdd_start..bdd_start + 8]);
+    let size = u64::from_le_bytes(size);
+    assert!(size > 2);
+
+    assert!(is_rejected(&with_node_count(u64::MAX)));
+    assert!(is_rejected(&with_node_count(u64::MAX / 10)));
+    assert!(is_rejected(&with_node_count(0)));
+    assert!(is_rejected(&with_node_count(
+        (saved.len() - bdd_start) as u64 / 10 + 1
+    )));
+    // the last node of the first BDD pointing to itself
+    let mut cyclic = saved.clone();
+    let last_node = bdd_start + 8 + (size as usize - 1) * 10;
+    let index = (size as u32 - 1).to_le_bytes();
+    cyclic[last_node + 2..last_node + 6].copy_from_slice(&index);
+    assert!(is_rejected(&cyclic));
+}
+
+#[test]
+fn model_hash_identifies_the_source() {
+    let source = std::fs::read("data/manual/initial_levels.sbml").expect("readable file");
+    assert_eq!(model_hash(&source), model_hash(&source));
+    let mut changed = source.clone();
+    changed.push(b'\n');
+    assert_ne!(model_hash(&source), model_hash(&changed));
+    // the hash must not change between runs (or versions of Rust)
+    assert_eq!(model_hash(b""), 0xcbf29ce484222325);
+    assert_eq!(model_hash(b"a"), 0xaf63dc4c8601ec8c);
+}
+
+#[test]
+fn state_sets_round_trip() {
+    for seed in 0..5 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        let system = SmartSystemUpdateFn::<GrayCodeIntegerDomain<u8>, u8>::from_update_fns(
+            model.update_fns.clone(),
+        );
+        let attractors = HctlChecker::new(&system).attractor_states();
+        let mut saved = Vec::new();
+        system
+            .write_states(&mut saved, &attractors)
+            .expect("writing to memory");
+
+        // the set can be loaded by the same system, an equal system built again, or the
+        // system loaded from its compiled form
+        let rebuilt = SmartSystemUpdateFn::<GrayCodeIntegerDomain<u8>, u8>::from_update_fns(
+            model.update_fns.clone(),
+        );
+        let mut compiled = Vec::new();
+        system
+            .write_compiled(&mut compiled, 0)
+            .expect("writing to memory");
+        let loaded = SmartSystemUpdateFn::<GrayCodeIntegerDomain<u8>, u8>::read_compiled(
+            &mut compiled.as_slice(),
+            None,
+        )
+        .expect("loading the saved system");
+        for target in [&system, &rebuilt, &loaded] {
+            let read = target
+                .read_states(&mut saved.as_slice())
+                .expect("compatible system");
+            assert_eq!(read, attractors);
+            assert_eq!(
+                target.iter_states(&read).collect::<Vec<_>>(),
+                system.iter_states(&attractors).collect::<Vec<_>>()
+            );
+        }
+    }
+}
+
+#[test]
+fn incompatible_state_sets_are_rejected() {
+    let model = random_model(2, &RandomModelOptions::default());
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(
+        model.update_fns.clone(),
+    );
+    let mut saved = Vec::new();
+    system
+        .write_states(&mut saved, &system.unit_vertex_set())
+        .expect("writing to memory");
+
+    let unary =
+        SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(model.update_fns.clone());
+    assert!(matches!(
+        unary.read_states(&mut saved.as_slice()),
+        Err(PersistenceError::LayoutMismatch(_))
+    ));
+    let interleaved =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns_with_ordering(
+            model.update_fns.clone(),
+            &VariableOrdering::new(VariableOrder::Alphabetical, true),
+        );
+    assert!(matches!(
+        interleaved.read_states(&mut saved.as_slice()),
+        Err(PersistenceError::LayoutMismatch(message)) if message.contains("BDD variables")
+    ));
+    let other = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(
+        random_model(3, &RandomModelOptions::default()).update_fns,
+    );
+    assert!(matches!(
+        other.read_states(&mut saved.as_slice()),
+        Err(PersistenceError::LayoutMismatch(_))
+    ));
+
+    // a compiled system is not a state set
+    let mut compiled = Vec::new();
+    system
+        .write_compiled(&mut compiled, 0)
+        .expect("writing to memory");
+    assert!(matches!(
+        system.read_states(&mut compiled.as_slice()),
+        Err(PersistenceError::UnexpectedContent { .. })
+    ));
+}
+
+#[test]
+fn colored_state_sets_round_trip() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    // the fixed points, i.e. `p = c`
+    let fixed_points = HctlChecker::new(&system).attractor_states();
+    assert_eq!(system.count_colors(&fixed_points), 2.0);
+    let mut saved = Vec::new();
+    system
+        .write_states(&mut saved, &fixed_points)
+        .expect("writing to memory");
+    assert_eq!(
+        system
+            .read_states(&mut saved.as_slice())
+            .expect("compatible system"),
+        fixed_points
+    );
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        explicit_system::ExplicitSystem,
+        hctl::HctlChecker,
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        perturbation::{Perturbation, PerturbationError},
+        proposition::ComparisonOperator as CmpOp,
+        state::State,
+        symbolic_domain::{
+            BinaryIntegerDomain, GrayCodeIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain,
+        },
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::{prop, random_model, RandomModel, RandomModelOptions},
+};
+
+/// The update function of the variable with every output clamped into `min..=max`.
+fn clamped(
+    update_fn: &UnprocessedVariableUpdateFn<u8>,
+    min: u8,
+    max: u8,
+) -> UnprocessedVariableUpdateFn<u8> {
+    UnprocessedVariableUpdateFn::new(
+        update_fn.input_vars_names.clone(),
+        update_fn.target_var_name.clone(),
+        update_fn
+            .terms
+            .iter()
+            .map(|(value, term)| ((*value).clamp(min, max), term.clone()))
+            .collect(),
+        update_fn.default.clamp(min, max),
+    )
+}
+
+/// Compares the perturbed symbolic system with the explicit semantics of the model whose
+/// update functions were clamped by hand.
+fn check_perturbed<D: SymbolicDomainOrd<u8> + Clone>(
+    model: &RandomModel,
+    perturbations: &HashMap<String, Perturbation<u8>>,
+) {
+    let system = SmartSystemUpdateFn::<D, u8>::from_update_fns(model.update_fns.clone());
+    let perturbed = system
+        .perturbed(perturbations)
+        .expect("valid perturbations");
+
+    let mut update_fns = model.update_fns.clone();
+    for (variable, perturbation) in perturbations {
+        let (min, max) = match perturbation {
+            Perturbation::Knockout => (0, 0),
+            Perturbation::OverExpression => {
+                (model.max_values[variable], model.max_values[variable])
+            }
+            Perturbation::Fixed(value) => (*value, *value),
+            Perturbation::Interval { min, max } => (*min, *max),
+        };
+        update_fns.insert(variable.clone(), clamped(&update_fns[variable], min, max));
+    }
+    let explicit = ExplicitSystem::new(update_fns);
+
+    let states = perturbed
+        .iter_states(&perturbed.unit_vertex_set())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        states.len() as f64,
+        system.count_states(&system.unit_vertex_set())
+    );
+    for state in &states {
+        let encoded = perturbed.encode_state(state).expect("valid state");
+        for (idx, variable) in perturbed.get_system_variables().iter().enumerate() {
+            let mut values = state.values().to_vec();
+            values[idx] = explicit.next_value(state, idx);
+            let expected = perturbed
+                .encode_state(&State::new(values))
+                .expect("valid state");
+            assert_eq!(
+                perturbed.successors_async(variable, &encoded),
+                expected,
+                "{:?} {:?} {}",
+                perturbations,
+                state,
+                variable
+            );
+            assert!(perturbed
+                .predecessors_async(variable, expected)
+                .and(&encoded)
+                .eq(&encoded));
+        }
+    }
+}
+
+fn perturbations_for(seed: u64, model: &RandomModel) -> Vec<HashMap<String, Perturbation<u8>>> {
+    let max = |variable: &str| model.max_values[variable];
+    vec![
+        HashMap::from([("v0".to_string(), Perturbation::Knockout)]),
+        HashMap::from([("v1".to_string(), Perturbation::OverExpression)]),
+        HashMap::from([
+            (
+                "v0".to_string(),
+                Perturbation::Fixed(seed as u8 % (max("v0") + 1)),
+            ),
+            (
+                "v2".to_string(),
+                Perturbation::Interval {
+                    min: max("v2") / 2,
+                    max: max("v2"),
+                },
+            ),
+        ]),
+        HashMap::from([(
+            "v1".to_string(),
+            Perturbation::Interval {
+                min: 0,
+                max: max("v1").saturating_sub(1),
+            },
+        )]),
+    ]
+}
+
+#[test]
+fn perturbations_match_explicit_semantics() {
+    for seed in 0..10 {
+        let model = random_model(seed, &RandomModelOptions::default());
+        for perturbations in perturbations_for(seed, &model) {
+            check_perturbed::<UnaryIntegerDomain>(&model, &perturbations);
+            check_perturbed::<BinaryIntegerDomain<u8>>(&model, &perturbations);
+            check_perturbed::<GrayCodeIntegerDomain<u8>>(&model, &perturbations);
+        }
+    }
+}
+
+/// `p` counts up to three and stays there, `q` follows whether `p >= 2`.
+fn counter() -> HashMap<String, UnprocessedVariableUpdateFn<u8>> {
+    HashMap::from([
+        (
+            "p".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![],
+                "p".to_string(),
+                vec![(1, prop(CmpOp::Eq, "p", 0)), (2, prop(CmpOp::Eq, "p", 1))],
+                3,
+            ),
+        ),
+        (
+            "q".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec![],
+                "q".to_string(),
+                vec![(1, prop(CmpOp::Geq, "p", 2))],
+                0,
+            ),
+        ),
+    ])
+}
+
+#[test]
+fn perturbations_change_the_attractors() {
+    type System = SmartSystemUpdateFn<BinaryIntegerDomain<u8>, u8>;
+    let system = System::from_update_fns(counter());
+    let attractors = |system: &System| {
+        let attractors = HctlChecker::new(system).attractor_states();
+        system.iter_states(&attractors).collect::<Vec<_>>()
+    };
+    assert_eq!(attractors(&system), [State::new(vec![3, 1])]);
+
+    let knockout = system
+        .perturbed(&HashMap::from([("p".to_string(), Perturbation::Knockout)]))
+        .expect("valid perturbation");
+    assert_eq!(attractors(&knockout), [State::new(vec![0, 0])]);
+    let restricted = system
+        .perturbed(&HashMap::from([(
+            "p".to_string(),
+            Perturbation::Interval { min: 0, max: 1 },
+        )]))
+        .expect("valid perturbation");
+    assert_eq!(attractors(&restricted), [State::new(vec![1, 0])]);
+    // the original system is not changed
+    assert_eq!(attractors(&system), [State::new(vec![3, 1])]);
+
+    // perturbing in place gives the same system and the perturbations compose
+    let mut in_place = System::from_update_fns(counter());
+    in_place
+        .perturb("p", &Perturbation::Interval { min: 0, max: 1 })
+        .expect("valid perturbation");
+    let all = in_place.unit_vertex_set();
+    assert_eq!(
+        in_place.successors_async("p", &all),
+        restricted.successors_async("p", &all)
+    );
+    in_place
+        .perturb("p", &Perturbation::Fixed(2))
+        .expect("valid perturbation");
+    assert_eq!(attractors(&in_place), [State::new(vec![2, 1])]);
+}
+
+#[test]
+fn colored_systems_can_be_perturbed() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let mut system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    system
+        .perturb("p", &Perturbation::OverExpression)
+        .expect("valid perturbation");
+    let all = system.unit_vertex_set();
+    assert_eq!(
+        system.successors_async("p", &all),
+        system.encode_one("p", &1).and(&all)
+    );
+}
+
+#[test]
+fn invalid_perturbations_are_rejected() {
+    let mut system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(counter());
+    assert!(matches!(
+        system.perturb("r", &Perturbation::Knockout),
+        Err(PerturbationError::UnknownVariable(name)) if name == "r"
+    ));
+    assert!(matches!(
+        system.perturb("q", &Perturbation::Fixed(2)),
+        Err(PerturbationError::InvalidValue { variable, value }) if variable == "q" && value == "2"
+    ));
+    assert!(matches!(
+        system.perturb("p", &Perturbation::Interval { min: 2, max: 1 }),
+        Err(PerturbationError::EmptyInterval(name)) if name == "p"
+    ));
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        self as bio,
+        simulation::{simulate, SimulationError, SimulationOptions},
+        state::State,
+        symbolic_domain::{BinaryIntegerDomain, MixedIntegerDomain},
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::mutual_copy,
+};
+
+#[test]
+fn walks_end_in_fixed_points() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let options = SimulationOptions {
+        walks: 200,
+        record_trajectories: true,
+        ..SimulationOptions::default()
+    };
+    let result = simulate(&system, &State::new(vec![0, 1]), &options).expect("valid options");
+
+    assert_eq!(result.variables, ["p", "q"]);
+    assert_eq!(result.fixed_point_walks, 200);
+    assert_eq!(result.trajectories.len(), 200);
+    for trajectory in &result.trajectories {
+        assert_eq!(trajectory.states.len(), 2);
+        assert_eq!(trajectory.updated_variables.len(), 1);
+    }
+
+    let frequencies = result.final_state_frequencies();
+    assert_eq!(frequencies.len(), 2);
+    let mut final_states = frequencies
+        .iter()
+        .map(|(state, _)| state.values().to_vec())
+        .collect::<Vec<_>>();
+    final_states.sort();
+    assert_eq!(final_states, [[0, 0], [1, 1]]);
+    for (_, frequency) in &frequencies {
+        assert!((0.35..0.65).contains(frequency), "{}", frequency);
+    }
+
+    // the initial state is visited once per walk, and so is the final state
+    let visits = result.visit_frequencies();
+    assert_eq!(visits[0].0, State::new(vec![0, 1]));
+    assert!((visits[0].1 - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn rates_bias_the_walks() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let options = SimulationOptions {
+        walks: 50,
+        rates: HashMap::from([("p".to_string(), 0.0)]),
+        ..SimulationOptions::default()
+    };
+    let result = simulate(&system, &State::new(vec![0, 1]), &options).expect("valid options");
+    assert_eq!(
+        result.final_state_frequencies(),
+        vec![(State::new(vec![0, 0]), 1.0)]
+    );
+
+    let options = SimulationOptions {
+        walks: 500,
+        rates: HashMap::from([("p".to_string(), 9.0)]),
+        ..SimulationOptions::default()
+    };
+    let result = simulate(&system, &State::new(vec![0, 1]), &options).expect("valid options");
+    let frequencies = result.final_state_frequencies();
+    assert_eq!(frequencies[0].0, State::new(vec![1, 1]));
+    assert!(frequencies[0].1 > 0.8, "{}", frequencies[0].1);
+}
+
+#[test]
+fn walks_stall_when_only_frozen_variables_can_change() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let options = SimulationOptions {
+        walks: 10,
+        rates: HashMap::from([("p".to_string(), 0.0), ("q".to_string(), 0.0)]),
+        ..SimulationOptions::default()
+    };
+
+    let result = simulate(&system, &State::new(vec![0, 1]), &options).expect("valid options");
+    assert_eq!(result.stalled_walks, 10);
+    assert_eq!(result.fixed_point_walks, 0);
+    assert_eq!(result.final_states, vec![State::new(vec![0, 1]); 10]);
+
+    let result = simulate(&system, &State::new(vec![1, 1]), &options).expect("valid options");
+    assert_eq!(result.stalled_walks, 0);
+    assert_eq!(result.fixed_point_walks, 10);
+}
+
+#[test]
+fn invalid_options_are_rejected() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let initial = State::new(vec![0, 1]);
+
+    let unknown = SimulationOptions {
+        rates: HashMap::from([("r".to_string(), 1.0)]),
+        ..SimulationOptions::default()
+    };
+    assert!(matches!(
+        simulate(&system, &initial, &unknown),
+        Err(SimulationError::UnknownVariable(name)) if name == "r"
+    ));
+
+    for rate in [-1.0, f64::NAN, f64::INFINITY] {
+        let invalid = SimulationOptions {
+            rates: HashMap::from([("q".to_string(), rate)]),
+            ..SimulationOptions::default()
+        };
+        assert!(matches!(
+            simulate(&system, &initial, &invalid),
+            Err(SimulationError::InvalidRate { variable, .. }) if variable == "q"
+        ));
+    }
+
+    assert!(matches!(
+        simulate(&system, &State::new(vec![0]), &SimulationOptions::default()),
+        Err(SimulationError::WrongVariableCount {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+#[test]
+fn trajectories_follow_the_symbolic_transitions() {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open("data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml")
+            .expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let system = SmartSystemUpdateFn::<MixedIntegerDomain, u8>::try_from_xml(&mut xml)
+        .expect("should be able to parse");
+    let variables = system.get_system_variables();
+    let initial = State::new(vec![0; variables.len()]);
+
+    let options = SimulationOptions {
+        walks: 3,
+        steps: 20,
+        seed: 7,
+        record_trajectories: true,
+        ..SimulationOptions::default()
+    };
+    let result = simulate(&system, &initial, &options).expect("valid options");
+    for trajectory in &result.trajectories {
+        for (idx, variable) in trajectory.updated_variables.iter().enumerate() {
+            let source = system
+                .encode_state(&trajectory.states[idx])
+                .expect("valid state");
+            let target = system
+                .encode_state(&trajectory.states[idx + 1])
+                .expect("valid state");
+            assert_ne!(source, target);
+            assert!(target
+                .imp(&system.successors_async(&variables[*variable], &source))
+                .is_true());
+        }
+    }
+
+    // the same seed gives the same walks
+    let again = simulate(&system, &initial, &options).expect("valid options");
+    assert_eq!(again.trajectories, result.trajectories);
+    assert_eq!(again.visits, result.visits);
+}
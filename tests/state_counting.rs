@@ -0,0 +1,127 @@
+use biodivine_lib_logical_models::{
+    prelude::{
+        symbolic_domain::{
+            BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+            SymbolicDomainOrd, UnaryIntegerDomain,
+        },
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::load_update_fns,
+};
+use num_bigint::BigInt;
+
+const MODEL: &str = "data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml";
+
+/// The number of all states of the system, i.e. the product of the sizes of all domains.
+fn expected_state_count<D: SymbolicDomainOrd<u8>>(system: &SmartSystemUpdateFn<D, u8>) -> BigInt {
+    system
+        .standard_domains()
+        .into_iter()
+        .map(|domain| BigInt::from(domain.get_all_possible_values().len()))
+        .product()
+}
+
+fn check_counts<D: SymbolicDomainOrd<u8>>(system: &SmartSystemUpdateFn<D, u8>) {
+    let expected = expected_state_count(system);
+    let bdd_variable_set = system.get_bdd_variable_set();
+
+    // invalid bit patterns are not counted, even if the set is not restricted to valid states
+    assert_eq!(
+        system.count_states_exact(&bdd_variable_set.mk_true()),
+        expected
+    );
+    assert_eq!(
+        system.count_states_exact(&system.unit_vertex_set()),
+        expected
+    );
+    assert_eq!(
+        system.count_states(&bdd_variable_set.mk_true()),
+        expected.to_string().parse::<f64>().unwrap()
+    );
+    assert_eq!(
+        system.count_states_exact(&bdd_variable_set.mk_false()),
+        BigInt::from(0)
+    );
+
+    // dependence on the primed variables is projected away
+    let state = system.pick_state_bdd(&system.unit_vertex_set());
+    let primed = bdd_variable_set.mk_var(system.primed_variables()[0]);
+    assert_eq!(system.count_states_exact(&state), BigInt::from(1));
+    assert_eq!(
+        system.count_states_exact(&state.and(&primed)),
+        BigInt::from(1)
+    );
+    assert_eq!(system.count_states(&state.and(&primed)), 1.0);
+
+    // projection onto the bits of a single variable counts its values
+    for (name, domain) in system.standard_variables_names_and_domains() {
+        let values = domain.get_all_possible_values().len();
+        assert_eq!(
+            system.count_projected_exact(&bdd_variable_set.mk_true(), &domain.raw_bdd_variables()),
+            BigInt::from(values),
+            "{}",
+            name
+        );
+        let one = system.encode_one(name, &0);
+        assert_eq!(
+            system.count_projected_exact(&one, &domain.raw_bdd_variables()),
+            BigInt::from(1),
+            "{}",
+            name
+        );
+        // fixing one variable leaves all values of the others
+        assert_eq!(
+            system.count_states_exact(&one) * BigInt::from(values),
+            expected,
+            "{}",
+            name
+        );
+    }
+
+    // projection onto all BDD variables of a set without primed dependence doubles
+    // the count for every primed bit
+    let all = bdd_variable_set.variables();
+    assert_eq!(
+        system.count_projected_exact(&system.unit_vertex_set(), &all),
+        expected << system.primed_variables().len()
+    );
+}
+
+fn check_counts_for<DO: SymbolicDomainOrd<u8>>() {
+    check_counts(&SmartSystemUpdateFn::<DO, u8>::from_update_fns(
+        load_update_fns(MODEL),
+    ));
+}
+
+#[test]
+fn counts_do_not_depend_on_encoding() {
+    check_counts_for::<UnaryIntegerDomain>();
+    check_counts_for::<BinaryIntegerDomain<u8>>();
+    check_counts_for::<GrayCodeIntegerDomain<u8>>();
+    check_counts_for::<PetriNetIntegerDomain>();
+    check_counts_for::<MixedIntegerDomain>();
+}
+
+#[test]
+fn counts_of_reachable_states_match_across_encodings() {
+    let unary =
+        SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(load_update_fns(MODEL));
+    let binary =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(load_update_fns(MODEL));
+
+    let variables = unary.get_system_variables();
+    let mut reached_unary = variables.iter().fold(unary.unit_vertex_set(), |acc, var| {
+        acc.and(&unary.encode_one(var, &0))
+    });
+    let mut reached_binary = variables.iter().fold(binary.unit_vertex_set(), |acc, var| {
+        acc.and(&binary.encode_one(var, &0))
+    });
+    for var in variables.iter().cycle().take(3 * variables.len()) {
+        reached_unary = reached_unary.or(&unary.successors_async(var, &reached_unary));
+        reached_binary = reached_binary.or(&binary.successors_async(var, &reached_binary));
+        assert_eq!(
+            unary.count_states_exact(&reached_unary),
+            binary.count_states_exact(&reached_binary)
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        self as bio,
+        parametrized_update_function::{ParametrizedVariableUpdateFn, TermOutput, UninterpretedFn},
+        state_transition_graph::{StateTransitionGraph, StgError, StgOptions, UpdateScheme},
+        symbolic_domain::{BinaryIntegerDomain, UnaryIntegerDomain},
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::mutual_copy,
+};
+
+/// The edges of the graph as triples of (source label, target label, edge label).
+fn labelled_edges(graph: &StateTransitionGraph<u8>) -> HashSet<(String, String, String)> {
+    graph
+        .edges()
+        .iter()
+        .map(|edge| {
+            (
+                graph.state_label(edge.source),
+                graph.state_label(edge.target),
+                graph.edge_label(edge),
+            )
+        })
+        .collect()
+}
+
+fn edge(source: &str, target: &str, label: &str) -> (String, String, String) {
+    (source.to_string(), target.to_string(), label.to_string())
+}
+
+#[test]
+fn asynchronous_graph() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let graph = StateTransitionGraph::try_from_system(&system, &StgOptions::default())
+        .expect("small enough");
+
+    assert_eq!(graph.variables(), ["p", "q"]);
+    let mut labels = (0..graph.states().len())
+        .map(|idx| graph.state_label(idx))
+        .collect::<Vec<_>>();
+    labels.sort();
+    assert_eq!(labels, ["p=0,q=0", "p=0,q=1", "p=1,q=0", "p=1,q=1"]);
+
+    assert_eq!(
+        labelled_edges(&graph),
+        HashSet::from([
+            edge("p=0,q=1", "p=1,q=1", "p"),
+            edge("p=0,q=1", "p=0,q=0", "q"),
+            edge("p=1,q=0", "p=0,q=0", "p"),
+            edge("p=1,q=0", "p=1,q=1", "q"),
+        ])
+    );
+
+    let with_loops = StateTransitionGraph::try_from_system(
+        &system,
+        &StgOptions {
+            include_self_loops: true,
+            ..StgOptions::default()
+        },
+    )
+    .expect("small enough");
+    // every state has one outgoing transition per variable
+    assert_eq!(with_loops.edges().len(), 8);
+    assert!(labelled_edges(&with_loops).contains(&edge("p=1,q=1", "p=1,q=1", "q")));
+}
+
+#[test]
+fn synchronous_graph() {
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(mutual_copy());
+    let options = StgOptions {
+        scheme: UpdateScheme::Synchronous,
+        ..StgOptions::default()
+    };
+    let graph = StateTransitionGraph::try_from_system(&system, &options).expect("small enough");
+    assert_eq!(
+        labelled_edges(&graph),
+        HashSet::from([
+            edge("p=0,q=1", "p=1,q=0", "p,q"),
+            edge("p=1,q=0", "p=0,q=1", "p,q"),
+        ])
+    );
+}
+
+#[test]
+fn graph_is_restricted_to_a_subset() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let p_is_one = system.encode_one("p", &1);
+    let graph = StateTransitionGraph::try_from_set(&system, &p_is_one, &StgOptions::default())
+        .expect("small enough");
+    assert_eq!(graph.states().len(), 2);
+    assert_eq!(
+        labelled_edges(&graph),
+        HashSet::from([edge("p=1,q=0", "p=1,q=1", "q")])
+    );
+}
+
+#[test]
+fn exports_contain_labels() {
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(mutual_copy());
+    let graph = StateTransitionGraph::try_from_system(&system, &StgOptions::default())
+        .expect("small enough");
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("[label=\"p=1,q=0\"]"));
+    assert_eq!(dot.matches("->").count(), 4);
+
+    let graphml = graph.to_graphml();
+    xml::reader::EventReader::new(graphml.as_bytes())
+        .into_iter()
+        .for_each(|event| {
+            event.expect("valid XML");
+        });
+    assert_eq!(graphml.matches("<node ").count(), 4);
+    assert_eq!(graphml.matches("<edge ").count(), 4);
+    assert!(graphml.contains(">p=0,q=1</data>"));
+
+    let json = serde_json::from_str::<serde_json::Value>(&graph.to_json()).expect("valid JSON");
+    assert_eq!(json["variables"], serde_json::json!(["p", "q"]));
+    assert_eq!(json["states"].as_array().map(|it| it.len()), Some(4));
+    assert_eq!(json["edges"].as_array().map(|it| it.len()), Some(4));
+    let first_edge = &json["edges"][0];
+    assert_eq!(
+        first_edge["label"], first_edge["variables"][0],
+        "asynchronous edges update one variable"
+    );
+}
+
+#[test]
+fn large_graphs_are_rejected() {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open("data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml")
+            .expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+    let system = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::try_from_xml(&mut xml)
+        .expect("should be able to parse");
+
+    let options = StgOptions {
+        state_limit: 100,
+        ..StgOptions::default()
+    };
+    let result = StateTransitionGraph::try_from_system(&system, &options);
+    assert!(matches!(
+        result,
+        Err(StgError::TooManyStates { limit: 100, .. })
+    ));
+
+    // a single state with all its asynchronous successors fits
+    let state = system.pick_state_bdd(&system.unit_vertex_set());
+    let neighbourhood = system
+        .get_system_variables()
+        .iter()
+        .fold(state.clone(), |acc, var| {
+            acc.or(&system.successors_async(var, &state))
+        });
+    let graph = StateTransitionGraph::try_from_set(&system, &neighbourhood, &options)
+        .expect("small enough");
+    assert!(graph.states().len() <= system.get_system_variables().len() + 1);
+    assert!(graph
+        .edges()
+        .iter()
+        .all(|edge| edge.updated_variables.len() == 1));
+}
+
+#[test]
+fn colored_graphs_need_a_single_color() {
+    // `p := c`, where `c` is an unknown constant
+    let update_fn = ParametrizedVariableUpdateFn::new(
+        vec![],
+        "p".to_string(),
+        vec![],
+        TermOutput::Unknown(UninterpretedFn::parameter("c".to_string())),
+        vec![0, 1],
+    );
+    let system = SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_parametrized_update_fns(
+        HashMap::from([("p".to_string(), update_fn)]),
+    );
+    let options = StgOptions::default();
+    assert!(matches!(
+        StateTransitionGraph::try_from_system(&system, &options),
+        Err(StgError::MultipleColors { count }) if count == 2.into()
+    ));
+
+    for value in [0, 1] {
+        let color = system.encode_parameter("c", &[], &value);
+        let graph = StateTransitionGraph::try_from_set(
+            &system,
+            &system.unit_vertex_set().and(&color),
+            &options,
+        )
+        .expect("a single color");
+        assert_eq!(graph.states().len(), 2);
+        let source = format!("p={}", 1 - value);
+        let target = format!("p={}", value);
+        assert_eq!(
+            labelled_edges(&graph),
+            HashSet::from([edge(&source, &target, "p")])
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet};
+
+use biodivine_lib_logical_models::{
+    prelude::{
+        state::{State, StateError},
+        symbolic_domain::{
+            BinaryIntegerDomain, GrayCodeIntegerDomain, MixedIntegerDomain, PetriNetIntegerDomain,
+            SymbolicDomainOrd, UnaryIntegerDomain,
+        },
+        update_fn::SmartSystemUpdateFn,
+    },
+    test_utils::load_update_fns,
+};
+use num_bigint::BigInt;
+
+const MODEL: &str = "data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml";
+
+fn zero_state<DO: SymbolicDomainOrd<u8>>(system: &SmartSystemUpdateFn<DO, u8>) -> State<u8> {
+    State::new(vec![0; system.get_system_variables().len()])
+}
+
+fn check_states_round_trip<DO: SymbolicDomainOrd<u8>>() {
+    let system = SmartSystemUpdateFn::<DO, u8>::from_update_fns(load_update_fns(MODEL));
+
+    // a few rounds of reachability from the zero state give a reasonably sized set
+    let initial = system
+        .encode_state(&zero_state(&system))
+        .expect("valid state");
+    let mut reached = initial.clone();
+    for var in system.get_system_variables().iter().cycle().take(40) {
+        reached = reached.or(&system.successors_async(var, &reached));
+    }
+
+    let states = system.iter_states(&reached).collect::<Vec<_>>();
+    assert_eq!(
+        BigInt::from(states.len()),
+        system.count_states_exact(&reached)
+    );
+    assert_eq!(
+        states.iter().collect::<HashSet<_>>().len(),
+        states.len(),
+        "states are listed once"
+    );
+    assert_eq!(
+        system.encode_states(&states).expect("valid states"),
+        reached
+    );
+
+    // the states agree with the values decoded by the individual domains
+    for (name, domain) in system.standard_variables_names_and_domains() {
+        let mut expected = domain.decode_collection(system.get_bdd_variable_set(), &reached);
+        expected.sort();
+        expected.dedup();
+        let mut actual = states
+            .iter()
+            .map(|state| *system.state_value(state, name).expect("known variable"))
+            .collect::<Vec<_>>();
+        actual.sort();
+        actual.dedup();
+        assert_eq!(actual, expected, "{}", name);
+    }
+
+    // primed variables do not produce duplicate states
+    let primed = system
+        .get_bdd_variable_set()
+        .mk_var(system.primed_variables()[0]);
+    let with_primed = initial.and(&primed).or(&initial.and(&primed.not()));
+    assert_eq!(
+        system.iter_states(&with_primed).collect::<Vec<_>>(),
+        vec![zero_state(&system)]
+    );
+    assert_eq!(
+        system.pick_state(&initial.and(&primed)),
+        Some(zero_state(&system))
+    );
+    assert_eq!(
+        system.pick_state(&system.get_bdd_variable_set().mk_false()),
+        None
+    );
+}
+
+#[test]
+fn states_round_trip_unary() {
+    check_states_round_trip::<UnaryIntegerDomain>();
+}
+
+#[test]
+fn states_round_trip_binary() {
+    check_states_round_trip::<BinaryIntegerDomain<u8>>();
+}
+
+#[test]
+fn states_round_trip_gray() {
+    check_states_round_trip::<GrayCodeIntegerDomain<u8>>();
+}
+
+#[test]
+fn states_round_trip_petri_net() {
+    check_states_round_trip::<PetriNetIntegerDomain>();
+}
+
+#[test]
+fn states_round_trip_mixed() {
+    check_states_round_trip::<MixedIntegerDomain>();
+}
+
+#[test]
+fn iterator_skips_invalid_values() {
+    let system =
+        SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns(load_update_fns(MODEL));
+    let everything = system.get_bdd_variable_set().mk_true();
+    let expected = system.count_states_exact(&everything);
+    // the iterator is lazy, so this does not decode all of the states
+    let first = system.iter_states(&everything).take(10).collect::<Vec<_>>();
+    assert_eq!(first.len(), 10);
+    for state in &first {
+        let encoded = system.encode_state(state).expect("valid state");
+        assert!(encoded.imp(&system.unit_vertex_set()).is_true());
+    }
+    assert!(expected > BigInt::from(10));
+}
+
+#[test]
+fn states_convert_from_and_to_maps() {
+    let system =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns(load_update_fns(MODEL));
+    let variables = system.get_system_variables();
+
+    let map = variables
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| (name.clone(), u8::from(idx % 2 == 0)))
+        .collect::<HashMap<_, _>>();
+    let state = system.state_from_map(&map).expect("valid state");
+    for (idx, name) in variables.iter().enumerate() {
+        assert_eq!(system.variable_index(name), Some(idx));
+        assert_eq!(state.get(idx), map.get(name));
+        assert_eq!(system.state_value(&state, name), map.get(name));
+    }
+    assert_eq!(system.state_to_map(&state), map);
+    assert_eq!(system.variable_index("unknown"), None);
+
+    let mut missing = map.clone();
+    missing.remove(&variables[0]);
+    assert!(matches!(
+        system.state_from_map(&missing),
+        Err(StateError::MissingVariable(name)) if name == variables[0]
+    ));
+
+    let mut unknown = map.clone();
+    unknown.insert("unknown".to_string(), 0);
+    assert!(matches!(
+        system.state_from_map(&unknown),
+        Err(StateError::UnknownVariable(name)) if name == "unknown"
+    ));
+
+    let short = State::new(state.values()[1..].to_vec());
+    assert!(matches!(
+        system.encode_state(&short),
+        Err(StateError::WrongVariableCount { expected, actual })
+            if expected == variables.len() && actual == variables.len() - 1
+    ));
+}
@@ -0,0 +1,212 @@
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::{
+    prelude::{
+        symbolic_domain::{
+            BinaryIntegerDomain, SymbolicDomain, SymbolicDomainOrd, UnaryIntegerDomain,
+        },
+        update_fn::{SmartSystemUpdateFn, UpdateFnError},
+        variable_ordering::{VariableOrder, VariableOrderError, VariableOrdering},
+    },
+    test_utils::load_update_fns,
+};
+
+const MODEL: &str = "data/test-models/146_BUDDING-YEAST-FAURE-2009.sbml";
+
+fn all_orderings() -> Vec<VariableOrdering> {
+    let mut reversed = load_update_fns(MODEL).into_keys().collect::<Vec<_>>();
+    reversed.sort();
+    reversed.reverse();
+
+    [
+        VariableOrder::Alphabetical,
+        VariableOrder::Explicit(reversed),
+        VariableOrder::RegulatoryDfs,
+        VariableOrder::RegulatoryBfs,
+        VariableOrder::Force,
+    ]
+    .into_iter()
+    .flat_map(|order| {
+        [false, true]
+            .into_iter()
+            .map(move |interleave_bits| VariableOrdering::new(order.clone(), interleave_bits))
+    })
+    .collect()
+}
+
+/// The values each variable attains in a set of states, sorted by the name of the variable.
+type Projections = Vec<(String, Vec<u8>)>;
+
+/// Projects the set of states onto each variable, i.e. lists the values each variable
+/// attains in the set (sorted by the name of the variable).
+fn projections<DO: SymbolicDomainOrd<u8>>(
+    system: &SmartSystemUpdateFn<DO, u8>,
+    set: &Bdd,
+) -> Projections {
+    let mut result = system
+        .standard_variables_names_and_domains()
+        .into_iter()
+        .map(|(name, domain)| {
+            let mut values = domain.decode_collection(system.get_bdd_variable_set(), set);
+            values.sort();
+            (name.to_string(), values)
+        })
+        .collect::<Vec<_>>();
+    result.sort();
+    result
+}
+
+/// Two rounds of forward reachability from the state where every variable is zero,
+/// followed by a round backward.
+fn explore<DO: SymbolicDomainOrd<u8>>(
+    system: &SmartSystemUpdateFn<DO, u8>,
+) -> Vec<(f64, Projections)> {
+    let mut variables = system.get_system_variables();
+    variables.sort();
+    let initial = variables.iter().fold(system.unit_vertex_set(), |acc, var| {
+        acc.and(&system.encode_one(var, &0))
+    });
+
+    let mut result = Vec::new();
+    let mut reached = initial.clone();
+    for _ in 0..2 {
+        for var in &variables {
+            reached = reached.or(&system.successors_async(var, &reached));
+        }
+        result.push((reached.cardinality(), projections(system, &reached)));
+    }
+    let mut reached = initial;
+    for _ in 0..1 {
+        for var in &variables {
+            reached = reached.or(&system.predecessors_async(var, reached.clone()));
+        }
+        result.push((reached.cardinality(), projections(system, &reached)));
+    }
+    result
+}
+
+fn check_orderings_are_equivalent<DO: SymbolicDomainOrd<u8>>() {
+    let reference = explore(&SmartSystemUpdateFn::<DO, u8>::from_update_fns(
+        load_update_fns(MODEL),
+    ));
+    for ordering in all_orderings() {
+        let system = SmartSystemUpdateFn::<DO, u8>::from_update_fns_with_ordering(
+            load_update_fns(MODEL),
+            &ordering,
+        );
+        assert_eq!(explore(&system), reference, "{:?}", ordering);
+    }
+}
+
+#[test]
+fn orderings_are_equivalent_unary() {
+    check_orderings_are_equivalent::<UnaryIntegerDomain>();
+}
+
+#[test]
+fn orderings_are_equivalent_binary() {
+    check_orderings_are_equivalent::<BinaryIntegerDomain<u8>>();
+}
+
+#[test]
+fn system_follows_the_ordering() {
+    for ordering in all_orderings() {
+        let expected = ordering
+            .order
+            .sort_variables(&load_update_fns(MODEL))
+            .expect("valid order");
+        assert_eq!(
+            ordering
+                .order
+                .sort_variables(&load_update_fns(MODEL))
+                .expect("valid order"),
+            expected,
+            "the order should be deterministic"
+        );
+
+        let system =
+            SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns_with_ordering(
+                load_update_fns(MODEL),
+                &ordering,
+            );
+        assert_eq!(system.get_system_variables(), expected);
+
+        let bdd_variable_set = system.get_bdd_variable_set();
+        let bdd_variable_names = system
+            .standard_variables_names_and_domains()
+            .into_iter()
+            .flat_map(|(_, domain)| domain.raw_bdd_variables())
+            .chain(system.primed_variables())
+            .map(|var| bdd_variable_set.name_of(var))
+            .collect::<Vec<_>>();
+        let mut sorted_names = bdd_variable_set
+            .variables()
+            .into_iter()
+            .map(|var| bdd_variable_set.name_of(var))
+            .collect::<Vec<_>>();
+        sorted_names.sort();
+        let mut names = bdd_variable_names.clone();
+        names.sort();
+        assert_eq!(
+            names, sorted_names,
+            "every BDD variable belongs to a domain"
+        );
+
+        // the bits of each variable and its primed copy are next to each other
+        let variables = bdd_variable_set.variables();
+        for (name, domain) in system.standard_variables_names_and_domains() {
+            let bits = domain.raw_bdd_variables();
+            let first = variables.iter().position(|it| *it == bits[0]).unwrap();
+            let expected_names = if ordering.interleave_bits {
+                (1..=bits.len())
+                    .flat_map(|bit| [format!("{name}_v{bit}"), format!("{name}'_v{bit}")])
+                    .collect::<Vec<_>>()
+            } else {
+                (1..=bits.len())
+                    .map(|bit| format!("{name}_v{bit}"))
+                    .chain((1..=bits.len()).map(|bit| format!("{name}'_v{bit}")))
+                    .collect::<Vec<_>>()
+            };
+            let actual_names = variables[first..first + 2 * bits.len()]
+                .iter()
+                .map(|var| bdd_variable_set.name_of(*var))
+                .collect::<Vec<_>>();
+            assert_eq!(actual_names, expected_names, "{:?}", ordering);
+        }
+    }
+}
+
+#[test]
+fn invalid_explicit_order_is_rejected() {
+    let mut names = load_update_fns(MODEL).into_keys().collect::<Vec<_>>();
+    names.sort();
+
+    let missing = names[1..].to_vec();
+    let unknown = [names.clone(), vec!["unknown".to_string()]].concat();
+    let duplicate = [names.clone(), vec![names[0].clone()]].concat();
+    for (order, is_expected) in [
+        (
+            missing,
+            (|it| matches!(it, VariableOrderError::MissingVariable(_)))
+                as fn(&VariableOrderError) -> bool,
+        ),
+        (
+            unknown,
+            |it| matches!(it, VariableOrderError::UnknownVariable(name) if name == "unknown"),
+        ),
+        (duplicate, |it| {
+            matches!(it, VariableOrderError::DuplicateVariable(_))
+        }),
+    ] {
+        let result =
+            SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::try_from_update_fns_with_ordering(
+                load_update_fns(MODEL),
+                &VariableOrdering::new(VariableOrder::Explicit(order), false),
+            );
+        match result {
+            Err(UpdateFnError::InvalidVariableOrder(error)) => {
+                assert!(is_expected(&error), "{}", error)
+            }
+            _ => panic!("the order should be rejected"),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::BddVariableSetBuilder;
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    expression::Expression,
+    proposition::{ComparisonOperator as CmpOp, Proposition},
+    symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainEq,
+        SymbolicDomainOrd, UnaryIntegerDomain,
+    },
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::SmartSystemUpdateFn,
+};
+
+const COUNTER_MAX: u16 = 300;
+
+/// A single variable which counts from zero to `COUNTER_MAX` and then resets back to zero.
+fn counter_model() -> HashMap<String, UnprocessedVariableUpdateFn<u16>> {
+    let terms = (0..COUNTER_MAX)
+        .map(|value| {
+            let condition =
+                Expression::Terminal(Proposition::new(CmpOp::Eq, "counter".to_string(), value));
+            (value + 1, condition)
+        })
+        .collect();
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["counter".to_string()],
+        "counter".to_string(),
+        terms,
+        0,
+    );
+    HashMap::from([("counter".to_string(), update_fn)])
+}
+
+fn check_counter_reaches_all_values<DO: SymbolicDomainOrd<u16>>() {
+    let system = SmartSystemUpdateFn::<DO, u16>::from_update_fns(counter_model());
+    let domain = system.get_domain("counter").expect("known variable");
+
+    let mut values = domain.get_all_possible_values();
+    values.sort();
+    assert_eq!(values, (0..=COUNTER_MAX).collect::<Vec<_>>());
+
+    let mut reached = system.encode_one("counter", &0);
+    loop {
+        let successors = system.successors_async("counter", &reached);
+        if successors.imp(&reached).is_true() {
+            break;
+        }
+        reached = reached.or(&successors);
+    }
+    assert_eq!(reached, system.unit_vertex_set());
+    assert_eq!(
+        domain
+            .decode_collection(system.get_bdd_variable_set(), &reached)
+            .len(),
+        usize::from(COUNTER_MAX) + 1
+    );
+
+    let high_values = domain.decode_collection(
+        system.get_bdd_variable_set(),
+        &domain.encode_gt(system.get_bdd_variable_set(), &255),
+    );
+    assert_eq!(high_values.len(), usize::from(COUNTER_MAX - 255));
+}
+
+#[test]
+fn u16_counter_binary() {
+    check_counter_reaches_all_values::<BinaryIntegerDomain<u16>>();
+}
+
+#[test]
+fn u16_counter_gray() {
+    check_counter_reaches_all_values::<GrayCodeIntegerDomain<u16>>();
+}
+
+fn check_u16_round_trip<DO: SymbolicDomainOrd<u16>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &COUNTER_MAX);
+    let bdd_variable_set = builder.build();
+
+    let mut values = domain.get_all_possible_values();
+    values.sort();
+    assert_eq!(values, (0..=COUNTER_MAX).collect::<Vec<_>>());
+
+    for value in [0, 1, 254, 255, 256, 299, COUNTER_MAX] {
+        let encoded = domain.encode_one(&bdd_variable_set, &value);
+        assert_eq!(
+            domain.decode_collection(&bdd_variable_set, &encoded),
+            vec![value]
+        );
+
+        let mut smaller = domain.decode_collection(
+            &bdd_variable_set,
+            &domain.encode_lt(&bdd_variable_set, &value),
+        );
+        smaller.sort();
+        assert_eq!(smaller, (0..value).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn u16_round_trip_unary() {
+    check_u16_round_trip::<UnaryIntegerDomain<u16>>();
+}
+
+#[test]
+fn u16_round_trip_petri_net() {
+    check_u16_round_trip::<PetriNetIntegerDomain<u16>>();
+}
+
+fn check_u32_round_trip<DO: SymbolicDomainOrd<u32>>() {
+    let max_value = 70_000u32;
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &max_value);
+    let bdd_variable_set = builder.build();
+
+    for value in [0, 1, 255, 256, 65_535, 65_536, 69_999, max_value] {
+        let encoded = domain.encode_one(&bdd_variable_set, &value);
+        assert_eq!(
+            domain.decode_collection(&bdd_variable_set, &encoded),
+            vec![value]
+        );
+
+        let smaller = domain.encode_lt(&bdd_variable_set, &value);
+        assert_eq!(smaller.cardinality(), f64::from(value));
+    }
+
+    let unit = domain.unit_collection(&bdd_variable_set);
+    assert_eq!(unit.cardinality(), f64::from(max_value) + 1.0);
+}
+
+#[test]
+fn u32_binary_round_trip() {
+    check_u32_round_trip::<BinaryIntegerDomain<u32>>();
+}
+
+#[test]
+fn u32_gray_round_trip() {
+    check_u32_round_trip::<GrayCodeIntegerDomain<u32>>();
+}
+
+#[test]
+fn sbml_models_parse_into_u16() {
+    let load = || {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open("data/test-models/178-mast-cell-activation.sbml")
+                .expect("should be able to open file"),
+        ));
+        bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
+        xml
+    };
+
+    let system_u8 = SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::try_from_xml(&mut load())
+        .expect("should be able to parse");
+    let system_u16 =
+        SmartSystemUpdateFn::<BinaryIntegerDomain<u16>, u16>::try_from_xml(&mut load())
+            .expect("should be able to parse");
+
+    assert_eq!(
+        system_u8.get_system_variables(),
+        system_u16.get_system_variables()
+    );
+    assert_eq!(
+        system_u8.count_states(&system_u8.unit_vertex_set()),
+        system_u16.count_states(&system_u16.unit_vertex_set())
+    );
+    for var_name in system_u8.get_system_variables() {
+        let values_u8 = system_u8
+            .get_domain(&var_name)
+            .expect("known variable")
+            .get_all_possible_values();
+        let values_u16 = system_u16
+            .get_domain(&var_name)
+            .expect("known variable")
+            .get_all_possible_values();
+        assert_eq!(
+            values_u8.into_iter().map(u16::from).collect::<Vec<_>>(),
+            values_u16
+        );
+    }
+}